@@ -0,0 +1,391 @@
+//! Satellite antenna phase center offsets
+use crate::{
+    prelude::{Epoch, Error, ParsingError, TimeScale, SP3, SV},
+    Vector3D,
+};
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [SatelliteAntenna] describes the phase center offsets (PCO)
+/// of one satellite antenna, over its validity period.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SatelliteAntenna {
+    /// [SV] this antenna is mounted on
+    pub sv: SV,
+
+    /// Antenna type, which is the satellite block (like "BLOCK IIF")
+    pub block: String,
+
+    /// Start of validity. None means valid since the beginning of times.
+    pub valid_from: Option<Epoch>,
+
+    /// End of validity. None means valid until now.
+    pub valid_until: Option<Epoch>,
+
+    /// Phase center offsets per frequency code (like "G01"),
+    /// expressed in the satellite body frame (x, y, z), in millimeters.
+    pub pco_mm: BTreeMap<String, Vector3D>,
+}
+
+impl SatelliteAntenna {
+    /// Returns true if this [SatelliteAntenna] is valid at this [Epoch].
+    pub fn is_valid(&self, t: Epoch) -> bool {
+        let after_start = self.valid_from.map(|start| t >= start).unwrap_or(true);
+        let before_end = self.valid_until.map(|end| t <= end).unwrap_or(true);
+        after_start && before_end
+    }
+
+    /// Copies and returns [SatelliteAntenna] with this phase center offset
+    /// (body frame, in millimeters) defined for this frequency code.
+    pub fn with_pco_mm(&self, frequency: &str, pco_mm: Vector3D) -> Self {
+        let mut s = self.clone();
+        s.pco_mm.insert(frequency.to_string(), pco_mm);
+        s
+    }
+}
+
+/// [AntennaTable] gathers [SatelliteAntenna]s, either parsed
+/// from an ANTEX file or defined manually.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AntennaTable {
+    /// [SatelliteAntenna]s
+    pub antennas: Vec<SatelliteAntenna>,
+}
+
+/// Parses ANTEX VALID FROM / VALID UNTIL (GPST) fields
+fn parse_antex_epoch(content: &str) -> Result<Epoch, ParsingError> {
+    let items = content.split_ascii_whitespace().collect::<Vec<_>>();
+
+    if items.len() < 6 {
+        return Err(ParsingError::MalformedAntex(content.to_string()));
+    }
+
+    let mut values = [0_u32; 5];
+    for (value, item) in values.iter_mut().zip(items.iter()) {
+        *value = u32::from_str(item).or(Err(ParsingError::MalformedAntex(content.to_string())))?;
+    }
+
+    let seconds =
+        f64::from_str(items[5]).or(Err(ParsingError::MalformedAntex(content.to_string())))?;
+
+    Epoch::from_str(&format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02} GPST",
+        values[0], values[1], values[2], values[3], values[4], seconds as u32,
+    ))
+    .or(Err(ParsingError::MalformedAntex(content.to_string())))
+}
+
+impl AntennaTable {
+    /// Parses satellite antennas from local ANTEX file.
+    /// Receiver antennas and phase center variations are not supported and ignored.
+    pub fn from_antex_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let fd = File::open(path)?;
+        let mut reader = BufReader::new(fd);
+        Self::from_antex_reader(&mut reader)
+    }
+
+    /// Parses satellite antennas from [Read]able ANTEX content.
+    /// Receiver antennas and phase center variations are not supported and ignored.
+    pub fn from_antex_reader<R: Read>(reader: &mut BufReader<R>) -> Result<Self, Error> {
+        let mut antennas = Vec::new();
+        let mut antenna = Option::<SatelliteAntenna>::None;
+        let mut frequency = Option::<String>::None;
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.len() < 60 {
+                continue;
+            }
+
+            let (content, label) = line.split_at(60);
+            let label = label.trim();
+
+            match label {
+                "START OF ANTENNA" => {
+                    antenna = None;
+                    frequency = None;
+                },
+                "TYPE / SERIAL NO" => {
+                    // satellite antennas are identified by SV in the serial field
+                    let sv = content
+                        .get(20..40)
+                        .and_then(|serial| SV::from_str(serial.trim()).ok());
+
+                    antenna = sv.map(|sv| SatelliteAntenna {
+                        sv,
+                        block: content[..20].trim().to_string(),
+                        ..Default::default()
+                    });
+                },
+                "VALID FROM" => {
+                    if let Some(antenna) = &mut antenna {
+                        antenna.valid_from = Some(parse_antex_epoch(content)?);
+                    }
+                },
+                "VALID UNTIL" => {
+                    if let Some(antenna) = &mut antenna {
+                        antenna.valid_until = Some(parse_antex_epoch(content)?);
+                    }
+                },
+                "START OF FREQUENCY" => {
+                    frequency = content.get(3..6).map(|code| code.trim().to_string());
+                },
+                "NORTH / EAST / UP" => {
+                    if let (Some(antenna), Some(frequency)) = (&mut antenna, &frequency) {
+                        let mut pco_mm = [0.0_f64; 3];
+                        for (i, pco_mm) in pco_mm.iter_mut().enumerate() {
+                            let field = content
+                                .get(i * 10..(i + 1) * 10)
+                                .ok_or(ParsingError::MalformedAntex(content.to_string()))?;
+
+                            *pco_mm = f64::from_str(field.trim())
+                                .or(Err(ParsingError::MalformedAntex(content.to_string())))?;
+                        }
+
+                        antenna
+                            .pco_mm
+                            .insert(frequency.clone(), (pco_mm[0], pco_mm[1], pco_mm[2]));
+                    }
+                },
+                "END OF FREQUENCY" => {
+                    frequency = None;
+                },
+                "END OF ANTENNA" => {
+                    if let Some(antenna) = antenna.take() {
+                        antennas.push(antenna);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(Self { antennas })
+    }
+
+    /// Copies and returns [AntennaTable] with this [SatelliteAntenna] added.
+    pub fn with_antenna(&self, antenna: SatelliteAntenna) -> Self {
+        let mut s = self.clone();
+        s.antennas.push(antenna);
+        s
+    }
+
+    /// Returns the [SatelliteAntenna] mounted on this [SV] at this [Epoch].
+    pub fn antenna(&self, sv: SV, t: Epoch) -> Option<&SatelliteAntenna> {
+        let t = t.to_time_scale(TimeScale::GPST);
+        self.antennas
+            .iter()
+            .find(|antenna| antenna.sv == sv && antenna.is_valid(t))
+    }
+
+    /// Returns the satellite block of this [SV] at this [Epoch].
+    pub fn block(&self, sv: SV, t: Epoch) -> Option<&str> {
+        self.antenna(sv, t).map(|antenna| antenna.block.as_str())
+    }
+
+    /// Returns the phase center offset (body frame, in millimeters)
+    /// of this [SV] at this [Epoch], for this frequency code (like "G01").
+    pub fn pco_mm(&self, sv: SV, t: Epoch, frequency: &str) -> Option<Vector3D> {
+        self.antenna(sv, t)?.pco_mm.get(frequency).copied()
+    }
+}
+
+impl SP3 {
+    /// Returns the Earth fixed antenna phase center offset (in km) of this [SV]
+    /// at this [Epoch], using the nominal attitude.
+    fn satellite_pco_ecef_km(
+        &self,
+        sv: SV,
+        epoch: Epoch,
+        antennas: &AntennaTable,
+        frequency: &str,
+    ) -> Option<Vector3D> {
+        let pco_mm = antennas.pco_mm(sv, epoch, frequency)?;
+        let attitude = self.satellite_nominal_attitude(sv, epoch)?;
+        let (x, y, z) = attitude.body_to_ecef(pco_mm);
+        Some((x * 1.0E-6, y * 1.0E-6, z * 1.0E-6))
+    }
+
+    fn apply_pco_mut(&mut self, antennas: &AntennaTable, frequency: &str, sign: f64) {
+        let offsets = self
+            .data
+            .keys()
+            .filter_map(|k| {
+                let pco_km = self.satellite_pco_ecef_km(k.sv, k.epoch, antennas, frequency)?;
+                Some((k.clone(), pco_km))
+            })
+            .collect::<Vec<_>>();
+
+        for (k, pco_km) in offsets {
+            if let Some(entry) = self.data.get_mut(&k) {
+                entry.position_km.0 += sign * pco_km.0;
+                entry.position_km.1 += sign * pco_km.1;
+                entry.position_km.2 += sign * pco_km.2;
+            }
+        }
+    }
+
+    /// Converts all satellite positions from center of mass (as published
+    /// in SP3 files) to antenna phase center, for this frequency code (like "G01").
+    /// The phase center offsets are rotated to the Earth fixed frame using the
+    /// nominal yaw-steering attitude ([crate::prelude::SatelliteAttitude]).
+    /// Entries for which no offset is defined in the [AntennaTable] are left untouched.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// // empty table: nothing to convert
+    /// let apc = sp3.center_of_mass_to_antenna_phase_center(&AntennaTable::default(), "G01");
+    /// assert_eq!(apc, sp3);
+    /// ```
+    pub fn center_of_mass_to_antenna_phase_center(
+        &self,
+        antennas: &AntennaTable,
+        frequency: &str,
+    ) -> Self {
+        let mut s = self.clone();
+        s.center_of_mass_to_antenna_phase_center_mut(antennas, frequency);
+        s
+    }
+
+    /// Mutable implementation of [Self::center_of_mass_to_antenna_phase_center].
+    pub fn center_of_mass_to_antenna_phase_center_mut(
+        &mut self,
+        antennas: &AntennaTable,
+        frequency: &str,
+    ) {
+        self.apply_pco_mut(antennas, frequency, 1.0);
+    }
+
+    /// Converts all satellite positions from antenna phase center
+    /// back to center of mass, for this frequency code (like "G01").
+    /// This is the inverse of [Self::center_of_mass_to_antenna_phase_center].
+    pub fn antenna_phase_center_to_center_of_mass(
+        &self,
+        antennas: &AntennaTable,
+        frequency: &str,
+    ) -> Self {
+        let mut s = self.clone();
+        s.antenna_phase_center_to_center_of_mass_mut(antennas, frequency);
+        s
+    }
+
+    /// Mutable implementation of [Self::antenna_phase_center_to_center_of_mass].
+    pub fn antenna_phase_center_to_center_of_mass_mut(
+        &mut self,
+        antennas: &AntennaTable,
+        frequency: &str,
+    ) {
+        self.apply_pco_mut(antennas, frequency, -1.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AntennaTable;
+    use crate::prelude::{Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::{io::BufReader, str::FromStr};
+
+    const ANTEX: &str =
+        "     1.4            M                                       ANTEX VERSION / SYST
+                                                            END OF HEADER
+                                                            START OF ANTENNA
+BLOCK IIF           G01                 G063      2011-036A TYPE / SERIAL NO
+  2011    07    16    00    00    0.0000000                 VALID FROM
+   G01                                                      START OF FREQUENCY
+    394.00      0.00   1091.50                              NORTH / EAST / UP
+   NOOAZI    0.00    0.00                                   
+   G01                                                      END OF FREQUENCY
+   G02                                                      START OF FREQUENCY
+    394.00      0.00   1091.50                              NORTH / EAST / UP
+   G02                                                      END OF FREQUENCY
+                                                            END OF ANTENNA
+                                                            START OF ANTENNA
+TRM59800.00     NONE                                        TYPE / SERIAL NO
+   G01                                                      START OF FREQUENCY
+      1.28     -0.25     66.73                              NORTH / EAST / UP
+   G01                                                      END OF FREQUENCY
+                                                            END OF ANTENNA
+                                                            START OF ANTENNA
+BLOCK IIR-M         G01                 G050      2009-043A TYPE / SERIAL NO
+  2009    08    17    00    00    0.0000000                 VALID FROM
+  2011    05    08    23    59   59.9999999                 VALID UNTIL
+   G01                                                      START OF FREQUENCY
+      1.00      0.00      0.00                              NORTH / EAST / UP
+   G01                                                      END OF FREQUENCY
+                                                            END OF ANTENNA
+";
+
+    #[test]
+    fn antex_parsing() {
+        let table = AntennaTable::from_antex_reader(&mut BufReader::new(ANTEX.as_bytes())).unwrap();
+
+        // receiver antenna is discarded
+        assert_eq!(table.antennas.len(), 2);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let t = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+
+        assert_eq!(table.block(g01, t), Some("BLOCK IIF"));
+        assert_eq!(table.pco_mm(g01, t, "G01"), Some((394.0, 0.0, 1091.5)));
+        assert!(table.pco_mm(g01, t, "G05").is_none());
+
+        let t = Epoch::from_str("2010-01-01T00:00:00 GPST").unwrap();
+        assert_eq!(table.block(g01, t), Some("BLOCK IIR-M"));
+
+        let t = Epoch::from_str("2005-01-01T00:00:00 GPST").unwrap();
+        assert!(table.block(g01, t).is_none());
+    }
+
+    #[test]
+    fn phase_center_round_trip() {
+        let table = AntennaTable::from_antex_reader(&mut BufReader::new(ANTEX.as_bytes())).unwrap();
+
+        let g01 = SV::from_str("G01").unwrap();
+        let epoch = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let position_km = (13_000.0, 20_000.0, 10_000.0);
+
+        let mut sp3 = SP3::default();
+        sp3.data.insert(
+            SP3Key { sv: g01, epoch },
+            SP3Entry::from_position_km(position_km),
+        );
+
+        let apc = sp3.center_of_mass_to_antenna_phase_center(&table, "G01");
+        let apc_km = apc.data.values().next().unwrap().position_km;
+
+        // offset magnitude is preserved
+        let offset_mm = (
+            (apc_km.0 - position_km.0) * 1.0E6,
+            (apc_km.1 - position_km.1) * 1.0E6,
+            (apc_km.2 - position_km.2) * 1.0E6,
+        );
+
+        let norm_mm = (offset_mm.0.powi(2) + offset_mm.1.powi(2) + offset_mm.2.powi(2)).sqrt();
+        assert!((norm_mm - (394.0_f64.powi(2) + 1091.5_f64.powi(2)).sqrt()).abs() < 1.0E-3);
+
+        // z offset points towards Earth: APC is closer
+        let r = |p: (f64, f64, f64)| (p.0.powi(2) + p.1.powi(2) + p.2.powi(2)).sqrt();
+        assert!(r(apc_km) < r(position_km));
+
+        let com = apc.antenna_phase_center_to_center_of_mass(&table, "G01");
+        let com_km = com.data.values().next().unwrap().position_km;
+
+        assert!((com_km.0 - position_km.0).abs() < 1.0E-9);
+        assert!((com_km.1 - position_km.1).abs() < 1.0E-9);
+        assert!((com_km.2 - position_km.2).abs() < 1.0E-9);
+    }
+}
@@ -0,0 +1,135 @@
+//! Satellite attitude
+use crate::{
+    math::{cross, dot, norm, scale, sub, unit},
+    prelude::{Epoch, SP3, SV},
+    Vector3D,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+mod sun;
+mod yaw;
+
+pub(crate) use sun::sun_position_ecef_km;
+pub use yaw::{SatelliteBlock, YawAttitude};
+
+/// [SatelliteAttitude] describes the satellite body frame axes,
+/// expressed as unit vectors in the Earth fixed frame, following the IGS conventions:
+/// - z axis points towards the Earth center
+/// - y axis is the solar panels rotation axis, perpendicular to the Sun direction
+/// - x axis completes the frame, pointing towards the sunlit hemisphere
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SatelliteAttitude {
+    /// x axis unit vector
+    pub x: Vector3D,
+
+    /// y axis unit vector
+    pub y: Vector3D,
+
+    /// z axis unit vector
+    pub z: Vector3D,
+}
+
+impl SatelliteAttitude {
+    /// Nominal yaw-steering [SatelliteAttitude] from the satellite
+    /// and Sun positions (in the same frame and units).
+    /// Returns None when the Sun, Earth and satellite are aligned,
+    /// where the nominal attitude is not defined.
+    pub fn nominal(position: Vector3D, sun_position: Vector3D) -> Option<Self> {
+        let z = unit(scale(position, -1.0))?;
+        let sun = unit(sub(sun_position, position))?;
+        let y = unit(cross(z, sun))?;
+        let x = cross(y, z);
+        Some(Self { x, y, z })
+    }
+
+    /// Converts a vector expressed in the body frame to the Earth fixed frame.
+    pub fn body_to_ecef(&self, v: Vector3D) -> Vector3D {
+        (
+            self.x.0 * v.0 + self.y.0 * v.1 + self.z.0 * v.2,
+            self.x.1 * v.0 + self.y.1 * v.1 + self.z.1 * v.2,
+            self.x.2 * v.0 + self.y.2 * v.1 + self.z.2 * v.2,
+        )
+    }
+
+    /// Converts a vector expressed in the Earth fixed frame to the body frame.
+    pub fn ecef_to_body(&self, v: Vector3D) -> Vector3D {
+        (dot(self.x, v), dot(self.y, v), dot(self.z, v))
+    }
+}
+
+impl SP3 {
+    /// Returns the nominal yaw-steering [SatelliteAttitude] of this [SV]
+    /// at this [Epoch], which must exist in this record.
+    /// The Sun position is obtained from a low precision analytical model.
+    pub fn satellite_nominal_attitude(&self, sv: SV, epoch: Epoch) -> Option<SatelliteAttitude> {
+        let position_km = self.satellite_position_km(sv, epoch)?;
+        SatelliteAttitude::nominal(position_km, sun_position_ecef_km(epoch))
+    }
+
+    /// Returns the Sun elevation angle (in degrees) above the orbital plane
+    /// (β angle) of this [SV] at this [Epoch], which must exist in this record.
+    pub fn satellite_sun_beta_angle_deg(&self, sv: SV, epoch: Epoch) -> Option<f64> {
+        let position_km = self.satellite_position_km(sv, epoch)?;
+        let velocity_km_s = self.satellite_velocity_km_s(sv, epoch)?;
+
+        let velocity_km_s = crate::math::inertial_velocity(position_km, velocity_km_s);
+        let normal = unit(cross(position_km, velocity_km_s))?;
+        let sun = unit(sun_position_ecef_km(epoch))?;
+
+        Some(dot(normal, sun).clamp(-1.0, 1.0).asin().to_degrees())
+    }
+
+    /// Returns satellite position (in km) at this [Epoch], which must exist in this record.
+    fn satellite_position_km(&self, sv: SV, epoch: Epoch) -> Option<Vector3D> {
+        let position_km = self
+            .data
+            .get(&crate::SP3Key { sv, epoch })
+            .map(|v| v.position_km)?;
+
+        if norm(position_km) > 0.0 {
+            Some(position_km)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SatelliteAttitude;
+    use crate::math::{dot, norm};
+
+    #[test]
+    fn nominal_attitude() {
+        let position = (26_000.0, 0.0, 0.0);
+        let sun = (1.0E8, 1.0E8, 0.0);
+
+        let attitude = SatelliteAttitude::nominal(position, sun).unwrap();
+
+        // z points towards Earth
+        assert!((attitude.z.0 + 1.0).abs() < 1.0E-12);
+
+        // orthonormal frame
+        for axis in [attitude.x, attitude.y, attitude.z] {
+            assert!((norm(axis) - 1.0).abs() < 1.0E-12);
+        }
+        assert!(dot(attitude.x, attitude.y).abs() < 1.0E-12);
+        assert!(dot(attitude.y, attitude.z).abs() < 1.0E-12);
+
+        // x axis points towards the Sun
+        assert!(attitude.x.1 > 0.0);
+
+        // body <=> ECEF
+        let v = attitude.body_to_ecef((1.0, 2.0, 3.0));
+        let v = attitude.ecef_to_body(v);
+        assert!((v.0 - 1.0).abs() < 1.0E-12);
+        assert!((v.1 - 2.0).abs() < 1.0E-12);
+        assert!((v.2 - 3.0).abs() < 1.0E-12);
+
+        // Sun / Earth / satellite alignment
+        assert!(SatelliteAttitude::nominal(position, (1.0E8, 0.0, 0.0)).is_none());
+    }
+}
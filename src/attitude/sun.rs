@@ -0,0 +1,62 @@
+use crate::{prelude::Epoch, Vector3D};
+
+/// Astronomical unit, in km
+const AU_KM: f64 = 149_597_870.7;
+
+/// Low precision (0.01°) Sun position, in the Earth fixed frame (km),
+/// following the Astronomical Almanac analytical expressions.
+pub(crate) fn sun_position_ecef_km(t: Epoch) -> Vector3D {
+    let n = t.to_jde_tt_days() - 2_451_545.0;
+
+    let l = (280.460 + 0.985_647_4 * n).to_radians();
+    let g = (357.528 + 0.985_600_3 * n).to_radians();
+
+    let lambda = l + (1.915 * g.sin() + 0.020 * (2.0 * g).sin()).to_radians();
+    let epsilon = (23.439 - 0.000_000_4 * n).to_radians();
+    let r_km = (1.000_14 - 0.016_71 * g.cos() - 0.000_14 * (2.0 * g).cos()) * AU_KM;
+
+    // mean equator of date
+    let (x, y, z) = (
+        r_km * lambda.cos(),
+        r_km * epsilon.cos() * lambda.sin(),
+        r_km * epsilon.sin() * lambda.sin(),
+    );
+
+    // Greenwich mean sidereal time (UT1 ~ UTC)
+    let d_ut = t.to_mjd_utc_days() + 2_400_000.5 - 2_451_545.0;
+    let gmst = (280.460_618_37 + 360.985_647_366_29 * d_ut)
+        .rem_euclid(360.0)
+        .to_radians();
+
+    (
+        x * gmst.cos() + y * gmst.sin(),
+        -x * gmst.sin() + y * gmst.cos(),
+        z,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::sun_position_ecef_km;
+    use crate::prelude::Epoch;
+    use std::str::FromStr;
+
+    #[test]
+    fn sun_position() {
+        // March equinox: Sun crosses the equator
+        let t = Epoch::from_str("2020-03-20T03:50:00 UTC").unwrap();
+        let (x, y, z) = sun_position_ecef_km(t);
+        let r = (x.powi(2) + y.powi(2) + z.powi(2)).sqrt();
+
+        assert!((r / 149_597_870.7 - 0.996).abs() < 0.01);
+        assert!((z / r).asin().to_degrees().abs() < 0.1);
+
+        // June solstice at noon over Greenwich: Sun above the tropic of cancer
+        let t = Epoch::from_str("2020-06-20T12:00:00 UTC").unwrap();
+        let (x, y, z) = sun_position_ecef_km(t);
+        let r = (x.powi(2) + y.powi(2) + z.powi(2)).sqrt();
+
+        assert!(((z / r).asin().to_degrees() - 23.44).abs() < 0.1);
+        assert!(y.atan2(x).to_degrees().abs() < 2.0);
+    }
+}
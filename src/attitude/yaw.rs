@@ -0,0 +1,657 @@
+use crate::{
+    attitude::{sun_position_ecef_km, SatelliteAttitude},
+    math::{cross, dot, inertial_velocity, norm, scale, sub, unit},
+    prelude::{AntennaTable, Constellation, Duration, Epoch, ParsingError, SP3, SV},
+    Vector3D,
+};
+
+use std::{
+    f64::consts::{FRAC_PI_2, PI, TAU},
+    str::FromStr,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Mean Earth radius used in the cylindrical shadow model (km)
+const EARTH_RADIUS_KM: f64 = 6_378.137;
+
+/// [SatelliteBlock] selects the attitude law of a satellite.
+/// It is usually resolved from the antenna type of an ANTEX file,
+/// see [AntennaTable::satellite_block].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SatelliteBlock {
+    /// GPS Block IIR (and IIR-M): noon and midnight turns at 0.20°/s,
+    /// nominal yaw steering in Earth shadow.
+    GpsIIR,
+
+    /// GPS Block IIF: noon turns at 0.11°/s, constant yaw rate
+    /// throughout the Earth shadow.
+    GpsIIF,
+
+    /// GPS Block III, modelled like [SatelliteBlock::GpsIIF].
+    GpsIII,
+
+    /// Galileo In Orbit Validation: smoothed yaw turns when |β| < 2°.
+    GalileoIOV,
+
+    /// Galileo Full Operational Capability: smoothed yaw turns when |β| < 4.1°.
+    GalileoFOC,
+
+    /// BeiDou geostationary satellites, permanently in orbit normal mode.
+    BeiDouGEO,
+
+    /// BeiDou-2 IGSO and MEO: orbit normal mode when |β| < 4°.
+    BeiDou2,
+
+    /// BeiDou-3 IGSO and MEO: continuous yaw steering.
+    BeiDou3,
+
+    /// Any other satellite: nominal yaw steering.
+    #[default]
+    Other,
+}
+
+impl FromStr for SatelliteBlock {
+    type Err = ParsingError;
+
+    /// Identifies the [SatelliteBlock] from the ANTEX antenna type (like "BLOCK IIF").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let s = trimmed.to_uppercase();
+
+        if s.starts_with("BLOCK IIR") {
+            Ok(Self::GpsIIR)
+        } else if s.starts_with("BLOCK IIF") {
+            Ok(Self::GpsIIF)
+        } else if s.starts_with("BLOCK III") {
+            Ok(Self::GpsIII)
+        } else if s.starts_with("GALILEO-1") {
+            Ok(Self::GalileoIOV)
+        } else if s.starts_with("GALILEO-2") {
+            Ok(Self::GalileoFOC)
+        } else if s.starts_with("BEIDOU-2G") || s.starts_with("BEIDOU-3G") {
+            Ok(Self::BeiDouGEO)
+        } else if s.starts_with("BEIDOU-2") {
+            Ok(Self::BeiDou2)
+        } else if s.starts_with("BEIDOU-3") {
+            Ok(Self::BeiDou3)
+        } else if s.starts_with("BLOCK") || s.starts_with("GALILEO") || s.starts_with("BEIDOU") {
+            Ok(Self::Other)
+        } else {
+            Err(ParsingError::UnknownSatelliteBlock(trimmed.to_string()))
+        }
+    }
+}
+
+impl From<SV> for SatelliteBlock {
+    /// Best guess of the [SatelliteBlock] from the [SV] identity only.
+    /// GPS blocks cannot be identified this way and use the nominal attitude.
+    fn from(sv: SV) -> Self {
+        match sv.constellation {
+            Constellation::Galileo => match sv.prn {
+                11 | 12 | 19 | 20 => Self::GalileoIOV,
+                _ => Self::GalileoFOC,
+            },
+            Constellation::BeiDou => match sv.prn {
+                1..=5 | 59.. => Self::BeiDouGEO,
+                6..=18 => Self::BeiDou2,
+                _ => Self::BeiDou3,
+            },
+            _ => Self::Other,
+        }
+    }
+}
+
+impl AntennaTable {
+    /// Returns the [SatelliteBlock] of this [SV] at this [Epoch],
+    /// from the antenna type when known, otherwise from the [SV] identity.
+    pub fn satellite_block(&self, sv: SV, t: Epoch) -> SatelliteBlock {
+        self.block(sv, t)
+            .and_then(|block| SatelliteBlock::from_str(block).ok())
+            .unwrap_or(SatelliteBlock::from(sv))
+    }
+}
+
+/// [YawAttitude] of a satellite, obtained with [SatelliteBlock::yaw_attitude].
+/// The yaw angle is the rotation of the body x axis from the along track direction,
+/// about the z axis (pointing towards Earth).
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct YawAttitude {
+    /// Modelled yaw angle (in degrees)
+    pub yaw_deg: f64,
+
+    /// Nominal yaw steering angle (in degrees)
+    pub nominal_yaw_deg: f64,
+
+    /// Sun elevation above the orbital plane (in degrees)
+    pub beta_deg: f64,
+
+    /// Orbit angle measured from orbit midnight (in degrees)
+    pub orbit_angle_deg: f64,
+
+    /// True when the satellite is in Earth shadow
+    pub eclipse: bool,
+
+    /// Body frame axes
+    pub axes: SatelliteAttitude,
+}
+
+/// Wraps an angle to ]-π; π]
+fn wrap_pi(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(TAU);
+    if angle > PI {
+        angle - TAU
+    } else {
+        angle
+    }
+}
+
+/// Nominal yaw angle for given β and orbit angle μ (from midnight)
+fn nominal_yaw(beta: f64, mu: f64) -> f64 {
+    (-beta.tan()).atan2(mu.sin())
+}
+
+/// Orbit geometry with respect to the Sun
+struct OrbitGeometry {
+    beta: f64,
+    mu: f64,
+    mu_rate: f64,
+    normal: Vector3D,
+    radial: Vector3D,
+    along_track: Vector3D,
+    eclipse_half_angle: Option<f64>,
+}
+
+impl OrbitGeometry {
+    fn new(position_km: Vector3D, velocity_km_s: Vector3D, sun_km: Vector3D) -> Option<Self> {
+        let r_km = norm(position_km);
+        let radial = unit(position_km)?;
+        let normal = unit(cross(position_km, velocity_km_s))?;
+        let sun = unit(sun_km)?;
+
+        let sin_beta = dot(normal, sun);
+        let beta = sin_beta.clamp(-1.0, 1.0).asin();
+
+        let midnight = scale(unit(sub(sun, scale(normal, sin_beta)))?, -1.0);
+        let mu = dot(cross(midnight, radial), normal).atan2(dot(midnight, radial));
+
+        let cos_mu_e = (1.0 - (EARTH_RADIUS_KM / r_km).powi(2)).sqrt() / beta.cos();
+
+        Some(Self {
+            beta,
+            mu,
+            mu_rate: norm(velocity_km_s) / r_km,
+            normal,
+            radial,
+            along_track: cross(normal, radial),
+            eclipse_half_angle: if cos_mu_e < 1.0 {
+                Some(cos_mu_e.acos())
+            } else {
+                None
+            },
+        })
+    }
+
+    fn in_eclipse(&self) -> bool {
+        self.eclipse_half_angle
+            .map(|mu_e| self.mu.abs() < mu_e)
+            .unwrap_or(false)
+    }
+
+    /// Direction of the nominal yaw rotation about noon (π) or midnight (0)
+    fn turn_direction(&self, center: f64) -> f64 {
+        if self.beta == 0.0 {
+            1.0
+        } else {
+            (self.beta.tan() * center.cos()).signum()
+        }
+    }
+
+    /// Yaw turn at constant maximal rate (rad.s⁻¹) about noon or midnight,
+    /// starting when the nominal yaw rate exceeds it, and ending
+    /// when the nominal yaw is caught up.
+    fn rate_limited_turn(&self, center: f64, max_rate: f64) -> Option<f64> {
+        let tan_beta = self.beta.tan().abs();
+        let k = max_rate / self.mu_rate;
+
+        if tan_beta * k >= 1.0 {
+            return None;
+        }
+
+        let cos_mu_s = (-tan_beta
+            + (tan_beta.powi(2) + 4.0 * k.powi(2) * (1.0 + tan_beta.powi(2))).sqrt())
+            / (2.0 * k);
+        let mu_s = cos_mu_s.clamp(-1.0, 1.0).acos();
+
+        let elapsed_s = (wrap_pi(self.mu - center) + mu_s) / self.mu_rate;
+        if elapsed_s < 0.0 || elapsed_s > PI / max_rate {
+            return None;
+        }
+
+        let direction = self.turn_direction(center);
+        let yaw_start = nominal_yaw(self.beta, center - mu_s);
+
+        let nominal_progress =
+            (direction * (nominal_yaw(self.beta, self.mu) - yaw_start)).rem_euclid(TAU);
+        let progress = max_rate * elapsed_s;
+
+        if progress < nominal_progress {
+            Some(yaw_start + direction * progress)
+        } else {
+            None
+        }
+    }
+
+    /// Yaw rotation at constant rate throughout the Earth shadow,
+    /// from the nominal yaw at shadow entry to the nominal yaw at shadow exit.
+    fn shadow_crossing(&self) -> Option<f64> {
+        let mu_e = self.eclipse_half_angle?;
+        if self.mu.abs() >= mu_e {
+            return None;
+        }
+
+        let direction = self.turn_direction(0.0);
+        let yaw_entry = nominal_yaw(self.beta, -mu_e);
+        let yaw_exit = nominal_yaw(self.beta, mu_e);
+
+        let progress = (direction * (yaw_exit - yaw_entry)).rem_euclid(TAU);
+        let fraction = (self.mu + mu_e) / (2.0 * mu_e);
+
+        Some(yaw_entry + direction * progress * fraction)
+    }
+
+    /// Galileo smoothed yaw turn about noon or midnight, for |β| < max_beta
+    /// and |μ - center| < half_window, following the published Galileo satellite metadata.
+    fn smoothed_turn(&self, center: f64, max_beta: f64, half_window: f64) -> Option<f64> {
+        if self.beta.abs() >= max_beta {
+            return None;
+        }
+
+        let d = wrap_pi(self.mu - center);
+        if d.abs() >= half_window {
+            return None;
+        }
+
+        let yaw_init = nominal_yaw(self.beta, center - half_window);
+        let asymptote = if self.beta >= 0.0 {
+            -FRAC_PI_2
+        } else {
+            FRAC_PI_2
+        };
+
+        Some(
+            asymptote
+                + (yaw_init - asymptote) * (PI * (d + half_window) / (2.0 * half_window)).cos(),
+        )
+    }
+
+    /// Body frame axes for this yaw angle
+    fn axes(&self, yaw: f64) -> SatelliteAttitude {
+        let z = scale(self.radial, -1.0);
+        let (sin_yaw, cos_yaw) = yaw.sin_cos();
+        let x = (
+            cos_yaw * self.along_track.0 - sin_yaw * self.normal.0,
+            cos_yaw * self.along_track.1 - sin_yaw * self.normal.1,
+            cos_yaw * self.along_track.2 - sin_yaw * self.normal.2,
+        );
+        let y = cross(z, x);
+        SatelliteAttitude { x, y, z }
+    }
+}
+
+impl SatelliteBlock {
+    /// Maximal yaw rate (rad.s⁻¹) of rate limited turns
+    fn max_yaw_rate(&self) -> Option<f64> {
+        match self {
+            Self::GpsIIR => Some(0.20_f64.to_radians()),
+            Self::GpsIIF | Self::GpsIII => Some(0.11_f64.to_radians()),
+            _ => None,
+        }
+    }
+
+    /// Evaluates the [YawAttitude] of a satellite of this [SatelliteBlock].
+    /// ## Input
+    /// - position_km: satellite position
+    /// - velocity_km_s: satellite inertial velocity
+    /// - sun_position_km: Sun position, in the same frame
+    pub fn yaw_attitude(
+        &self,
+        position_km: Vector3D,
+        velocity_km_s: Vector3D,
+        sun_position_km: Vector3D,
+    ) -> Option<YawAttitude> {
+        let geometry = OrbitGeometry::new(position_km, velocity_km_s, sun_position_km)?;
+        let nominal = nominal_yaw(geometry.beta, geometry.mu);
+
+        let yaw = match self {
+            Self::GpsIIR => {
+                let max_rate = self.max_yaw_rate()?;
+                geometry
+                    .rate_limited_turn(PI, max_rate)
+                    .or_else(|| geometry.rate_limited_turn(0.0, max_rate))
+            },
+            Self::GpsIIF | Self::GpsIII => {
+                let max_rate = self.max_yaw_rate()?;
+                geometry
+                    .shadow_crossing()
+                    .or_else(|| geometry.rate_limited_turn(PI, max_rate))
+                    .or_else(|| geometry.rate_limited_turn(0.0, max_rate))
+            },
+            Self::GalileoIOV => {
+                let (max_beta, half_window) = (2.0_f64.to_radians(), 15.0_f64.to_radians());
+                geometry
+                    .smoothed_turn(PI, max_beta, half_window)
+                    .or_else(|| geometry.smoothed_turn(0.0, max_beta, half_window))
+            },
+            Self::GalileoFOC => {
+                let (max_beta, half_window) = (4.1_f64.to_radians(), 10.0_f64.to_radians());
+                geometry
+                    .smoothed_turn(PI, max_beta, half_window)
+                    .or_else(|| geometry.smoothed_turn(0.0, max_beta, half_window))
+            },
+            Self::BeiDouGEO => Some(0.0),
+            Self::BeiDou2 => {
+                if geometry.beta.abs() < 4.0_f64.to_radians() {
+                    Some(0.0)
+                } else {
+                    None
+                }
+            },
+            Self::BeiDou3 | Self::Other => None,
+        };
+
+        let yaw = yaw.map(wrap_pi).unwrap_or(nominal);
+
+        Some(YawAttitude {
+            yaw_deg: yaw.to_degrees(),
+            nominal_yaw_deg: nominal.to_degrees(),
+            beta_deg: geometry.beta.to_degrees(),
+            orbit_angle_deg: geometry.mu.to_degrees(),
+            eclipse: geometry.in_eclipse(),
+            axes: geometry.axes(yaw),
+        })
+    }
+}
+
+impl SP3 {
+    /// Returns the [YawAttitude] of this [SV] at this [Epoch], which must exist
+    /// in this record, following the attitude law of this [SatelliteBlock].
+    pub fn satellite_yaw_attitude(
+        &self,
+        sv: SV,
+        epoch: Epoch,
+        block: SatelliteBlock,
+    ) -> Option<YawAttitude> {
+        let position_km = self.satellite_position_km(sv, epoch)?;
+        let velocity_km_s = self.satellite_velocity_km_s(sv, epoch)?;
+
+        block.yaw_attitude(
+            position_km,
+            inertial_velocity(position_km, velocity_km_s),
+            sun_position_ecef_km(epoch),
+        )
+    }
+
+    /// Returns the [YawAttitude] of this [SV] at any [Epoch] `t`,
+    /// using Lagrangian interpolation of this order.
+    /// See [Self::satellite_position_lagrangian_interpolation] for limitations.
+    pub fn satellite_yaw_attitude_interpolation(
+        &self,
+        sv: SV,
+        t: Epoch,
+        order: usize,
+        block: SatelliteBlock,
+    ) -> Option<YawAttitude> {
+        let dt = Duration::from_milliseconds(500.0);
+
+        let position_km = self.satellite_position_lagrangian_interpolation(sv, t, order)?;
+        let p0 = self.satellite_position_lagrangian_interpolation(sv, t - dt, order)?;
+        let p1 = self.satellite_position_lagrangian_interpolation(sv, t + dt, order)?;
+
+        block.yaw_attitude(
+            position_km,
+            inertial_velocity(position_km, sub(p1, p0)),
+            sun_position_ecef_km(t),
+        )
+    }
+
+    /// Forms an [Iterator] of [YawAttitude] for each satellite and each [Epoch]
+    /// of this record. The [SatelliteBlock] is resolved from the [AntennaTable]
+    /// (see [AntennaTable::satellite_block]).
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// // without ANTEX, blocks are guessed from the SV identity
+    /// let antennas = AntennaTable::default();
+    ///
+    /// for (epoch, sv, attitude) in sp3.satellites_yaw_attitude_iter(&antennas) {
+    ///     assert!(attitude.yaw_deg.abs() <= 180.0);
+    /// }
+    /// ```
+    pub fn satellites_yaw_attitude_iter(
+        &self,
+        antennas: &AntennaTable,
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, YawAttitude)> + '_> {
+        let attitudes = self
+            .data
+            .keys()
+            .filter_map(|k| {
+                let block = antennas.satellite_block(k.sv, k.epoch);
+                let attitude = self.satellite_yaw_attitude(k.sv, k.epoch, block)?;
+                Some((k.epoch, k.sv, attitude))
+            })
+            .collect::<Vec<_>>();
+
+        Box::new(attitudes.into_iter())
+    }
+
+    /// Forms an [Iterator] of [YawAttitude] for each satellite, evenly spaced
+    /// by `sampling` over the time frame of this record, using Lagrangian interpolation
+    /// of this order. Epochs that cannot be interpolated are not reported.
+    pub fn satellites_yaw_attitude_interpolated_iter(
+        &self,
+        sampling: Duration,
+        order: usize,
+        antennas: &AntennaTable,
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, YawAttitude)> + '_> {
+        let mut attitudes = Vec::new();
+
+        let (Some(first), Some(last)) = (self.first_epoch(), self.last_epoch()) else {
+            return Box::new(attitudes.into_iter());
+        };
+
+        for sv in self.satellites_iter() {
+            let mut t = first;
+            while t <= last {
+                let block = antennas.satellite_block(sv, t);
+                if let Some(attitude) =
+                    self.satellite_yaw_attitude_interpolation(sv, t, order, block)
+                {
+                    attitudes.push((t, sv, attitude));
+                }
+                t += sampling;
+            }
+        }
+
+        Box::new(attitudes.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SatelliteBlock, YawAttitude};
+    use crate::{
+        math::dot,
+        prelude::{ParsingError, SV},
+        Vector3D,
+    };
+    use std::str::FromStr;
+
+    const GM_KM3_S2: f64 = 398_600.441_8;
+
+    /// Circular orbit state for this β and orbit angle, Sun along x axis
+    fn state(radius_km: f64, beta_deg: f64, mu_deg: f64) -> (Vector3D, Vector3D, f64) {
+        let (beta, mu) = (beta_deg.to_radians(), mu_deg.to_radians());
+        let mu_rate = (GM_KM3_S2 / radius_km.powi(3)).sqrt();
+
+        let midnight = (-beta.cos(), 0.0, beta.sin());
+        let quadrature = (0.0, -1.0, 0.0);
+
+        let position_km = (
+            radius_km * (mu.cos() * midnight.0 + mu.sin() * quadrature.0),
+            radius_km * (mu.cos() * midnight.1 + mu.sin() * quadrature.1),
+            radius_km * (mu.cos() * midnight.2 + mu.sin() * quadrature.2),
+        );
+
+        let v = radius_km * mu_rate;
+        let velocity_km_s = (
+            v * (-mu.sin() * midnight.0 + mu.cos() * quadrature.0),
+            v * (-mu.sin() * midnight.1 + mu.cos() * quadrature.1),
+            v * (-mu.sin() * midnight.2 + mu.cos() * quadrature.2),
+        );
+
+        (position_km, velocity_km_s, mu_rate)
+    }
+
+    fn attitude(block: SatelliteBlock, radius_km: f64, beta_deg: f64, mu_deg: f64) -> YawAttitude {
+        let (position_km, velocity_km_s, _) = state(radius_km, beta_deg, mu_deg);
+        block
+            .yaw_attitude(position_km, velocity_km_s, (1.5E8, 0.0, 0.0))
+            .unwrap()
+    }
+
+    /// Maximal yaw rate (°/s) over an orbit arc
+    fn max_yaw_rate(block: SatelliteBlock, radius_km: f64, beta_deg: f64, mu_deg: f64) -> f64 {
+        let (_, _, mu_rate) = state(radius_km, beta_deg, 0.0);
+        let step_deg = 0.05_f64;
+        let dt_s = step_deg.to_radians() / mu_rate;
+
+        let mut max_rate = 0.0_f64;
+        let mut mu = mu_deg - 30.0;
+        let mut past = attitude(block, radius_km, beta_deg, mu).yaw_deg;
+
+        while mu < mu_deg + 30.0 {
+            mu += step_deg;
+            let yaw = attitude(block, radius_km, beta_deg, mu).yaw_deg;
+            let dyaw = (yaw - past + 540.0).rem_euclid(360.0) - 180.0;
+            max_rate = max_rate.max(dyaw.abs() / dt_s);
+            past = yaw;
+        }
+        max_rate
+    }
+
+    #[test]
+    fn satellite_blocks() {
+        assert_eq!(
+            SatelliteBlock::from_str("BLOCK IIR-M").unwrap(),
+            SatelliteBlock::GpsIIR
+        );
+        assert_eq!(
+            SatelliteBlock::from_str("GALILEO-2").unwrap(),
+            SatelliteBlock::GalileoFOC
+        );
+        assert_eq!(
+            SatelliteBlock::from_str("BEIDOU-2G").unwrap(),
+            SatelliteBlock::BeiDouGEO
+        );
+        assert!(matches!(
+            SatelliteBlock::from_str("TRM59800.00"),
+            Err(ParsingError::UnknownSatelliteBlock(block)) if block == "TRM59800.00"
+        ));
+
+        let e11 = SV::from_str("E11").unwrap();
+        assert_eq!(SatelliteBlock::from(e11), SatelliteBlock::GalileoIOV);
+
+        let c01 = SV::from_str("C01").unwrap();
+        assert_eq!(SatelliteBlock::from(c01), SatelliteBlock::BeiDouGEO);
+    }
+
+    #[test]
+    fn nominal_yaw_steering() {
+        let gps_km = 26_560.0;
+
+        for (beta_deg, mu_deg) in [(30.0, 45.0), (-20.0, 170.0), (5.0, 300.0)] {
+            let yaw = attitude(SatelliteBlock::Other, gps_km, beta_deg, mu_deg);
+            assert_eq!(yaw.yaw_deg, yaw.nominal_yaw_deg);
+            assert!((yaw.beta_deg - beta_deg).abs() < 1.0E-9);
+
+            // consistent with the geometrical definition
+            let (position_km, _, _) = state(gps_km, beta_deg, mu_deg);
+            let nominal =
+                crate::attitude::SatelliteAttitude::nominal(position_km, (1.5E8, 0.0, 0.0))
+                    .unwrap();
+
+            assert!(dot(nominal.x, yaw.axes.x) > 1.0 - 1.0E-6);
+            assert!(dot(nominal.y, yaw.axes.y) > 1.0 - 1.0E-6);
+            assert!(dot(nominal.z, yaw.axes.z) > 1.0 - 1.0E-9);
+        }
+
+        // nominal yaw rate is unbounded at low β
+        assert!(max_yaw_rate(SatelliteBlock::Other, gps_km, 0.5, 180.0) > 0.5);
+    }
+
+    #[test]
+    fn gps_yaw_turns() {
+        let gps_km = 26_560.0;
+
+        // noon turns are rate limited
+        let rate = max_yaw_rate(SatelliteBlock::GpsIIR, gps_km, 0.5, 180.0);
+        assert!(rate < 0.2 + 1.0E-6);
+
+        let yaw = attitude(SatelliteBlock::GpsIIR, gps_km, 0.5, 180.0);
+        assert!((yaw.yaw_deg - yaw.nominal_yaw_deg).abs() > 1.0);
+
+        let rate = max_yaw_rate(SatelliteBlock::GpsIIF, gps_km, 0.5, 180.0);
+        assert!(rate < 0.11 + 1.0E-6);
+
+        // nominal attitude away from noon and midnight
+        let yaw = attitude(SatelliteBlock::GpsIIF, gps_km, 0.5, 90.0);
+        assert_eq!(yaw.yaw_deg, yaw.nominal_yaw_deg);
+
+        // shadow crossing at constant rate
+        let yaw = attitude(SatelliteBlock::GpsIIF, gps_km, 2.0, 0.0);
+        assert!(yaw.eclipse);
+
+        let y0 = attitude(SatelliteBlock::GpsIIF, gps_km, 2.0, -5.0).yaw_deg;
+        let y1 = attitude(SatelliteBlock::GpsIIF, gps_km, 2.0, 0.0).yaw_deg;
+        let y2 = attitude(SatelliteBlock::GpsIIF, gps_km, 2.0, 5.0).yaw_deg;
+        assert!(((y1 - y0) - (y2 - y1)).abs() < 1.0E-6);
+
+        let yaw = attitude(SatelliteBlock::GpsIIF, gps_km, 30.0, 0.0);
+        assert!(!yaw.eclipse);
+    }
+
+    #[test]
+    fn galileo_and_beidou_yaw() {
+        let galileo_km = 29_600.0;
+
+        // smoothed turn: continuous at window boundaries
+        for mu_deg in [170.01, 189.99, 180.0] {
+            let yaw = attitude(SatelliteBlock::GalileoFOC, galileo_km, 1.0, mu_deg);
+            let dyaw = (yaw.yaw_deg - yaw.nominal_yaw_deg + 540.0).rem_euclid(360.0) - 180.0;
+            assert!(dyaw.abs() < 0.1);
+        }
+
+        assert!(max_yaw_rate(SatelliteBlock::GalileoFOC, galileo_km, 1.0, 180.0) < 0.1);
+
+        // no smoothing at high β
+        let yaw = attitude(SatelliteBlock::GalileoIOV, galileo_km, 3.0, 178.0);
+        assert_eq!(yaw.yaw_deg, yaw.nominal_yaw_deg);
+
+        // orbit normal mode
+        let yaw = attitude(SatelliteBlock::BeiDou2, 27_900.0, 2.0, 120.0);
+        assert_eq!(yaw.yaw_deg, 0.0);
+
+        let yaw = attitude(SatelliteBlock::BeiDou2, 27_900.0, 10.0, 120.0);
+        assert_eq!(yaw.yaw_deg, yaw.nominal_yaw_deg);
+
+        let yaw = attitude(SatelliteBlock::BeiDouGEO, 42_164.0, 20.0, 120.0);
+        assert_eq!(yaw.yaw_deg, 0.0);
+    }
+}
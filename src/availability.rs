@@ -0,0 +1,387 @@
+//! Data availability and completeness analysis
+use crate::{
+    prelude::{Duration, Epoch, SP3, SV},
+    validation::HeaderInconsistency,
+};
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [DataGap] describes a period of missing epochs
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DataGap {
+    /// First missing [Epoch]
+    pub start: Epoch,
+
+    /// Last missing [Epoch]
+    pub end: Epoch,
+
+    /// Missing period, as [Duration]
+    pub duration: Duration,
+
+    /// Number of missing epochs
+    pub missing_epochs: usize,
+}
+
+/// [DataArc] describes a contiguous period of data
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DataArc {
+    /// First [Epoch] of this arc
+    pub start: Epoch,
+
+    /// Last [Epoch] of this arc
+    pub end: Epoch,
+
+    /// Number of epochs in this arc
+    pub epochs: usize,
+}
+
+impl DataArc {
+    /// Returns the [Duration] of this [DataArc]
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// [SatelliteAvailability] report of one [SV]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SatelliteAvailability {
+    /// Number of epochs expected over the file time frame
+    pub expected_epochs: usize,
+
+    /// Number of epochs actually present
+    pub epochs: usize,
+
+    /// Contiguous [DataArc]s
+    pub arcs: Vec<DataArc>,
+
+    /// [DataGap]s, including missing epochs at the beginning and end of the time frame
+    pub gaps: Vec<DataGap>,
+
+    /// Number of epochs with predicted orbit
+    pub predicted_epochs: usize,
+
+    /// Number of epochs under maneuver
+    pub maneuver_epochs: usize,
+
+    /// Number of epochs without clock offset
+    pub missing_clocks: usize,
+}
+
+impl SatelliteAvailability {
+    /// Returns the ratio of present over expected epochs (0 to 1).
+    pub fn completeness(&self) -> f64 {
+        if self.expected_epochs == 0 {
+            0.0
+        } else {
+            self.epochs as f64 / self.expected_epochs as f64
+        }
+    }
+
+    /// Returns the fraction of present epochs with predicted orbit (0 to 1).
+    pub fn predicted_fraction(&self) -> f64 {
+        self.fraction(self.predicted_epochs)
+    }
+
+    /// Returns the fraction of present epochs under maneuver (0 to 1).
+    pub fn maneuver_fraction(&self) -> f64 {
+        self.fraction(self.maneuver_epochs)
+    }
+
+    /// Returns the fraction of present epochs without clock offset (0 to 1).
+    pub fn missing_clock_fraction(&self) -> f64 {
+        self.fraction(self.missing_clocks)
+    }
+
+    fn fraction(&self, count: usize) -> f64 {
+        if self.epochs == 0 {
+            0.0
+        } else {
+            count as f64 / self.epochs as f64
+        }
+    }
+}
+
+/// [AvailabilityReport], obtained with [SP3::availability_report].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AvailabilityReport {
+    /// First [Epoch] of the record
+    pub first_epoch: Option<Epoch>,
+
+    /// Last [Epoch] of the record
+    pub last_epoch: Option<Epoch>,
+
+    /// Sampling period the analysis relied on
+    pub sampling_period: Duration,
+
+    /// Number of epochs expected over the time frame
+    pub expected_epochs: usize,
+
+    /// Number of epochs present in the record
+    pub epochs: usize,
+
+    /// [SatelliteAvailability] per [SV] found in the record
+    pub satellites: BTreeMap<SV, SatelliteAvailability>,
+
+    /// [HeaderInconsistency]s
+    pub inconsistencies: Vec<HeaderInconsistency>,
+}
+
+impl AvailabilityReport {
+    /// Returns true if the header is consistent with the record
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Returns the ratio of present over expected epochs (0 to 1), for all satellites.
+    pub fn completeness(&self) -> f64 {
+        let (epochs, expected) = self
+            .satellites
+            .values()
+            .fold((0, 0), |(epochs, expected), sv| {
+                (epochs + sv.epochs, expected + sv.expected_epochs)
+            });
+
+        if expected == 0 {
+            0.0
+        } else {
+            epochs as f64 / expected as f64
+        }
+    }
+}
+
+/// Number of sampling periods between two [Epoch]s
+pub(crate) fn periods(start: Epoch, end: Epoch, sampling_period: Duration) -> usize {
+    ((end - start).to_seconds() / sampling_period.to_seconds()).round() as usize
+}
+
+/// Returns [DataGap] between these [Epoch]s (both missing)
+pub(crate) fn data_gap(start: Epoch, end: Epoch, sampling_period: Duration) -> DataGap {
+    DataGap {
+        start,
+        end,
+        duration: end - start + sampling_period,
+        missing_epochs: periods(start, end, sampling_period) + 1,
+    }
+}
+
+impl SP3 {
+    /// Analyzes the data availability and completeness of this [SP3],
+    /// per [SV] and for the whole file, and verifies the [crate::prelude::Header]
+    /// consistency. The expected epochs are defined by the sampling period
+    /// (from the header, or from the record when not defined), over the time frame
+    /// of the record.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let report = sp3.availability_report();
+    ///
+    /// for inconsistency in report.inconsistencies.iter() {
+    ///     println!("header inconsistency: {:?}", inconsistency);
+    /// }
+    ///
+    /// for (sv, availability) in report.satellites.iter() {
+    ///     for gap in availability.gaps.iter() {
+    ///         println!("{}: missing {} from {}", sv, gap.duration, gap.start);
+    ///     }
+    /// }
+    /// ```
+    pub fn availability_report(&self) -> AvailabilityReport {
+        let mut report = AvailabilityReport::default();
+        let sampling_period = self.nominal_sampling_period();
+
+        report.sampling_period = sampling_period;
+        report.epochs = self.total_epochs();
+        report.first_epoch = self.first_epoch();
+        report.last_epoch = self.last_epoch();
+
+        let (Some(first), Some(last)) = (report.first_epoch, report.last_epoch) else {
+            return report;
+        };
+
+        report.expected_epochs = if sampling_period > Duration::ZERO {
+            periods(first, last, sampling_period) + 1
+        } else {
+            report.epochs
+        };
+
+        let mut per_sv = BTreeMap::<SV, Vec<Epoch>>::new();
+
+        for (k, v) in self.data.iter() {
+            per_sv.entry(k.sv).or_default().push(k.epoch);
+
+            let availability = report.satellites.entry(k.sv).or_default();
+
+            availability.epochs += 1;
+
+            if v.predicted_orbit {
+                availability.predicted_epochs += 1;
+            }
+            if v.maneuver {
+                availability.maneuver_epochs += 1;
+            }
+            if v.clock_us.is_none() {
+                availability.missing_clocks += 1;
+            }
+        }
+
+        for (sv, epochs) in per_sv.iter() {
+            let Some(availability) = report.satellites.get_mut(sv) else {
+                continue;
+            };
+
+            availability.expected_epochs = report.expected_epochs;
+
+            let mut arc = DataArc {
+                start: epochs[0],
+                end: epochs[0],
+                epochs: 1,
+            };
+
+            if sampling_period > Duration::ZERO && epochs[0] > first {
+                availability.gaps.push(data_gap(
+                    first,
+                    epochs[0] - sampling_period,
+                    sampling_period,
+                ));
+            }
+
+            for window in epochs.windows(2) {
+                let (past, now) = (window[0], window[1]);
+
+                if sampling_period > Duration::ZERO && periods(past, now, sampling_period) > 1 {
+                    availability.arcs.push(arc);
+                    availability.gaps.push(data_gap(
+                        past + sampling_period,
+                        now - sampling_period,
+                        sampling_period,
+                    ));
+
+                    arc = DataArc {
+                        start: now,
+                        end: now,
+                        epochs: 1,
+                    };
+                } else {
+                    arc.end = now;
+                    arc.epochs += 1;
+                }
+            }
+
+            availability.arcs.push(arc);
+
+            if sampling_period > Duration::ZERO && arc.end < last {
+                availability
+                    .gaps
+                    .push(data_gap(arc.end + sampling_period, last, sampling_period));
+            }
+        }
+
+        report.inconsistencies = self.validate();
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{
+        Constellation, Duration, Epoch, HeaderInconsistency, SP3Entry, SP3Key, TimeScale, SP3, SV,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn availability_report() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+        sp3.header.num_epochs = 12;
+        sp3.header.constellation = Constellation::GPS;
+        sp3.header.satellites = vec![g01, g03];
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.week = 2111;
+        sp3.header.week_nanos = 345_600_000_000_000;
+        sp3.header.mjd = 59025;
+
+        for i in 0..10 {
+            let epoch = t0 + dt * i as f64;
+
+            let mut entry = SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_clock_offset_us(1.0);
+
+            if i >= 8 {
+                entry.predicted_orbit = true;
+            }
+
+            sp3.data.insert(SP3Key { sv: g01, epoch }, entry);
+
+            // G02: gap [3; 5] and missing last epoch
+            if !(3..=5).contains(&i) && i < 9 {
+                let entry = SP3Entry::from_position_km((0.0, 0.0, 0.0));
+                sp3.data.insert(SP3Key { sv: g02, epoch }, entry);
+            }
+        }
+
+        let report = sp3.availability_report();
+
+        assert_eq!(report.epochs, 10);
+        assert_eq!(report.expected_epochs, 10);
+
+        let g01_report = report.satellites.get(&g01).unwrap();
+        assert_eq!(g01_report.completeness(), 1.0);
+        assert_eq!(g01_report.arcs.len(), 1);
+        assert!(g01_report.gaps.is_empty());
+        assert_eq!(g01_report.predicted_fraction(), 0.2);
+        assert_eq!(g01_report.missing_clocks, 0);
+
+        let g02_report = report.satellites.get(&g02).unwrap();
+        assert_eq!(g02_report.epochs, 6);
+        assert_eq!(g02_report.missing_clocks, 6);
+        assert_eq!(g02_report.arcs.len(), 2);
+        assert_eq!(g02_report.arcs[0].epochs, 3);
+        assert_eq!(g02_report.arcs[1].duration(), dt * 2.0);
+        assert_eq!(g02_report.gaps.len(), 2);
+
+        let gap = g02_report.gaps[0];
+        assert_eq!(gap.start, t0 + dt * 3.0);
+        assert_eq!(gap.end, t0 + dt * 5.0);
+        assert_eq!(gap.missing_epochs, 3);
+        assert_eq!(gap.duration, dt * 3.0);
+
+        let gap = g02_report.gaps[1];
+        assert_eq!(gap.start, t0 + dt * 9.0);
+        assert_eq!(gap.missing_epochs, 1);
+
+        assert!((report.completeness() - 16.0 / 20.0).abs() < 1.0E-9);
+
+        assert!(!report.is_consistent());
+        assert_eq!(report.inconsistencies.len(), 3);
+
+        assert!(report
+            .inconsistencies
+            .contains(&HeaderInconsistency::NumberOfEpochs {
+                header: 12,
+                record: 10
+            }));
+        assert!(report
+            .inconsistencies
+            .contains(&HeaderInconsistency::MissingSatellite(g03)));
+        assert!(report
+            .inconsistencies
+            .contains(&HeaderInconsistency::UndeclaredSatellite(g02)));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    prelude::{Epoch, SV},
+    Vector3D,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// PZ-90 gravitational constant (km³.s⁻²)
+const GM_KM3_S2: f64 = 398_600.441_8;
+
+/// PZ-90 semi major axis (km)
+const EARTH_RADIUS_KM: f64 = 6_378.136;
+
+/// Second zonal harmonic of the geopotential
+const J2: f64 = 1_082_625.75E-9;
+
+/// PZ-90 Earth rotation rate (rad.s⁻¹)
+const EARTH_ROTATION_RATE_RAD_S: f64 = 7.292_115E-5;
+
+/// Maximal integration step, in seconds
+const INTEGRATION_STEP_S: f64 = 60.0;
+
+/// [GlonassEphemeris] is the GLONASS broadcast state vector,
+/// expressed in the PZ-90 Earth fixed frame, as defined in the GLONASS ICD.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GlonassEphemeris {
+    /// [SV] broadcasting this message
+    pub sv: SV,
+
+    /// Reference [Epoch] (tb) of the state vector
+    pub toe: Epoch,
+
+    /// Position at reference epoch (km)
+    pub position_km: Vector3D,
+
+    /// Velocity at reference epoch (km.s⁻¹)
+    pub velocity_km_s: Vector3D,
+
+    /// Luni-solar acceleration (km.s⁻²)
+    pub acceleration_km_s2: Vector3D,
+
+    /// Clock bias τn (s)
+    pub tau_n: f64,
+
+    /// Relative frequency bias γn
+    pub gamma_n: f64,
+}
+
+type State = [f64; 6];
+
+impl GlonassEphemeris {
+    /// Equations of motion in the Earth fixed frame.
+    fn derivatives(&self, s: &State) -> State {
+        let (x, y, z) = (s[0], s[1], s[2]);
+        let (vx, vy, vz) = (s[3], s[4], s[5]);
+
+        let r2 = x.powi(2) + y.powi(2) + z.powi(2);
+        let r = r2.sqrt();
+
+        let mu = GM_KM3_S2 / r.powi(3);
+        let j2 = 1.5 * J2 * GM_KM3_S2 * EARTH_RADIUS_KM.powi(2) / r.powi(5);
+        let z2 = 5.0 * z.powi(2) / r2;
+        let w2 = EARTH_ROTATION_RATE_RAD_S.powi(2);
+        let w = EARTH_ROTATION_RATE_RAD_S;
+
+        let (ax, ay, az) = self.acceleration_km_s2;
+
+        [
+            vx,
+            vy,
+            vz,
+            -mu * x - j2 * x * (1.0 - z2) + w2 * x + 2.0 * w * vy + ax,
+            -mu * y - j2 * y * (1.0 - z2) + w2 * y - 2.0 * w * vx + ay,
+            -mu * z - j2 * z * (3.0 - z2) + az,
+        ]
+    }
+
+    /// Runge-Kutta (4th order) step
+    fn rk4_step(&self, s: &State, h: f64) -> State {
+        let add = |a: &State, b: &State, k: f64| {
+            let mut out = *a;
+            for (out, b) in out.iter_mut().zip(b) {
+                *out += b * k;
+            }
+            out
+        };
+
+        let k1 = self.derivatives(s);
+        let k2 = self.derivatives(&add(s, &k1, h / 2.0));
+        let k3 = self.derivatives(&add(s, &k2, h / 2.0));
+        let k4 = self.derivatives(&add(s, &k3, h));
+
+        let mut out = *s;
+        for (i, out) in out.iter_mut().enumerate() {
+            *out += h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+        }
+        out
+    }
+
+    /// Integrates the state vector to desired [Epoch], returns
+    /// the Earth fixed position (km) and velocity (km.s⁻¹).
+    pub fn position_velocity_km(&self, t: Epoch) -> (Vector3D, Vector3D) {
+        let mut state = [
+            self.position_km.0,
+            self.position_km.1,
+            self.position_km.2,
+            self.velocity_km_s.0,
+            self.velocity_km_s.1,
+            self.velocity_km_s.2,
+        ];
+
+        let mut remaining = (t - self.toe).to_seconds();
+
+        while remaining.abs() > 1.0E-9 {
+            let h = remaining.signum() * remaining.abs().min(INTEGRATION_STEP_S);
+            state = self.rk4_step(&state, h);
+            remaining -= h;
+        }
+
+        (
+            (state[0], state[1], state[2]),
+            (state[3], state[4], state[5]),
+        )
+    }
+
+    /// Evaluates Earth fixed position (in km) at desired [Epoch].
+    pub fn position_km(&self, t: Epoch) -> Vector3D {
+        self.position_velocity_km(t).0
+    }
+
+    /// Evaluates Earth fixed velocity (in km.s⁻¹) at desired [Epoch].
+    pub fn velocity_km_s(&self, t: Epoch) -> Vector3D {
+        self.position_velocity_km(t).1
+    }
+
+    /// Evaluates satellite clock offset (in seconds) at desired [Epoch].
+    pub fn clock_offset_s(&self, t: Epoch) -> f64 {
+        -self.tau_n + self.gamma_n * (t - self.toe).to_seconds()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GlonassEphemeris;
+    use crate::prelude::{Epoch, SV};
+    use hifitime::Duration;
+    use std::str::FromStr;
+
+    #[test]
+    fn glonass_integration() {
+        let toe = Epoch::from_str("2020-06-25T00:15:00 UTC").unwrap();
+
+        let eph = GlonassEphemeris {
+            sv: SV::from_str("R01").unwrap(),
+            toe,
+            position_km: (7_003.008_789, -12_206.626_953, 21_280.765_625),
+            velocity_km_s: (0.783_254_623_4, 2.804_101_943_97, 1.352_424_621_58),
+            acceleration_km_s2: (0.0, 1.7E-9, -5.41E-9),
+            tau_n: 1.0E-5,
+            gamma_n: 1.0E-12,
+        };
+
+        // null propagation
+        assert_eq!(eph.position_km(toe), eph.position_km);
+
+        // forward then backward
+        let t1 = toe + Duration::from_seconds(900.0);
+        let (p1, v1) = eph.position_velocity_km(t1);
+
+        let back = GlonassEphemeris {
+            toe: t1,
+            position_km: p1,
+            velocity_km_s: v1,
+            ..eph
+        };
+
+        let p0 = back.position_km(toe);
+        assert!((p0.0 - eph.position_km.0).abs() < 1.0E-6);
+        assert!((p0.1 - eph.position_km.1).abs() < 1.0E-6);
+        assert!((p0.2 - eph.position_km.2).abs() < 1.0E-6);
+
+        // orbit radius remains consistent with GLONASS altitude
+        let r = (p1.0.powi(2) + p1.1.powi(2) + p1.2.powi(2)).sqrt();
+        assert!(r > 25_000.0 && r < 25_800.0);
+
+        assert!((eph.clock_offset_s(t1) + 1.0E-5 - 9.0E-10).abs() < 1.0E-15);
+    }
+}
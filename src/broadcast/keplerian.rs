@@ -0,0 +1,238 @@
+use crate::{
+    prelude::{Constellation, Epoch, SV},
+    Vector3D,
+};
+
+use hifitime::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Speed of light in m.s⁻¹
+const SPEED_OF_LIGHT_M_S: f64 = 299_792_458.0;
+
+/// [KeplerianEphemeris] is the broadcast orbit and clock parameter set,
+/// as defined in the GPS, Galileo, BeiDou and QZSS interface control documents.
+/// Angles are expressed in radians (and radians per second),
+/// distances in meters and durations in seconds.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeplerianEphemeris {
+    /// [SV] broadcasting this message
+    pub sv: SV,
+
+    /// Time of clock, expressed in the [SV] timescale
+    pub toc: Epoch,
+
+    /// Clock bias (s)
+    pub af0: f64,
+
+    /// Clock drift (s.s⁻¹)
+    pub af1: f64,
+
+    /// Clock drift rate (s.s⁻²)
+    pub af2: f64,
+
+    /// Time of ephemeris, expressed in the [SV] timescale
+    pub toe: Epoch,
+
+    /// Square root of semi major axis (m^½)
+    pub sqrt_a: f64,
+
+    /// Eccentricity
+    pub e: f64,
+
+    /// Inclination angle at reference time
+    pub i0: f64,
+
+    /// Rate of inclination angle
+    pub idot: f64,
+
+    /// Longitude of ascending node of orbit plane at weekly epoch
+    pub omega0: f64,
+
+    /// Rate of right ascension
+    pub omega_dot: f64,
+
+    /// Argument of perigee
+    pub omega: f64,
+
+    /// Mean anomaly at reference time
+    pub m0: f64,
+
+    /// Mean motion difference from computed value
+    pub delta_n: f64,
+
+    /// Amplitude of the cosine harmonic correction term to the argument of latitude
+    pub cuc: f64,
+
+    /// Amplitude of the sine harmonic correction term to the argument of latitude
+    pub cus: f64,
+
+    /// Amplitude of the cosine harmonic correction term to the orbit radius (m)
+    pub crc: f64,
+
+    /// Amplitude of the sine harmonic correction term to the orbit radius (m)
+    pub crs: f64,
+
+    /// Amplitude of the cosine harmonic correction term to the angle of inclination
+    pub cic: f64,
+
+    /// Amplitude of the sine harmonic correction term to the angle of inclination
+    pub cis: f64,
+}
+
+impl KeplerianEphemeris {
+    /// Returns (gravitational constant (m³.s⁻²), Earth rotation rate (rad.s⁻¹))
+    /// as defined by the constellation ICD.
+    fn constants(&self) -> (f64, f64) {
+        match self.sv.constellation {
+            Constellation::Galileo => (3.986_004_418E14, 7.292_115_146_7E-5),
+            Constellation::BeiDou => (3.986_004_418E14, 7.292_115E-5),
+            _ => (3.986_005E14, 7.292_115_146_7E-5),
+        }
+    }
+
+    /// Returns true for BeiDou geostationary satellites
+    fn is_beidou_geo(&self) -> bool {
+        self.sv.constellation == Constellation::BeiDou && (self.sv.prn <= 5 || self.sv.prn >= 59)
+    }
+
+    /// Seconds of week of the time of ephemeris, in the [SV] timescale.
+    fn toe_seconds_of_week(&self) -> f64 {
+        let toe = match self.sv.constellation.timescale() {
+            Some(timescale) => self.toe.to_time_scale(timescale),
+            None => self.toe,
+        };
+
+        let (_, nanos) = toe.to_time_of_week();
+        nanos as f64 * 1.0E-9
+    }
+
+    /// Solves Kepler equation, returns eccentric anomaly.
+    fn eccentric_anomaly(&self, tk: f64) -> f64 {
+        let (gm, _) = self.constants();
+        let a = self.sqrt_a.powi(2);
+        let n = (gm / a.powi(3)).sqrt() + self.delta_n;
+        let m = self.m0 + n * tk;
+
+        let mut e_k = m;
+        for _ in 0..20 {
+            let next = m + self.e * e_k.sin();
+            if (next - e_k).abs() < 1.0E-14 {
+                return next;
+            }
+            e_k = next;
+        }
+        e_k
+    }
+
+    /// Evaluates ECEF position (in km) at desired [Epoch].
+    pub fn position_km(&self, t: Epoch) -> Vector3D {
+        let (_, omega_e) = self.constants();
+
+        let tk = (t - self.toe).to_seconds();
+        let a = self.sqrt_a.powi(2);
+
+        let e_k = self.eccentric_anomaly(tk);
+        let nu = ((1.0 - self.e.powi(2)).sqrt() * e_k.sin()).atan2(e_k.cos() - self.e);
+
+        let phi = nu + self.omega;
+        let (sin2phi, cos2phi) = (2.0 * phi).sin_cos();
+
+        let u = phi + self.cus * sin2phi + self.cuc * cos2phi;
+        let r = a * (1.0 - self.e * e_k.cos()) + self.crs * sin2phi + self.crc * cos2phi;
+        let i = self.i0 + self.idot * tk + self.cis * sin2phi + self.cic * cos2phi;
+
+        let (x_p, y_p) = (r * u.cos(), r * u.sin());
+        let toe_sow = self.toe_seconds_of_week();
+
+        let (x, y, z) = if self.is_beidou_geo() {
+            let omega = self.omega0 + self.omega_dot * tk - omega_e * toe_sow;
+            let (xg, yg, zg) = (
+                x_p * omega.cos() - y_p * i.cos() * omega.sin(),
+                x_p * omega.sin() + y_p * i.cos() * omega.cos(),
+                y_p * i.sin(),
+            );
+
+            // Rx(-5°) then Rz(omega_e.tk)
+            let (sin5, cos5) = (-5.0_f64).to_radians().sin_cos();
+            let (sin_rz, cos_rz) = (omega_e * tk).sin_cos();
+
+            let (x1, y1, z1) = (xg, yg * cos5 + zg * sin5, -yg * sin5 + zg * cos5);
+
+            (x1 * cos_rz + y1 * sin_rz, -x1 * sin_rz + y1 * cos_rz, z1)
+        } else {
+            let omega = self.omega0 + (self.omega_dot - omega_e) * tk - omega_e * toe_sow;
+            (
+                x_p * omega.cos() - y_p * i.cos() * omega.sin(),
+                x_p * omega.sin() + y_p * i.cos() * omega.cos(),
+                y_p * i.sin(),
+            )
+        };
+
+        (x * 1.0E-3, y * 1.0E-3, z * 1.0E-3)
+    }
+
+    /// Evaluates ECEF velocity (in km.s⁻¹) at desired [Epoch],
+    /// by differentiation of the position.
+    pub fn velocity_km_s(&self, t: Epoch) -> Vector3D {
+        let dt = Duration::from_milliseconds(500.0);
+        let (p0, p1) = (self.position_km(t - dt), self.position_km(t + dt));
+        (p1.0 - p0.0, p1.1 - p0.1, p1.2 - p0.2)
+    }
+
+    /// Evaluates satellite clock offset (in seconds) at desired [Epoch],
+    /// including the relativistic correction.
+    pub fn clock_offset_s(&self, t: Epoch) -> f64 {
+        let (gm, _) = self.constants();
+
+        let dt = (t - self.toc).to_seconds();
+        let tk = (t - self.toe).to_seconds();
+
+        let f = -2.0 * gm.sqrt() / SPEED_OF_LIGHT_M_S.powi(2);
+        let relativistic = f * self.e * self.sqrt_a * self.eccentric_anomaly(tk).sin();
+
+        self.af0 + self.af1 * dt + self.af2 * dt.powi(2) + relativistic
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::KeplerianEphemeris;
+    use crate::prelude::{Epoch, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn keplerian_circular_orbit() {
+        let toe = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let sqrt_a = 5153.6_f64;
+
+        let eph = KeplerianEphemeris {
+            sv: SV::from_str("G01").unwrap(),
+            toe,
+            toc: toe,
+            sqrt_a,
+            i0: 0.96,
+            af0: 1.0E-4,
+            af1: 1.0E-11,
+            ..Default::default()
+        };
+
+        for dt_s in [0.0, 900.0, 3600.0, 7200.0] {
+            let t = toe + hifitime::Duration::from_seconds(dt_s);
+
+            let (x, y, z) = eph.position_km(t);
+            let r_km = (x.powi(2) + y.powi(2) + z.powi(2)).sqrt();
+            assert!((r_km - sqrt_a.powi(2) * 1.0E-3).abs() < 1.0E-6);
+
+            let clock_s = eph.clock_offset_s(t);
+            assert!((clock_s - 1.0E-4 - 1.0E-11 * dt_s).abs() < 1.0E-15);
+        }
+
+        // ECEF velocity of a MEO satellite
+        let v = eph.velocity_km_s(toe);
+        let speed = (v.0.powi(2) + v.1.powi(2) + v.2.powi(2)).sqrt();
+        assert!(speed > 2.0 && speed < 4.5);
+    }
+}
@@ -0,0 +1,106 @@
+//! Broadcast ephemeris models
+use crate::{
+    prelude::{Constellation, Epoch, SV},
+    Vector3D,
+};
+
+use hifitime::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+mod glonass;
+mod keplerian;
+
+pub use glonass::GlonassEphemeris;
+pub use keplerian::KeplerianEphemeris;
+
+/// [BroadcastEphemeris] describes the orbit and clock of one satellite,
+/// as broadcast in the navigation message.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BroadcastEphemeris {
+    /// GPS, Galileo, BeiDou and QZSS [KeplerianEphemeris]
+    Keplerian(KeplerianEphemeris),
+
+    /// GLONASS state vector
+    Glonass(GlonassEphemeris),
+}
+
+impl From<KeplerianEphemeris> for BroadcastEphemeris {
+    fn from(ephemeris: KeplerianEphemeris) -> Self {
+        Self::Keplerian(ephemeris)
+    }
+}
+
+impl From<GlonassEphemeris> for BroadcastEphemeris {
+    fn from(ephemeris: GlonassEphemeris) -> Self {
+        Self::Glonass(ephemeris)
+    }
+}
+
+impl BroadcastEphemeris {
+    /// [SV] broadcasting this message
+    pub fn sv(&self) -> SV {
+        match self {
+            Self::Keplerian(eph) => eph.sv,
+            Self::Glonass(eph) => eph.sv,
+        }
+    }
+
+    /// Reference [Epoch] of this message
+    pub fn toe(&self) -> Epoch {
+        match self {
+            Self::Keplerian(eph) => eph.toe,
+            Self::Glonass(eph) => eph.toe,
+        }
+    }
+
+    /// Maximal time offset to the reference [Epoch] for which
+    /// this message should be used.
+    pub fn validity(&self) -> Duration {
+        match self.sv().constellation {
+            Constellation::Glonass => Duration::from_seconds(15.0 * 60.0),
+            Constellation::BeiDou => Duration::from_seconds(3600.0),
+            _ => Duration::from_seconds(2.0 * 3600.0),
+        }
+    }
+
+    /// Returns true if this message may be used at [Epoch].
+    pub fn is_valid(&self, t: Epoch) -> bool {
+        (t - self.toe()).abs() <= self.validity()
+    }
+
+    /// Evaluates Earth fixed position (in km) at desired [Epoch].
+    pub fn position_km(&self, t: Epoch) -> Vector3D {
+        match self {
+            Self::Keplerian(eph) => eph.position_km(t),
+            Self::Glonass(eph) => eph.position_km(t),
+        }
+    }
+
+    /// Evaluates Earth fixed velocity (in km.s⁻¹) at desired [Epoch].
+    pub fn velocity_km_s(&self, t: Epoch) -> Vector3D {
+        match self {
+            Self::Keplerian(eph) => eph.velocity_km_s(t),
+            Self::Glonass(eph) => eph.velocity_km_s(t),
+        }
+    }
+
+    /// Evaluates satellite clock offset (in seconds) at desired [Epoch].
+    pub fn clock_offset_s(&self, t: Epoch) -> f64 {
+        match self {
+            Self::Keplerian(eph) => eph.clock_offset_s(t),
+            Self::Glonass(eph) => eph.clock_offset_s(t),
+        }
+    }
+
+    /// Selects the [BroadcastEphemeris] to be used for this [SV] at this [Epoch],
+    /// among a set of messages: the closest valid message.
+    pub fn select(ephemerides: &[Self], sv: SV, t: Epoch) -> Option<&Self> {
+        ephemerides
+            .iter()
+            .filter(|eph| eph.sv() == sv && eph.is_valid(t))
+            .min_by_key(|eph| (t - eph.toe()).abs())
+    }
+}
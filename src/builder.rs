@@ -0,0 +1,402 @@
+//! [SP3] synthesis
+use crate::{
+    header::time_system::clock_interval,
+    prelude::{
+        Availability, BuildError, Campaign, Constellation, DataType, Duration, Epoch, Header,
+        OrbitType, ProductionAttributes, ReferenceFrame, ReleaseDate, ReleasePeriod, SP3Entry,
+        SP3Key, TimeScale, TimeSystem, Version, SP3, SV,
+    },
+};
+
+use std::collections::BTreeMap;
+
+/// Maximal number of satellites that revisions prior [Version::D] may describe
+const MAX_SATELLITES_PRIOR_D: usize = 85;
+
+/// [SP3Builder] is the easiest way to synthesize [SP3] data,
+/// for example from your own orbit estimates. All redundant
+/// [Header] fields are derived from the data on [SP3Builder::build].
+/// ```
+/// use sp3::prelude::*;
+/// use std::str::FromStr;
+///
+/// let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+/// let dt = Duration::from_seconds(900.0);
+/// let g01 = SV::from_str("G01").unwrap();
+///
+/// let mut builder = SP3Builder::default()
+///     .with_agency("TEST")
+///     .with_reference_frame(ReferenceFrame::IGS20)
+///     .with_orbit_type(OrbitType::FIT)
+///     .with_timescale(TimeScale::GPST)
+///     .with_sampling_period(dt)
+///     .with_production_attributes(Campaign::MGX, Availability::Final);
+///
+/// for i in 0..96 {
+///     let entry = SP3Entry::from_position_km((1.0, 2.0, 3.0))
+///         .with_clock_offset_us(1.0);
+///
+///     builder.push(t0 + dt * i as f64, g01, entry);
+/// }
+///
+/// let sp3 = builder.build()
+///     .unwrap();
+///
+/// assert_eq!(sp3.header.num_epochs, 96);
+/// assert!(sp3.validate().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SP3Builder {
+    /// [Version] to be generated
+    version: Version,
+
+    /// Agency producing this file
+    agency: String,
+
+    /// Coordinates system
+    coord_system: String,
+
+    /// [OrbitType] of the fit
+    orbit_type: OrbitType,
+
+    /// Observables used in the fit
+    observables: String,
+
+    /// [TimeScale] all [Epoch]s are expressed in
+    timescale: TimeScale,
+
+    /// [TimeSystem] descriptor
+    time_system: TimeSystem,
+
+    /// Sampling period. Derived from the data when not defined.
+    sampling_period: Option<Duration>,
+
+    /// File publication [Epoch]. Defaults to the first [Epoch].
+    release_epoch: Option<Epoch>,
+
+    /// [Campaign] and [Availability] of the [ProductionAttributes] to generate
+    production: Option<(Campaign, Availability)>,
+
+    /// File comments
+    comments: Vec<String>,
+
+    /// Pushed data
+    data: BTreeMap<SP3Key, SP3Entry>,
+}
+
+impl Default for SP3Builder {
+    fn default() -> Self {
+        Self {
+            version: Version::D,
+            agency: "XXXX".to_string(),
+            coord_system: ReferenceFrame::IGS20.to_string(),
+            orbit_type: OrbitType::FIT,
+            observables: "__u+U".to_string(),
+            timescale: TimeScale::GPST,
+            time_system: TimeSystem::GPS,
+            sampling_period: None,
+            release_epoch: None,
+            production: None,
+            comments: Vec::new(),
+            data: Default::default(),
+        }
+    }
+}
+
+impl SP3Builder {
+    /// Copies and returns [SP3Builder] with desired [Version].
+    pub fn with_version(&self, version: Version) -> Self {
+        let mut s = self.clone();
+        s.version = version;
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired agency.
+    pub fn with_agency(&self, agency: &str) -> Self {
+        let mut s = self.clone();
+        s.agency = agency.to_string();
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired [ReferenceFrame].
+    pub fn with_reference_frame(&self, frame: ReferenceFrame) -> Self {
+        let mut s = self.clone();
+        s.coord_system = frame.to_string();
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired [OrbitType].
+    pub fn with_orbit_type(&self, orbit_type: OrbitType) -> Self {
+        let mut s = self.clone();
+        s.orbit_type = orbit_type;
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired observables description.
+    pub fn with_observables(&self, observables: &str) -> Self {
+        let mut s = self.clone();
+        s.observables = observables.to_string();
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired [TimeScale].
+    /// All pushed [Epoch]s are expressed in this [TimeScale].
+    pub fn with_timescale(&self, timescale: TimeScale) -> Self {
+        let mut s = self.clone();
+        s.timescale = timescale;
+
+        if let Some(time_system) = TimeSystem::from_timescale(timescale) {
+            s.time_system = time_system;
+        }
+
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired [TimeSystem], for example
+    /// [TimeSystem::GLO] or [TimeSystem::IRN] that have no dedicated [TimeScale].
+    /// All pushed [Epoch]s are expressed in [TimeSystem::timescale].
+    pub fn with_time_system(&self, time_system: TimeSystem) -> Self {
+        let mut s = self.clone();
+        s.timescale = time_system.timescale();
+        s.time_system = time_system;
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired sampling period.
+    /// When not defined, the smallest interval between two [Epoch]s is used.
+    pub fn with_sampling_period(&self, sampling_period: Duration) -> Self {
+        let mut s = self.clone();
+        s.sampling_period = Some(sampling_period);
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with desired release [Epoch].
+    pub fn with_release_epoch(&self, epoch: Epoch) -> Self {
+        let mut s = self.clone();
+        s.release_epoch = Some(epoch);
+        s
+    }
+
+    /// Copies and returns [SP3Builder] with one more file comment.
+    pub fn with_comment(&self, comment: &str) -> Self {
+        let mut s = self.clone();
+        s.comments.push(comment.to_string());
+        s
+    }
+
+    /// Copies and returns [SP3Builder] that will also generate
+    /// [ProductionAttributes], for this [Campaign] and [Availability].
+    pub fn with_production_attributes(
+        &self,
+        campaign: Campaign,
+        availability: Availability,
+    ) -> Self {
+        let mut s = self.clone();
+        s.production = Some((campaign, availability));
+        s
+    }
+
+    /// Pushes a new [SP3Entry] for this [SV] at this [Epoch].
+    /// Any previous [SP3Entry] for this [SV] and [Epoch] is replaced.
+    pub fn push(&mut self, epoch: Epoch, sv: SV, entry: SP3Entry) -> &mut Self {
+        let epoch = epoch.to_time_scale(self.timescale);
+        self.data.insert(SP3Key { epoch, sv }, entry);
+        self
+    }
+
+    /// Verifies the [Version] constraints
+    fn verify_version(&self, satellites: &[SV]) -> Result<(), BuildError> {
+        if self.version < Version::C && self.timescale != TimeScale::GPST {
+            return Err(BuildError::VersionTimeScale(self.version, self.timescale));
+        }
+
+        for sv in satellites.iter() {
+            let supported = match self.version {
+                Version::A => sv.constellation == Constellation::GPS,
+                Version::B => matches!(
+                    sv.constellation,
+                    Constellation::GPS | Constellation::Glonass
+                ),
+                Version::C | Version::D => true,
+            };
+
+            if !supported {
+                return Err(BuildError::VersionConstellation(
+                    self.version,
+                    sv.constellation,
+                ));
+            }
+        }
+
+        if self.version < Version::D && satellites.len() > MAX_SATELLITES_PRIOR_D {
+            return Err(BuildError::TooManySatellites(
+                self.version,
+                satellites.len(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds [SP3] from all pushed data, deriving all redundant [Header] fields.
+    /// Fails if no data was pushed, if one [Epoch] does not match the sampling period,
+    /// or if the content cannot be described by the desired [Version].
+    pub fn build(&self) -> Result<SP3, BuildError> {
+        let mut sp3 = SP3 {
+            header: Header {
+                version: self.version,
+                agency: self.agency.clone(),
+                coord_system: self.coord_system.clone(),
+                orbit_type: self.orbit_type,
+                observables: self.observables.clone(),
+                timescale: self.timescale,
+                time_system: self.time_system,
+                ..Default::default()
+            },
+            comments: self.comments.clone(),
+            prod_attributes: None,
+            data: self.data.clone(),
+        };
+
+        sp3.rebuild_header_mut();
+
+        let (Some(first), Some(last)) = (sp3.first_epoch(), sp3.last_epoch()) else {
+            return Err(BuildError::NoData);
+        };
+
+        if let Some(sampling_period) = self.sampling_period {
+            sp3.header.sampling_period = sampling_period;
+        }
+
+        let sampling_period = sp3.header.sampling_period;
+
+        if sampling_period > Duration::ZERO {
+            for epoch in sp3.epochs_iter() {
+                // counted in the file timescale: UTC leap seconds do not break the grid
+                let interval = clock_interval(first, epoch);
+                let periods = interval.to_seconds() / sampling_period.to_seconds();
+
+                if interval != sampling_period * periods.round() {
+                    return Err(BuildError::UnsteadySampling(epoch));
+                }
+            }
+        }
+
+        self.verify_version(&sp3.header.satellites)?;
+
+        if sp3.data.values().any(|entry| entry.velocity_km_s.is_some()) {
+            sp3.header.data_type = DataType::Velocity;
+        }
+
+        sp3.header.release_epoch = self.release_epoch.unwrap_or(first);
+
+        if let Some((campaign, availability)) = self.production {
+            let span = last - first + sampling_period;
+
+            let release_period = if span <= Duration::from_hours(1.0) {
+                ReleasePeriod::Hourly
+            } else if span <= Duration::from_hours(12.0) {
+                ReleasePeriod::HalfDay
+            } else if span <= Duration::from_days(1.0) {
+                ReleasePeriod::Daily
+            } else if span <= Duration::from_days(7.0) {
+                ReleasePeriod::Weekly
+            } else if span <= Duration::from_days(31.0) {
+                ReleasePeriod::Monthly
+            } else {
+                ReleasePeriod::Yearly
+            };
+
+            sp3.prod_attributes = Some(ProductionAttributes {
+                agency: format!("{:_<3}", self.agency).chars().take(3).collect(),
+                batch_id: 0,
+                release_date: ReleaseDate::from(first),
+                release_period,
+                campaign,
+                availability,
+                sampling_period,
+                ..Default::default()
+            });
+        }
+
+        Ok(sp3)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{
+        BuildError, Constellation, DataType, Duration, Epoch, ReleasePeriod, SP3Builder, SP3Entry,
+        Version, SV,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn sp3_builder() {
+        let t0 = Epoch::from_str("2019-10-27T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(300.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+
+        let mut builder = SP3Builder::default()
+            .with_agency("TEST")
+            .with_production_attributes(Default::default(), Default::default());
+
+        assert!(matches!(builder.build(), Err(BuildError::NoData)));
+
+        for i in 0..12 {
+            let entry =
+                SP3Entry::from_position_km((1.0, 2.0, 3.0)).with_velocity_km_s((0.1, 0.2, 0.3));
+
+            builder.push(t0 + dt * i as f64, g01, entry);
+
+            if i != 5 {
+                builder.push(t0 + dt * i as f64, e01, entry);
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        assert!(sp3.validate().is_empty());
+        assert_eq!(sp3.header.num_epochs, 12);
+        assert_eq!(sp3.header.sampling_period, dt);
+        assert_eq!(sp3.header.constellation, Constellation::Mixed);
+        assert_eq!(sp3.header.data_type, DataType::Velocity);
+        assert_eq!(sp3.header.week, 2077);
+        assert_eq!(sp3.header.mjd, 58783);
+        assert_eq!(sp3.header.release_epoch, t0);
+
+        let attributes = sp3.prod_attributes.unwrap();
+        assert_eq!(attributes.agency, "TES");
+        assert_eq!(attributes.release_period, ReleasePeriod::Hourly);
+        assert_eq!(attributes.sampling_period, dt);
+
+        // short agency codes are padded
+        let sp3 = builder.with_agency("AB").build().unwrap();
+
+        assert_eq!(sp3.prod_attributes.as_ref().unwrap().agency, "AB_");
+        assert!(sp3.standardized_filename().starts_with("AB_0"));
+
+        // version constraints
+        assert!(matches!(
+            builder.with_version(Version::A).build(),
+            Err(BuildError::VersionConstellation(
+                Version::A,
+                Constellation::Galileo
+            ))
+        ));
+
+        // off grid epoch
+        builder.push(
+            t0 + Duration::from_seconds(10.0),
+            g01,
+            SP3Entry::from_position_km((1.0, 2.0, 3.0)),
+        );
+
+        assert!(matches!(
+            builder.with_sampling_period(dt).build(),
+            Err(BuildError::UnsteadySampling(_))
+        ));
+    }
+}
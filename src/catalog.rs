@@ -0,0 +1,580 @@
+//! Local [SP3] archive indexing and best product selection
+use crate::prelude::{
+    Availability, CatalogError, Constellation, Duration, Epoch, ProductionAttributes, TimeScale,
+    SP3, SV,
+};
+
+use itertools::Itertools;
+
+use std::{
+    cmp::Reverse,
+    fs::{read_dir, File},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// First line of persisted catalogs
+const CATALOG_HEADER: &str = "# SP3 CATALOG V1";
+
+/// [CatalogEntry] describes one indexed [SP3] file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    /// File path
+    pub path: PathBuf,
+
+    /// [ProductionAttributes], when the filename follows a standard naming convention
+    pub attributes: Option<ProductionAttributes>,
+
+    /// Agency, as declared in the header
+    pub agency: String,
+
+    /// [TimeScale] of this file
+    pub timescale: TimeScale,
+
+    /// Publication [Epoch], as declared in the header
+    pub release_epoch: Epoch,
+
+    /// First [Epoch] of the record
+    pub first_epoch: Epoch,
+
+    /// Last [Epoch] of the record
+    pub last_epoch: Epoch,
+
+    /// Sampling period, as declared in the header
+    pub sampling_period: Duration,
+
+    /// [SV]s found in the record, sorted
+    pub satellites: Vec<SV>,
+}
+
+impl CatalogEntry {
+    /// Indexes this [SP3] file
+    fn new(path: &Path, sp3: &SP3) -> Option<Self> {
+        Some(Self {
+            path: path.to_path_buf(),
+            attributes: Self::attributes(path),
+            agency: sp3.header.agency.trim().to_string(),
+            timescale: sp3.header.timescale,
+            release_epoch: sp3.header.release_epoch,
+            first_epoch: sp3.first_epoch()?,
+            last_epoch: sp3.last_epoch()?,
+            sampling_period: sp3.header.sampling_period,
+            satellites: sp3.data.keys().map(|k| k.sv).unique().sorted().collect(),
+        })
+    }
+
+    /// Parses [ProductionAttributes] from this file name
+    fn attributes(path: &Path) -> Option<ProductionAttributes> {
+        let filename = path.file_name()?.to_string_lossy();
+        ProductionAttributes::from_str(&filename).ok()
+    }
+
+    /// [Availability] of this file, when known
+    pub fn availability(&self) -> Option<Availability> {
+        self.attributes.as_ref().map(|attr| attr.availability)
+    }
+
+    /// [Constellation] describing this file
+    pub fn constellation(&self) -> Option<Constellation> {
+        let mut constellations = self.satellites.iter().map(|sv| sv.constellation).unique();
+        let first = constellations.next()?;
+
+        if constellations.next().is_some() {
+            Some(Constellation::Mixed)
+        } else {
+            Some(first)
+        }
+    }
+
+    /// Returns true if this file was published by this agency.
+    /// Both the 3-letter filename code and the header agency are compared.
+    pub fn published_by(&self, agency: &str) -> bool {
+        self.agency.eq_ignore_ascii_case(agency)
+            || self
+                .attributes
+                .as_ref()
+                .is_some_and(|attr| attr.agency.eq_ignore_ascii_case(agency))
+    }
+
+    /// Returns true if this file describes this [SV]
+    pub fn contains(&self, sv: SV) -> bool {
+        self.satellites.binary_search(&sv).is_ok()
+    }
+
+    /// Time frame covered by this file, as [start, end[.
+    /// The last [Epoch] stands for one sampling period.
+    fn time_frame(&self) -> (Epoch, Epoch) {
+        (self.first_epoch, self.last_epoch + self.sampling_period)
+    }
+
+    /// Formats this entry as one catalog line.
+    /// Fails if the path contains a tab or a line break, which are reserved separators.
+    fn format(&self) -> Result<String, CatalogError> {
+        let path = self.path.to_string_lossy();
+
+        if path.contains(['\t', '\n', '\r']) {
+            return Err(CatalogError::UnsupportedPath(path.to_string()));
+        }
+
+        Ok(format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            path,
+            self.agency,
+            self.timescale,
+            self.release_epoch,
+            self.first_epoch,
+            self.last_epoch,
+            self.sampling_period.to_seconds(),
+            self.satellites.iter().join(","),
+        ))
+    }
+
+    /// Parses one catalog line
+    fn parse(line: &str) -> Result<Self, CatalogError> {
+        let malformed = || CatalogError::MalformedIndex(line.to_string());
+
+        let items = line.split('\t').collect::<Vec<_>>();
+
+        let [path, agency, timescale, release, first, last, sampling, satellites] = items[..]
+        else {
+            return Err(malformed());
+        };
+
+        let path = PathBuf::from(path);
+
+        let satellites = satellites
+            .split(',')
+            .filter(|sv| !sv.is_empty())
+            .map(SV::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| malformed())?;
+
+        Ok(Self {
+            attributes: Self::attributes(&path),
+            path,
+            agency: agency.to_string(),
+            timescale: TimeScale::from_str(timescale).map_err(|_| malformed())?,
+            release_epoch: Epoch::from_str(release).map_err(|_| malformed())?,
+            first_epoch: Epoch::from_str(first).map_err(|_| malformed())?,
+            last_epoch: Epoch::from_str(last).map_err(|_| malformed())?,
+            sampling_period: Duration::from_seconds(
+                sampling.parse::<f64>().map_err(|_| malformed())?,
+            ),
+            satellites,
+        })
+    }
+}
+
+/// [CatalogPreference] defines which file is selected, when several
+/// files describe the same time frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogPreference {
+    /// [Availability]s, from most to least preferred.
+    /// Files with another (or unknown) [Availability] come last.
+    pub availabilities: Vec<Availability>,
+
+    /// Agencies, from most to least preferred.
+    /// Files from other agencies come last.
+    pub agencies: Vec<String>,
+}
+
+impl Default for CatalogPreference {
+    /// Builds a [CatalogPreference] that prefers Final over Rapid over Ultra-Rapid
+    /// products, without agency preference.
+    fn default() -> Self {
+        Self {
+            availabilities: vec![
+                Availability::Final,
+                Availability::Rapid,
+                Availability::UltraRapid,
+            ],
+            agencies: Vec::new(),
+        }
+    }
+}
+
+impl CatalogPreference {
+    /// Copies and returns [CatalogPreference] with desired [Availability] order.
+    pub fn with_availabilities(&self, availabilities: &[Availability]) -> Self {
+        let mut s = self.clone();
+        s.availabilities = availabilities.to_vec();
+        s
+    }
+
+    /// Copies and returns [CatalogPreference] with desired agency order.
+    pub fn with_agencies(&self, agencies: &[&str]) -> Self {
+        let mut s = self.clone();
+        s.agencies = agencies.iter().map(|agency| agency.to_string()).collect();
+        s
+    }
+
+    /// Ranks this [CatalogEntry], lowest being preferred:
+    /// [Availability] first, then agency, then most recent publication.
+    fn rank(&self, entry: &CatalogEntry) -> (usize, usize, Reverse<Epoch>) {
+        let availability = entry
+            .availability()
+            .and_then(|availability| self.availabilities.iter().position(|a| *a == availability))
+            .unwrap_or(self.availabilities.len());
+
+        let agency = self
+            .agencies
+            .iter()
+            .position(|agency| entry.published_by(agency))
+            .unwrap_or(self.agencies.len());
+
+        (availability, agency, Reverse(entry.release_epoch))
+    }
+}
+
+/// [SP3Catalog] indexes local [SP3] archives, to select and load
+/// the best products describing a satellite over a time frame.
+/// ```
+/// use sp3::prelude::*;
+/// use std::str::FromStr;
+///
+/// let mut catalog = SP3Catalog::default();
+///
+/// // index a complete archive (recursively)
+/// let indexed = catalog.scan_dir_mut("data/SP3")
+///     .unwrap();
+///
+/// assert!(indexed > 0);
+///
+/// // persist the index, to avoid scanning again
+/// let index = std::env::temp_dir().join("sp3-catalog.txt");
+///
+/// catalog.to_file(&index)
+///     .unwrap();
+///
+/// let catalog = SP3Catalog::from_file(&index)
+///     .unwrap();
+///
+/// std::fs::remove_file(&index)
+///     .unwrap();
+///
+/// let g01 = SV::from_str("G01").unwrap();
+/// let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+/// let t1 = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+///
+/// // prefer Final products, from GRG then ESA
+/// let preference = CatalogPreference::default()
+///     .with_agencies(&["GRG", "ESA"]);
+///
+/// for entry in catalog.select(g01, t0, t1, &preference) {
+///     println!("selected {}", entry.path.display());
+/// }
+///
+/// // load and merge the selected files, restricted to [t0, t1]
+/// let sp3 = catalog.load(g01, t0, t1, &preference)
+///     .unwrap();
+///
+/// assert!(sp3.first_epoch().unwrap() >= t0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SP3Catalog {
+    /// Indexed files
+    entries: Vec<CatalogEntry>,
+}
+
+impl SP3Catalog {
+    /// Returns an iterator over all indexed files
+    pub fn entries_iter(&self) -> impl Iterator<Item = &CatalogEntry> + '_ {
+        self.entries.iter()
+    }
+
+    /// Number of indexed files
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no file is indexed
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Indexes this [SP3] file, replacing any previous index of the same file.
+    /// Any supported compression is handled, see [SP3::from_path].
+    /// Fails if this file does not contain any data.
+    pub fn index_file_mut(&mut self, path: impl AsRef<Path>) -> Result<(), CatalogError> {
+        let path = path.as_ref();
+        let sp3 = SP3::from_path(path)?;
+
+        let entry = CatalogEntry::new(path, &sp3).ok_or(CatalogError::NoData)?;
+
+        self.entries.retain(|entry| entry.path != path);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Scans this directory recursively and indexes every [SP3] file,
+    /// skipping files that are already indexed and files that are not valid [SP3].
+    /// Returns the number of newly indexed files.
+    pub fn scan_dir_mut(&mut self, dir: impl AsRef<Path>) -> Result<usize, CatalogError> {
+        let mut indexed = 0;
+
+        for entry in read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                indexed += self.scan_dir_mut(&path)?;
+                continue;
+            }
+
+            if self.entries.iter().any(|entry| entry.path == path) {
+                continue;
+            }
+
+            if self.index_file_mut(&path).is_ok() {
+                indexed += 1;
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    /// Selects the files that describe this [SV] over [t0, t1], by order of
+    /// [CatalogPreference]. A less preferred file is only selected when it
+    /// describes a portion of the time frame that preferred files do not.
+    pub fn select(
+        &self,
+        sv: SV,
+        t0: Epoch,
+        t1: Epoch,
+        preference: &CatalogPreference,
+    ) -> Vec<&CatalogEntry> {
+        let mut selected = Vec::<&CatalogEntry>::new();
+
+        let candidates = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                let (start, end) = entry.time_frame();
+                entry.contains(sv) && start <= t1 && end > t0
+            })
+            .sorted_by_key(|entry| preference.rank(entry));
+
+        for candidate in candidates {
+            let (start, end) = candidate.time_frame();
+            let (start, end) = (start.max(t0), end.min(t1 + candidate.sampling_period));
+
+            let covered = selected.iter().map(|entry| entry.time_frame()).sorted();
+
+            let mut cursor = start;
+
+            for (covered_start, covered_end) in covered {
+                if covered_start > cursor {
+                    break;
+                }
+                cursor = cursor.max(covered_end);
+            }
+
+            if cursor < end {
+                selected.push(candidate);
+            }
+        }
+
+        selected
+    }
+
+    /// Selects (see [Self::select]), loads and merges the best files describing
+    /// this [SV] over [t0, t1]. The returned [SP3] is restricted to this [SV] and [t0, t1]:
+    /// on common [Epoch]s, data from the preferred file is retained.
+    /// All files must be expressed in the same [TimeScale] and coordinates system.
+    pub fn load(
+        &self,
+        sv: SV,
+        t0: Epoch,
+        t1: Epoch,
+        preference: &CatalogPreference,
+    ) -> Result<SP3, CatalogError> {
+        let selected = self.select(sv, t0, t1, preference);
+
+        let (first, others) = selected.split_first().ok_or(CatalogError::NoData)?;
+
+        let mut sp3 = SP3::from_path(&first.path)?;
+
+        sp3.data
+            .retain(|k, _| k.sv == sv && k.epoch >= t0 && k.epoch <= t1);
+
+        for entry in others {
+            let rhs = SP3::from_path(&entry.path)?;
+
+            if rhs.header.timescale != sp3.header.timescale {
+                return Err(CatalogError::TimescaleMismatch);
+            }
+
+            if rhs.header.coord_system != sp3.header.coord_system {
+                return Err(CatalogError::ReferenceFrameMismatch);
+            }
+
+            for (key, value) in rhs.data {
+                if key.sv == sv && key.epoch >= t0 && key.epoch <= t1 {
+                    sp3.data.entry(key).or_insert(value);
+                }
+            }
+        }
+
+        if others.is_empty() {
+            sp3.prod_attributes = first.attributes.clone();
+        } else {
+            // no longer describes a single product
+            sp3.prod_attributes = None;
+        }
+
+        sp3.rebuild_header_mut();
+        Ok(sp3)
+    }
+
+    /// Persists this [SP3Catalog] as a text file, one indexed file per line.
+    /// Fails if one of the indexed paths contains a tab or a line break.
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), CatalogError> {
+        // validate every entry, prior overwriting anything
+        let lines = self
+            .entries
+            .iter()
+            .map(|entry| entry.format())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        writeln!(writer, "{}", CATALOG_HEADER)?;
+
+        for line in lines.iter() {
+            writeln!(writer, "{}", line)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a [SP3Catalog] that was persisted with [Self::to_file].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CatalogError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            entries.push(CatalogEntry::parse(&line)?);
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{
+        Availability, Campaign, CatalogError, CatalogPreference, Duration, Epoch, SP3Builder,
+        SP3Catalog, SP3Entry, SV,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn sp3_catalog() {
+        let dir = std::env::temp_dir().join(format!("sp3-catalog-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("rapid")).unwrap();
+
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        // final: first day, rapid: two days, offset by 1m in position
+        for (agency, availability, subdir, days, offset_km) in [
+            ("GRG", Availability::Final, "", 1, 0.0),
+            ("ESA", Availability::Rapid, "rapid", 2, 1.0E-3),
+        ] {
+            let mut builder = SP3Builder::default()
+                .with_agency(agency)
+                .with_production_attributes(Campaign::MGX, availability);
+
+            for i in 0..96 * days {
+                let entry = SP3Entry::from_position_km((1.0 + offset_km, 2.0, 3.0))
+                    .with_clock_offset_us(1.0);
+
+                builder.push(t0 + dt * i as f64, g01, entry);
+                builder.push(t0 + dt * i as f64, g02, entry);
+            }
+
+            let sp3 = builder.build().unwrap();
+            let path = dir.join(subdir).join(sp3.standardized_filename());
+            sp3.to_file(&path).unwrap();
+        }
+
+        let mut catalog = SP3Catalog::default();
+        assert_eq!(catalog.scan_dir_mut(&dir).unwrap(), 2);
+        assert_eq!(catalog.scan_dir_mut(&dir).unwrap(), 0);
+
+        // persistence
+        let index = dir.join("catalog.txt");
+        catalog.to_file(&index).unwrap();
+        assert_eq!(SP3Catalog::from_file(&index).unwrap(), catalog);
+
+        let t1 = t0 + Duration::from_hours(36.0);
+        let preference = CatalogPreference::default();
+
+        let selected = catalog.select(g01, t0, t1, &preference);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].availability(), Some(Availability::Final));
+
+        // first day only: rapid is not needed
+        let selected = catalog.select(g01, t0, t0 + Duration::from_hours(12.0), &preference);
+        assert_eq!(selected.len(), 1);
+
+        // agency preference does not override availability
+        let preference = preference.with_agencies(&["ESA"]);
+        let selected = catalog.select(g01, t0, t1, &preference);
+        assert_eq!(selected[0].agency, "GRG");
+
+        let preference = preference.with_availabilities(&[]);
+        let selected = catalog.select(g01, t0, t1, &preference);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].agency, "ESA");
+
+        // load
+        let sp3 = catalog
+            .load(g01, t0, t1, &CatalogPreference::default())
+            .unwrap();
+
+        assert_eq!(sp3.first_epoch(), Some(t0));
+        assert_eq!(sp3.last_epoch(), Some(t1));
+        assert_eq!(sp3.header.num_epochs, 145);
+        assert_eq!(sp3.header.satellites, vec![g01]);
+        assert!(sp3.validate().is_empty());
+
+        for (k, v) in sp3.data.iter() {
+            assert_eq!(k.sv, g01);
+
+            let expected_km = if k.epoch < t0 + Duration::from_days(1.0) {
+                1.0
+            } else {
+                1.001
+            };
+            assert!((v.position_km.0 - expected_km).abs() < 1.0E-6);
+        }
+
+        // tabs are reserved separators
+        let filename = catalog.entries_iter().next().unwrap().path.clone();
+        let tabbed = dir.join("tab\tseparated.sp3");
+        std::fs::copy(&filename, &tabbed).unwrap();
+
+        catalog.index_file_mut(&tabbed).unwrap();
+
+        assert!(matches!(
+            catalog.to_file(&index),
+            Err(CatalogError::UnsupportedPath(_))
+        ));
+
+        // previous index is left untouched
+        assert_eq!(SP3Catalog::from_file(&index).unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
-    prelude::{DataType, Epoch, SP3Entry, SP3, SV},
+    prelude::{DataType, Epoch, SP3Entry, SP3Key, SP3, SV},
     Vector3D,
 };
 
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Bound};
 
 impl SP3 {
     /// Resolve all satellite dynamics for each [Epoch]
@@ -110,6 +110,51 @@ impl SP3 {
         }
     }
 
+    /// Returns [SV] velocity vector (in km.s⁻¹) at desired [Epoch], which must
+    /// exist in this record. When not published, the velocity is
+    /// estimated by finite differences of the neighboring positions
+    /// (centered when feasible). Null (missing) positions are not differenced,
+    /// and neighbors more than 1.5 sampling periods away (data gaps) are not used.
+    pub fn satellite_velocity_km_s(&self, sv: SV, epoch: Epoch) -> Option<Vector3D> {
+        let key = SP3Key { sv, epoch };
+        let entry = self.data.get(&key)?;
+
+        if let Some(velocity_km_s) = entry.velocity_km_s {
+            return Some(velocity_km_s);
+        }
+
+        if entry.position_km == (0.0, 0.0, 0.0) {
+            return None;
+        }
+
+        let max_gap = self.nominal_sampling_period() * 1.5;
+
+        let previous = self.data.range(..key.clone()).next_back().filter(|(k, v)| {
+            k.sv == sv && v.position_km != (0.0, 0.0, 0.0) && epoch - k.epoch <= max_gap
+        });
+
+        let next = self
+            .data
+            .range((Bound::Excluded(key), Bound::Unbounded))
+            .next()
+            .filter(|(k, v)| {
+                k.sv == sv && v.position_km != (0.0, 0.0, 0.0) && k.epoch - epoch <= max_gap
+            });
+
+        let ((t0, p0), (t1, p1)) = match (previous, next) {
+            (Some((k0, v0)), Some((k1, v1))) => {
+                ((k0.epoch, v0.position_km), (k1.epoch, v1.position_km))
+            },
+            (Some((k0, v0)), None) => ((k0.epoch, v0.position_km), (epoch, entry.position_km)),
+            (None, Some((k1, v1))) => ((epoch, entry.position_km), (k1.epoch, v1.position_km)),
+            (None, None) => return None,
+        };
+
+        let dt = (t1 - t0).to_seconds();
+
+        Some(((p1.0 - p0.0) / dt, (p1.1 - p0.1) / dt, (p1.2 - p0.2) / dt))
+    }
+
     /// Refer to [SP3::resolve_dynamics_mut].
     pub fn resolve_velocities(&self) -> Self {
         let mut s = self.clone();
@@ -140,7 +185,7 @@ impl SP3 {
 
 #[cfg(test)]
 mod test {
-    use crate::prelude::{DataType, Duration, Epoch, SP3, SV};
+    use crate::prelude::{DataType, Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
 
     use std::str::FromStr;
 
@@ -468,4 +513,40 @@ mod test {
 
         assert_eq!(tests, 4);
     }
+
+    #[test]
+    fn velocity_data_gaps() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        // 1 km/s along x, with a 1h data gap after the third epoch
+        for i in [0, 1, 2, 6, 7] {
+            let epoch = t0 + dt * i as f64;
+            let position_km = (10_000.0 + 900.0 * i as f64, 0.0, 0.0);
+
+            sp3.data.insert(
+                SP3Key { sv: g01, epoch },
+                SP3Entry::from_position_km(position_km),
+            );
+        }
+
+        for i in [0, 1, 2, 6, 7] {
+            let velocity_km_s = sp3.satellite_velocity_km_s(g01, t0 + dt * i as f64);
+            assert_eq!(velocity_km_s, Some((1.0, 0.0, 0.0)), "epoch #{}", i);
+        }
+
+        // isolated epoch
+        let epoch = t0 + dt * 4.0;
+
+        sp3.data.insert(
+            SP3Key { sv: g01, epoch },
+            SP3Entry::from_position_km((13_600.0, 0.0, 0.0)),
+        );
+
+        assert!(sp3.satellite_velocity_km_s(g01, epoch).is_none());
+    }
 }
@@ -1,5 +1,5 @@
 #[cfg(doc)]
-use crate::prelude::SP3Key;
+use crate::prelude::{Header, SP3Key};
 
 use crate::{formatting::CoordsFormatter, prelude::SV, FormattingError, Vector3D};
 
@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 
 use std::io::{BufWriter, Write};
 
+/// Sentinel value used by SP3 producers for bad or absent clock data (999999.999999)
+pub(crate) const CLOCK_SENTINEL_US: f64 = 999_999.0;
+
 /// SP3 record content are [SP3Entry] indexed by [SP3Key].
 #[derive(Debug, Copy, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -37,6 +40,40 @@ pub struct SP3Entry {
 
     /// Clock drift in nanoseconds with 10⁻¹⁶ precision.
     pub clock_drift_ns: Option<f64>,
+
+    /// True when this entry was not published but interpolated,
+    /// for example when filling data gaps.
+    pub interpolated: bool,
+
+    /// Position standard deviation, in millimeters.
+    /// Formatted as exponents of [Header::position_velocity_base].
+    pub position_std_dev_mm: Option<Vector3D>,
+
+    /// Velocity standard deviation, in millimeters per second.
+    /// Formatted as exponents of [Header::position_velocity_base].
+    pub velocity_std_dev_mm_s: Option<Vector3D>,
+}
+
+/// Formats the standard deviation exponents columns (including clock), in this base,
+/// of these standard deviations scaled to the file unit. Blank when not defined.
+fn std_dev_exponents(std_dev: Option<Vector3D>, base: f64, scaling: f64) -> String {
+    match std_dev {
+        Some((x, y, z)) if base > 1.0 => {
+            let exponent = |value: f64| {
+                ((value * scaling).ln() / base.ln())
+                    .round()
+                    .clamp(0.0, 99.0) as u8
+            };
+
+            format!(
+                " {:>2} {:>2} {:>2}     ",
+                exponent(x),
+                exponent(y),
+                exponent(z)
+            )
+        },
+        _ => " ".repeat(14),
+    }
 }
 
 impl std::ops::Sub for SP3Entry {
@@ -46,7 +83,7 @@ impl std::ops::Sub for SP3Entry {
         Self {
             position_km: (
                 self.position_km.0 - rhs.position_km.0,
-                self.position_km.1 - self.position_km.1,
+                self.position_km.1 - rhs.position_km.1,
                 self.position_km.2 - rhs.position_km.2,
             ),
             velocity_km_s: if let Some(velocity_km_s) = self.velocity_km_s {
@@ -64,6 +101,9 @@ impl std::ops::Sub for SP3Entry {
             clock_event: self.clock_event,
             predicted_clock: self.predicted_clock,
             predicted_orbit: self.predicted_orbit,
+            interpolated: self.interpolated,
+            position_std_dev_mm: self.position_std_dev_mm,
+            velocity_std_dev_mm_s: self.velocity_std_dev_mm_s,
             clock_us: if let Some(clock_us) = self.clock_us {
                 rhs.clock_us.map(|rhs| clock_us - rhs)
             } else {
@@ -119,6 +159,9 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: false,
             clock_event: false,
+            interpolated: false,
+            position_std_dev_mm: None,
+            velocity_std_dev_mm_s: None,
         }
     }
 
@@ -133,6 +176,9 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: true,
             clock_event: false,
+            interpolated: false,
+            position_std_dev_mm: None,
+            velocity_std_dev_mm_s: None,
         }
     }
 
@@ -148,6 +194,9 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: false,
             clock_event: false,
+            interpolated: false,
+            position_std_dev_mm: None,
+            velocity_std_dev_mm_s: None,
         }
     }
 
@@ -166,11 +215,34 @@ impl SP3Entry {
             predicted_clock: false,
             predicted_orbit: true,
             clock_event: false,
+            interpolated: false,
+            position_std_dev_mm: None,
+            velocity_std_dev_mm_s: None,
         }
     }
 
-    /// Formats this [SP3Entry] according to SP3 standards
+    /// Returns the clock offset in microseconds, unless it is missing
+    /// or is the sentinel value (999999.999999) used for bad or absent clock data.
+    pub fn valid_clock_offset_us(&self) -> Option<f64> {
+        self.clock_us
+            .filter(|clock_us| clock_us.abs() < CLOCK_SENTINEL_US)
+    }
+
+    /// Formats this [SP3Entry] according to SP3 standards.
+    /// Standard deviations are not formatted, because they are expressed
+    /// in the header base: see [SP3::format](crate::prelude::SP3).
     pub fn format<W: Write>(&self, sv: SV, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
+        self.format_with_std_dev_base(sv, 0.0, w)
+    }
+
+    /// Formats this [SP3Entry], with standard deviations expressed as exponents
+    /// of this base. Standard deviations are omitted when the base is not defined.
+    pub(crate) fn format_with_std_dev_base<W: Write>(
+        &self,
+        sv: SV,
+        base: f64,
+        w: &mut BufWriter<W>,
+    ) -> Result<(), FormattingError> {
         let mut formatted = format!(
             "P{}{}{}{}",
             sv,
@@ -185,7 +257,8 @@ impl SP3Entry {
             formatted.push_str("              ");
         }
 
-        formatted.push_str("              "); // deviations not handled yet
+        // mm
+        formatted.push_str(&std_dev_exponents(self.position_std_dev_mm, base, 1.0));
 
         if self.clock_event {
             formatted.push('E');
@@ -225,7 +298,13 @@ impl SP3Entry {
                     "{}",
                     CoordsFormatter::coordinates(drift_ns * 1.0E-2)
                 )); // 0.1 us
+            } else {
+                formatted.push_str("              ");
             }
+
+            // 10⁻⁴ mm/s
+            formatted.push_str(&std_dev_exponents(self.velocity_std_dev_mm_s, base, 1.0E4));
+            formatted = formatted.trim_end().to_string();
         }
 
         writeln!(w, "{}", formatted)?;
@@ -309,6 +388,20 @@ impl SP3Entry {
         s.clock_drift_ns = Some(drift_ns);
         s
     }
+
+    /// Copies and returns [Self] with position standard deviation in millimeters
+    pub fn with_position_std_dev_mm(&self, std_dev_mm: Vector3D) -> Self {
+        let mut s = *self;
+        s.position_std_dev_mm = Some(std_dev_mm);
+        s
+    }
+
+    /// Copies and returns [Self] with velocity standard deviation in millimeters per second
+    pub fn with_velocity_std_dev_mm_s(&self, std_dev_mm_s: Vector3D) -> Self {
+        let mut s = *self;
+        s.velocity_std_dev_mm_s = Some(std_dev_mm_s);
+        s
+    }
 }
 
 #[cfg(test)]
@@ -333,6 +426,9 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    interpolated: false,
+                    position_std_dev_mm: None,
+                    velocity_std_dev_mm_s: None,
                     predicted_clock: false,
                     clock_us: Some(10.571484),
                 },
@@ -364,6 +460,9 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    interpolated: false,
+                    position_std_dev_mm: None,
+                    velocity_std_dev_mm_s: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -395,6 +494,9 @@ mod test {
                     maneuver: true,
                     clock_drift_ns: None,
                     clock_event: false,
+                    interpolated: false,
+                    position_std_dev_mm: None,
+                    velocity_std_dev_mm_s: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -426,6 +528,9 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    interpolated: false,
+                    position_std_dev_mm: None,
+                    velocity_std_dev_mm_s: None,
                     predicted_clock: true,
                     clock_us: None,
                 },
@@ -457,6 +562,9 @@ mod test {
                 maneuver: false,
                 clock_drift_ns: None,
                 clock_event: false,
+                interpolated: false,
+                position_std_dev_mm: None,
+                velocity_std_dev_mm_s: None,
                 predicted_clock: false,
                 clock_us: None,
             },
@@ -488,6 +596,9 @@ mod test {
                 maneuver: false,
                 clock_drift_ns: None,
                 clock_event: false,
+                interpolated: false,
+                position_std_dev_mm: None,
+                velocity_std_dev_mm_s: None,
                 predicted_clock: true,
                 clock_us: None,
             },
@@ -523,6 +634,9 @@ mod test {
                     maneuver: false,
                     clock_drift_ns: None,
                     clock_event: false,
+                    interpolated: false,
+                    position_std_dev_mm: None,
+                    velocity_std_dev_mm_s: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -560,6 +674,9 @@ VG01  -8880.949046 -23142.274905 -14050.679881\n",
                     maneuver: false,
                     clock_drift_ns: Some(8.9376),
                     clock_event: false,
+                    interpolated: false,
+                    position_std_dev_mm: None,
+                    velocity_std_dev_mm_s: None,
                     predicted_clock: false,
                     clock_us: None,
                 },
@@ -593,6 +710,9 @@ VG01  -8880.949046 -23142.274905 -14050.679881      0.089376\n",
             clock_event: true,
             clock_us: Some(-176.397152),
             clock_drift_ns: None,
+            interpolated: false,
+            position_std_dev_mm: None,
+            velocity_std_dev_mm_s: None,
         };
 
         let mut buf = BufWriter::new(Utf8Buffer::new(1024));
@@ -609,4 +729,11 @@ VG01  -8880.949046 -23142.274905 -14050.679881      0.089376\n",
             "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152              EP  MP\n"
         );
     }
+
+    #[test]
+    fn entry_substraction() {
+        let a = SP3Entry::from_position_km((1.0, 2.0, 3.0));
+        let b = SP3Entry::from_position_km((0.5, 1.0, 1.5));
+        assert_eq!((a - b).position_km, (0.5, 1.0, 1.5));
+    }
 }
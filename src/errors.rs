@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::prelude::{Constellation, Epoch, FileCompression, TimeScale, Version, SV};
+
 use gnss_rs::constellation::ParsingError as ConstellationParsingError;
 use hifitime::errors::ParsingError as EpochParsingError;
 use std::io::Error as IoError;
@@ -17,6 +19,9 @@ pub enum Error {
 
     #[error("File i/o error: {0}")]
     FileIo(#[from] IoError),
+
+    #[error("{0} compression is not supported: activate the related crate feature")]
+    UnsupportedCompression(FileCompression),
 }
 
 #[derive(Debug, Error)]
@@ -39,6 +44,9 @@ pub enum ParsingError {
     #[error("malformed %c line \"{0}\"")]
     MalformedDescriptor(String),
 
+    #[error("unknown time system \"{0}\"")]
+    UnknownTimeSystem(String),
+
     #[error("failed to parse Epoch")]
     EpochParsing,
 
@@ -77,6 +85,121 @@ pub enum ParsingError {
 
     #[error("not a valid IGS campaign name")]
     InvalidCampaignName,
+
+    #[error("unknown reference frame \"{0}\"")]
+    UnknownReferenceFrame(String),
+
+    #[error("malformed ANTEX content \"{0}\"")]
+    MalformedAntex(String),
+
+    #[error("unknown satellite block \"{0}\"")]
+    UnknownSatelliteBlock(String),
+}
+
+/// Errors that may rise when building [crate::prelude::SP3] with [crate::prelude::SP3Builder]
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("no data to build from")]
+    NoData,
+
+    #[error("epoch {0} does not match the sampling period")]
+    UnsteadySampling(Epoch),
+
+    #[error("revision {0} does not support {1} satellites")]
+    VersionConstellation(Version, Constellation),
+
+    #[error("revision {0} does not support the {1} timescale")]
+    VersionTimeScale(Version, TimeScale),
+
+    #[error("revision {0} cannot describe {1} satellites")]
+    TooManySatellites(Version, usize),
+}
+
+/// Errors that may rise when indexing or loading [crate::prelude::SP3Catalog] files
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error("file i/o error: {0}")]
+    FileIo(#[from] IoError),
+
+    #[error("sp3 error: {0}")]
+    SP3(#[from] Error),
+
+    #[error("malformed catalog line \"{0}\"")]
+    MalformedIndex(String),
+
+    #[error("no data")]
+    NoData,
+
+    #[error("files are expressed in different timescales")]
+    TimescaleMismatch,
+
+    #[error("files are expressed in different reference frames")]
+    ReferenceFrameMismatch,
+
+    #[error("path \"{0}\" cannot be persisted (contains tab or line break)")]
+    UnsupportedPath(String),
+}
+
+/// Errors that may rise when stitching ultra-rapid products
+#[derive(Debug, Error)]
+pub enum StitchingError {
+    #[error("no data to stitch")]
+    NoData,
+
+    #[error("only ultra-rapid products may be stitched")]
+    NotUltraRapid,
+
+    #[error("products come from different data providers")]
+    DataProviderMismatch,
+
+    #[error("products are expressed in different timescales")]
+    TimescaleMismatch,
+
+    #[error("products are expressed in different reference frames")]
+    ReferenceFrameMismatch,
+}
+
+/// Errors that may rise when converting to or from CCSDS OEM
+#[derive(Debug, Error)]
+pub enum OemError {
+    #[error("file i/o error: {0}")]
+    FileIo(#[from] IoError),
+
+    #[error("build error: {0}")]
+    Build(#[from] BuildError),
+
+    #[error("no data")]
+    NoData,
+
+    #[error("malformed OEM content \"{0}\"")]
+    Malformed(String),
+
+    #[error("missing {0} keyword")]
+    MissingKeyword(String),
+
+    #[error("invalid epoch \"{0}\"")]
+    Epoch(String),
+
+    #[error("unsupported time system \"{0}\"")]
+    TimeSystem(String),
+
+    #[error("unsupported reference frame \"{0}\"")]
+    ReferenceFrame(String),
+
+    #[error("unsupported center \"{0}\": only EARTH is supported")]
+    CenterName(String),
+
+    #[error("unknown object \"{0}\"")]
+    Object(String),
+
+    #[error("segments are expressed in different time systems")]
+    TimeSystemMismatch,
+
+    #[error("segments are expressed in different reference frames")]
+    ReferenceFrameMismatch,
+
+    #[error("velocity of {0} cannot be determined")]
+    Velocity(SV),
 }
 
 /// Errors that may rise in Formatting process
@@ -84,4 +207,7 @@ pub enum ParsingError {
 pub enum FormattingError {
     #[error("i/o: output error")]
     OutputError(#[from] IoError),
+
+    #[error("{0} compression is not supported: activate the related crate feature")]
+    UnsupportedCompression(FileCompression),
 }
@@ -7,13 +7,26 @@ use std::{
 
 use itertools::Itertools;
 
-use crate::{errors::FormattingError, prelude::SP3};
+use crate::{
+    errors::FormattingError,
+    lzw,
+    prelude::{FileCompression, SP3},
+};
 
 #[cfg(feature = "flate2")]
 use flate2::{write::GzEncoder, Compression as GzCompression};
 
+#[cfg(feature = "bzip2")]
+use bzip2::{write::BzEncoder, Compression as BzCompression};
+
+#[cfg(feature = "zstd")]
+use zstd::stream::write::Encoder as ZstdEncoder;
+
 use hifitime::efmt::{Format, Formatter};
 
+/// Standard deviations base, used when the header does not define one
+const DEFAULT_STD_DEV_BASE: f64 = 1.25;
+
 pub(crate) struct CoordsFormatter {
     value: f64,
     width: usize,
@@ -77,7 +90,24 @@ impl SP3 {
     pub fn format<W: Write>(&self, writer: &mut BufWriter<W>) -> Result<(), FormattingError> {
         let efmt = Format::from_str("%Y %m %d %H %M %S.%f").unwrap();
 
-        self.header.format(writer)?;
+        let has_std_devs = self.data.values().any(|entry| {
+            entry.position_std_dev_mm.is_some() || entry.velocity_std_dev_mm_s.is_some()
+        });
+
+        // standard deviations require a base
+        let base = if self.header.position_velocity_base > 1.0 || !has_std_devs {
+            self.header.position_velocity_base
+        } else {
+            DEFAULT_STD_DEV_BASE
+        };
+
+        if base != self.header.position_velocity_base {
+            let mut header = self.header.clone();
+            header.position_velocity_base = base;
+            header.format(writer)?;
+        } else {
+            self.header.format(writer)?;
+        }
 
         for comment in self.comments.iter() {
             writeln!(writer, "/* {}", comment)?;
@@ -96,7 +126,7 @@ impl SP3 {
                 .sorted()
             {
                 if let Some(entry) = self.data.get(key) {
-                    entry.format(key.sv, writer)?;
+                    entry.format_with_std_dev_base(key.sv, base, writer)?;
                 }
             }
         }
@@ -107,20 +137,66 @@ impl SP3 {
         Ok(())
     }
 
-    /// Dumps [SP3] into writable local file (as readable ASCII UTF-8),
-    /// using efficient buffered formatting.
-    /// This is the mirror operation of [SP3::from_file]
+    /// Dumps [SP3] into writable local file, using efficient buffered formatting.
+    /// The content is compressed when the file extension is a known [FileCompression]
+    /// (`.gz`, `.Z`, `.bz2` or `.zst`), otherwise it is written as readable ASCII UTF-8.
+    /// This is the mirror operation of [SP3::from_path]
     /// ```
     /// use sp3::prelude::*;
     ///
     /// let sp3 = SP3::from_file("data/SP3/C/co108870.sp3").unwrap();
     ///
     /// assert!(sp3.to_file("output.sp3").is_ok());
+    ///
+    /// // compressed on the fly
+    /// assert!(sp3.to_file("output.sp3.Z").is_ok());
+    ///
+    /// let parsed = SP3::from_path("output.sp3.Z")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(parsed.data, sp3.data);
     /// ```
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), FormattingError> {
-        let fd = File::create(path)?;
-        let mut writer = BufWriter::new(fd);
-        self.format(&mut writer)?;
+        match FileCompression::from_path(path.as_ref()) {
+            None => {
+                let mut writer = BufWriter::new(File::create(path)?);
+                self.format(&mut writer)?;
+            },
+            Some(FileCompression::Unix) => {
+                let mut writer = BufWriter::new(Vec::new());
+                self.format(&mut writer)?;
+
+                let content = writer.into_inner().map_err(|e| e.into_error())?;
+
+                let mut fd = File::create(path)?;
+                fd.write_all(&lzw::compress(&content))?;
+            },
+            #[cfg(feature = "flate2")]
+            Some(FileCompression::Gzip) => {
+                let encoder = GzEncoder::new(File::create(path)?, GzCompression::new(5));
+                let mut writer = BufWriter::new(encoder);
+                self.format(&mut writer)?;
+                writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+            },
+            #[cfg(feature = "bzip2")]
+            Some(FileCompression::Bzip2) => {
+                let encoder = BzEncoder::new(File::create(path)?, BzCompression::default());
+                let mut writer = BufWriter::new(encoder);
+                self.format(&mut writer)?;
+                writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+            },
+            #[cfg(feature = "zstd")]
+            Some(FileCompression::Zstd) => {
+                let encoder = ZstdEncoder::new(File::create(path)?, 0)?;
+                let mut writer = BufWriter::new(encoder);
+                self.format(&mut writer)?;
+                writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+            },
+            #[allow(unreachable_patterns)]
+            Some(compression) => {
+                return Err(FormattingError::UnsupportedCompression(compression));
+            },
+        }
         Ok(())
     }
 
@@ -162,7 +238,53 @@ impl SP3 {
 
 #[cfg(test)]
 mod test {
-    use crate::prelude::SP3;
+    use crate::prelude::{Duration, Epoch, SP3Builder, SP3Entry, SP3, SV};
+    use std::io::{BufReader, BufWriter};
+    use std::str::FromStr;
+
+    #[test]
+    fn sp3_std_dev_formatting() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+
+        let mut builder = SP3Builder::default().with_agency("TEST");
+
+        for i in 0..4 {
+            // 1.25^10, 1.25^9, 1.25^11 mm and 1.25^20 x 10⁻⁴ mm/s
+            let entry = SP3Entry::from_position_velocity_km_km_s((1.0, 2.0, 3.0), (0.1, 0.2, 0.3))
+                .with_clock_offset_us(1.0)
+                .with_position_std_dev_mm((9.3132, 7.4506, 11.6415))
+                .with_velocity_std_dev_mm_s((8.6736E-3, 8.6736E-3, 8.6736E-3));
+
+            builder.push(t0 + dt * i as f64, g01, entry);
+        }
+
+        let sp3 = builder.build().unwrap();
+        assert_eq!(sp3.header.position_velocity_base, 0.0);
+
+        let mut buffer = BufWriter::new(Vec::<u8>::new());
+        sp3.format(&mut buffer).unwrap();
+
+        let content = buffer.into_inner().unwrap();
+        let formatted = String::from_utf8(content.clone()).unwrap();
+
+        assert!(formatted.contains("%f  1.2500000"));
+        assert!(formatted.contains("    1.000000 10  9 11"));
+
+        let parsed = SP3::from_reader(&mut BufReader::new(content.as_slice())).unwrap();
+        assert_eq!(parsed.header.position_velocity_base, 1.25);
+
+        for entry in parsed.data.values() {
+            let (x_mm, y_mm, z_mm) = entry.position_std_dev_mm.unwrap();
+            assert!((x_mm - 1.25_f64.powi(10)).abs() < 1.0E-9);
+            assert!((y_mm - 1.25_f64.powi(9)).abs() < 1.0E-9);
+            assert!((z_mm - 1.25_f64.powi(11)).abs() < 1.0E-9);
+
+            let (vx_mm_s, _, _) = entry.velocity_std_dev_mm_s.unwrap();
+            assert!((vx_mm_s - 1.25_f64.powi(20) * 1.0E-4).abs() < 1.0E-9);
+        }
+    }
 
     #[test]
     fn sp3_c_formatting() {
@@ -0,0 +1,348 @@
+//! Terrestrial reference frames
+use crate::prelude::{Epoch, Error, HelmertTransform, ParsingError, SP3};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Terrestrial [ReferenceFrame]s that we support.
+/// IGS realizations are aligned to their ITRF counterpart,
+/// and we consider them equivalent to it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReferenceFrame {
+    ITRF88,
+    ITRF89,
+    ITRF90,
+    ITRF91,
+    ITRF92,
+    ITRF93,
+    ITRF94,
+    ITRF96,
+    ITRF97,
+    ITRF2000,
+    ITRF2005,
+    ITRF2008,
+    ITRF2014,
+    ITRF2020,
+    /// IGS realization of [ReferenceFrame::ITRF97]
+    IGS97,
+    /// IGS realization of [ReferenceFrame::ITRF2000]
+    IGS00,
+    /// Updated IGS realization of [ReferenceFrame::ITRF2000]
+    IGb00,
+    /// IGS realization of [ReferenceFrame::ITRF2005]
+    IGS05,
+    /// IGS realization of [ReferenceFrame::ITRF2008]
+    IGS08,
+    /// Updated IGS realization of [ReferenceFrame::ITRF2008]
+    IGb08,
+    /// IGS realization of [ReferenceFrame::ITRF2014]
+    IGS14,
+    /// Updated IGS realization of [ReferenceFrame::ITRF2014]
+    IGb14,
+    /// IGS realization of [ReferenceFrame::ITRF2020]
+    IGS20,
+}
+
+impl std::fmt::Display for ReferenceFrame {
+    /// Formats [ReferenceFrame] as 5 character SP3 header label.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::ITRF88 => write!(f, "ITR88"),
+            Self::ITRF89 => write!(f, "ITR89"),
+            Self::ITRF90 => write!(f, "ITR90"),
+            Self::ITRF91 => write!(f, "ITR91"),
+            Self::ITRF92 => write!(f, "ITR92"),
+            Self::ITRF93 => write!(f, "ITR93"),
+            Self::ITRF94 => write!(f, "ITR94"),
+            Self::ITRF96 => write!(f, "ITR96"),
+            Self::ITRF97 => write!(f, "ITR97"),
+            Self::ITRF2000 => write!(f, "ITR00"),
+            Self::ITRF2005 => write!(f, "ITR05"),
+            Self::ITRF2008 => write!(f, "ITR08"),
+            Self::ITRF2014 => write!(f, "ITR14"),
+            Self::ITRF2020 => write!(f, "ITR20"),
+            Self::IGS97 => write!(f, "IGS97"),
+            Self::IGS00 => write!(f, "IGS00"),
+            Self::IGb00 => write!(f, "IGb00"),
+            Self::IGS05 => write!(f, "IGS05"),
+            Self::IGS08 => write!(f, "IGS08"),
+            Self::IGb08 => write!(f, "IGb08"),
+            Self::IGS14 => write!(f, "IGS14"),
+            Self::IGb14 => write!(f, "IGb14"),
+            Self::IGS20 => write!(f, "IGS20"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReferenceFrame {
+    type Err = ParsingError;
+
+    /// Parses [ReferenceFrame] from SP3 header label (like "ITR97" or "IGS14")
+    /// or complete name (like "ITRF93" or "ITRF2014").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+
+        match trimmed {
+            "IGb00" | "IGB00" => return Ok(Self::IGb00),
+            "IGb08" | "IGB08" => return Ok(Self::IGb08),
+            "IGb14" | "IGB14" => return Ok(Self::IGb14),
+            _ => {},
+        }
+
+        let upper = trimmed.to_uppercase();
+
+        if let Some(year) = upper.strip_prefix("IGS") {
+            return match year {
+                "97" => Ok(Self::IGS97),
+                "00" => Ok(Self::IGS00),
+                "05" => Ok(Self::IGS05),
+                "08" => Ok(Self::IGS08),
+                "14" => Ok(Self::IGS14),
+                "20" => Ok(Self::IGS20),
+                _ => Err(ParsingError::UnknownReferenceFrame(trimmed.to_string())),
+            };
+        }
+
+        let year = upper
+            .strip_prefix("ITRF")
+            .or_else(|| upper.strip_prefix("ITR"))
+            .ok_or_else(|| ParsingError::UnknownReferenceFrame(trimmed.to_string()))?;
+
+        match year {
+            "88" => Ok(Self::ITRF88),
+            "89" => Ok(Self::ITRF89),
+            "90" => Ok(Self::ITRF90),
+            "91" => Ok(Self::ITRF91),
+            "92" => Ok(Self::ITRF92),
+            "93" => Ok(Self::ITRF93),
+            "94" => Ok(Self::ITRF94),
+            "96" => Ok(Self::ITRF96),
+            "97" => Ok(Self::ITRF97),
+            "00" | "2000" => Ok(Self::ITRF2000),
+            "05" | "2005" => Ok(Self::ITRF2005),
+            "08" | "2008" => Ok(Self::ITRF2008),
+            "14" | "2014" => Ok(Self::ITRF2014),
+            "20" | "2020" => Ok(Self::ITRF2020),
+            _ => Err(ParsingError::UnknownReferenceFrame(trimmed.to_string())),
+        }
+    }
+}
+
+impl ReferenceFrame {
+    /// Returns the ITRF realization this [ReferenceFrame] is aligned to.
+    pub fn itrf(&self) -> Self {
+        match self {
+            Self::IGS97 => Self::ITRF97,
+            Self::IGS00 | Self::IGb00 => Self::ITRF2000,
+            Self::IGS05 => Self::ITRF2005,
+            Self::IGS08 | Self::IGb08 => Self::ITRF2008,
+            Self::IGS14 | Self::IGb14 => Self::ITRF2014,
+            Self::IGS20 => Self::ITRF2020,
+            itrf => *itrf,
+        }
+    }
+
+    /// Returns the published 14-parameter [HelmertTransform] from ITRF2020
+    /// to this [ReferenceFrame] (IERS, parameters referenced to epoch 2015.0).
+    pub fn itrf2020_transform(&self) -> HelmertTransform {
+        // (T mm, D ppb, R mas), (T rates mm/y, D rate ppb/y, R rates mas/y)
+        let ((t, d, r), (t_dot, d_dot, r_dot)) = match self.itrf() {
+            Self::ITRF2014 => (
+                ((-1.4, -0.9, 1.4), -0.42, (0.0, 0.0, 0.0)),
+                ((0.0, -0.1, 0.2), 0.0, (0.0, 0.0, 0.0)),
+            ),
+            Self::ITRF2008 => (
+                ((0.2, 1.0, 3.3), -0.29, (0.0, 0.0, 0.0)),
+                ((0.0, -0.1, 0.1), 0.03, (0.0, 0.0, 0.0)),
+            ),
+            Self::ITRF2005 => (
+                ((2.7, 0.1, -1.4), 0.65, (0.0, 0.0, 0.0)),
+                ((0.3, -0.1, 0.1), 0.03, (0.0, 0.0, 0.0)),
+            ),
+            Self::ITRF2000 => (
+                ((-0.2, 0.8, -34.2), 2.25, (0.0, 0.0, 0.0)),
+                ((0.1, 0.0, -1.7), 0.11, (0.0, 0.0, 0.0)),
+            ),
+            Self::ITRF97 | Self::ITRF96 | Self::ITRF94 => (
+                ((6.5, -3.9, -77.9), 3.98, (0.0, 0.0, 0.36)),
+                ((0.1, -0.6, -3.1), 0.12, (0.0, 0.0, 0.02)),
+            ),
+            Self::ITRF93 => (
+                ((-65.8, 1.9, -71.3), 4.47, (-3.36, -4.33, 0.75)),
+                ((-2.8, -0.2, -2.3), 0.12, (-0.11, -0.19, 0.07)),
+            ),
+            Self::ITRF92 => (
+                ((14.5, -1.9, -85.9), 3.27, (0.0, 0.0, 0.36)),
+                ((0.1, -0.6, -3.1), 0.12, (0.0, 0.0, 0.02)),
+            ),
+            Self::ITRF91 => (
+                ((26.5, 12.1, -91.9), 4.67, (0.0, 0.0, 0.36)),
+                ((0.1, -0.6, -3.1), 0.12, (0.0, 0.0, 0.02)),
+            ),
+            Self::ITRF90 => (
+                ((24.5, 8.1, -107.9), 4.97, (0.0, 0.0, 0.36)),
+                ((0.1, -0.6, -3.1), 0.12, (0.0, 0.0, 0.02)),
+            ),
+            Self::ITRF89 => (
+                ((29.5, 32.1, -145.9), 8.37, (0.0, 0.0, 0.36)),
+                ((0.1, -0.6, -3.1), 0.12, (0.0, 0.0, 0.02)),
+            ),
+            Self::ITRF88 => (
+                ((24.5, -3.9, -169.9), 11.47, (0.1, 0.0, 0.36)),
+                ((0.1, -0.6, -3.1), 0.12, (0.0, 0.0, 0.02)),
+            ),
+            _ => Default::default(), // ITRF2020
+        };
+
+        HelmertTransform::from_seven_parameters(t, d, r).with_rates(
+            t_dot,
+            d_dot,
+            r_dot,
+            Epoch::from_gregorian_utc_at_midnight(2015, 1, 1),
+        )
+    }
+
+    /// Returns the 14-parameter [HelmertTransform] from this [ReferenceFrame]
+    /// to `target` [ReferenceFrame], deduced from the ITRF2020 table.
+    /// The transformation is null between equivalent realizations.
+    pub fn transform_to(&self, target: Self) -> HelmertTransform {
+        self.itrf2020_transform()
+            .inverse()
+            .then(&target.itrf2020_transform())
+    }
+}
+
+impl SP3 {
+    /// Returns the [ReferenceFrame] this [SP3] is expressed in,
+    /// as described by [Header::coord_system](crate::prelude::Header).
+    pub fn reference_frame(&self) -> Result<ReferenceFrame, ParsingError> {
+        self.header.coord_system.parse()
+    }
+
+    /// Transforms all positions and velocities of this [SP3] to `target` [ReferenceFrame],
+    /// using the published ITRF transformation parameters, propagated
+    /// to the [Epoch] of each record. The header coordinates system is updated.
+    /// This is typically needed prior `Merge` or comparison of products
+    /// expressed in different frames.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_file("data/SP3/D/example.txt")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(sp3.reference_frame().unwrap(), ReferenceFrame::IGS14);
+    ///
+    /// let itrf2020 = sp3.to_reference_frame(ReferenceFrame::ITRF2020)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(itrf2020.header.coord_system, "ITR20");
+    /// ```
+    pub fn to_reference_frame(&self, target: ReferenceFrame) -> Result<Self, Error> {
+        let mut s = self.clone();
+        s.to_reference_frame_mut(target)?;
+        Ok(s)
+    }
+
+    /// Transforms this [SP3] to `target` [ReferenceFrame], with mutable access.
+    /// See [Self::to_reference_frame].
+    pub fn to_reference_frame_mut(&mut self, target: ReferenceFrame) -> Result<(), Error> {
+        let source = self.reference_frame()?;
+
+        if source.itrf() != target.itrf() {
+            self.helmert_transform_mut(&source.transform_to(target));
+        }
+
+        self.header.coord_system = target.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReferenceFrame;
+    use crate::prelude::{Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn reference_frame_parsing() {
+        for (desc, expected) in [
+            ("ITRF93", ReferenceFrame::ITRF93),
+            ("ITR97", ReferenceFrame::ITRF97),
+            ("ITRF2014", ReferenceFrame::ITRF2014),
+            ("IGS14", ReferenceFrame::IGS14),
+            ("IGb08", ReferenceFrame::IGb08),
+            ("IGS20 ", ReferenceFrame::IGS20),
+        ] {
+            let frame = ReferenceFrame::from_str(desc).unwrap();
+            assert_eq!(frame, expected);
+
+            let formatted = frame.to_string();
+            assert_eq!(formatted.len(), 5);
+            assert_eq!(ReferenceFrame::from_str(&formatted).unwrap(), frame);
+        }
+
+        assert_eq!(ReferenceFrame::IGb14.itrf(), ReferenceFrame::ITRF2014);
+
+        for desc in ["ITRF2", "WGS84", "IGS99"] {
+            assert!(ReferenceFrame::from_str(desc).is_err());
+        }
+    }
+
+    #[test]
+    fn reference_frame_transformation() {
+        let t0 = Epoch::from_str("2015-01-01T00:00:00 UTC").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let position_km = (10_000.0, 20_000.0, -5_000.0);
+
+        let mut sp3 = SP3::default();
+        sp3.header.coord_system = "IGS20".to_string();
+        sp3.data.insert(
+            SP3Key { sv: g01, epoch: t0 },
+            SP3Entry::from_position_km(position_km),
+        );
+
+        // missing data
+        let null = SP3Key {
+            sv: SV::from_str("G02").unwrap(),
+            epoch: t0,
+        };
+
+        sp3.data
+            .insert(null.clone(), SP3Entry::from_position_km((0.0, 0.0, 0.0)));
+
+        let itrf2014 = sp3.to_reference_frame(ReferenceFrame::ITRF2014).unwrap();
+        assert_eq!(itrf2014.header.coord_system, "ITR14");
+
+        // null positions are preserved
+        assert_eq!(
+            itrf2014.data.get(&null).unwrap().position_km,
+            (0.0, 0.0, 0.0)
+        );
+
+        let transformed = itrf2014.data.values().next().unwrap().position_km;
+
+        // T + D.X at reference epoch (no rotation)
+        let d = -0.42E-9;
+        assert!((transformed.0 - position_km.0 * (1.0 + d) + 1.4E-6).abs() < 1.0E-9);
+        assert!((transformed.1 - position_km.1 * (1.0 + d) + 0.9E-6).abs() < 1.0E-9);
+        assert!((transformed.2 - position_km.2 * (1.0 + d) - 1.4E-6).abs() < 1.0E-9);
+
+        // round trip
+        let back = itrf2014.to_reference_frame(ReferenceFrame::IGS20).unwrap();
+        assert_eq!(back.header.coord_system, "IGS20");
+
+        let back = back.data.values().next().unwrap().position_km;
+        assert!((back.0 - position_km.0).abs() < 1.0E-9);
+        assert!((back.1 - position_km.1).abs() < 1.0E-9);
+        assert!((back.2 - position_km.2).abs() < 1.0E-9);
+
+        // equivalent realizations
+        let igs14 = itrf2014.to_reference_frame(ReferenceFrame::IGS14).unwrap();
+        assert_eq!(igs14.data, itrf2014.data);
+
+        // unknown frame
+        sp3.header.coord_system = "ITRF2".to_string();
+        assert!(sp3.to_reference_frame(ReferenceFrame::ITRF2014).is_err());
+    }
+}
@@ -2,13 +2,15 @@
 pub(crate) mod line1;
 pub(crate) mod line2;
 
+pub mod time_system;
+
 use std::io::{BufWriter, Write};
 
 pub mod version;
 
 use crate::{
     errors::FormattingError,
-    header::version::Version,
+    header::{time_system::TimeSystem, version::Version},
     prelude::{Constellation, Duration, Epoch, ParsingError, TimeScale, SV},
 };
 
@@ -146,7 +148,12 @@ pub struct Header {
     /// [TimeScale] that applies to all following [Epoch]s.
     pub timescale: TimeScale,
 
-    /// Total elapsed weeks in [TimeScale].
+    /// [TimeSystem] descriptor. It is only formatted when it describes [Self::timescale],
+    /// otherwise the default [TimeSystem] of [Self::timescale] is used.
+    pub time_system: TimeSystem,
+
+    /// Total elapsed weeks in [TimeScale]. GNSS [TimeScale]s count weeks
+    /// from their own origin, other [TimeScale]s from the GPS week origin.
     pub week: u32,
 
     /// Total number of nanoseconds in current week.
@@ -161,6 +168,14 @@ pub struct Header {
     /// Sampling period, as [Duration].
     pub sampling_period: Duration,
 
+    /// Base of the position and velocity standard deviation exponents
+    /// (first `%f` line). 0.0 when not defined.
+    pub position_velocity_base: f64,
+
+    /// Base of the clock and clock rate standard deviation exponents
+    /// (first `%f` line). 0.0 when not defined.
+    pub clock_base: f64,
+
     /// [SV] to be found in this record.
     pub satellites: Vec<SV>,
 }
@@ -192,20 +207,18 @@ impl Header {
         line2.format(writer)?;
         writeln!(writer)?;
 
-        // file descriptor support is incomplete
-        let gnss_timescale = match self.timescale {
-            TimeScale::GPST => "GPS",
-            TimeScale::GST => "GAL",
-            TimeScale::QZSST => "QZS",
-            TimeScale::UTC => "UTC",
-            _ => "TAI",
+        // the declared descriptor is only preserved when it describes the timescale
+        let time_system = if self.time_system.timescale() == self.timescale {
+            self.time_system
+        } else {
+            TimeSystem::from_timescale(self.timescale).unwrap_or(TimeSystem::TAI)
         };
 
         // TODO: `L` exists here in case only LEO vehicles are to be found
         writeln!(
             writer,
             "%c {:x}  cc {} ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc",
-            self.constellation, gnss_timescale,
+            self.constellation, time_system,
         )?;
 
         writeln!(
@@ -213,9 +226,24 @@ impl Header {
             "%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc"
         )?;
 
+        writeln!(
+            writer,
+            "%f {:10.7} {:12.9}  0.00000000000  0.000000000000000",
+            self.position_velocity_base, self.clock_base,
+        )?;
+
+        writeln!(
+            writer,
+            "%f  0.0000000  0.000000000  0.00000000000  0.000000000000000"
+        )?;
+
         // other fields are not supported
-        // %i
-        // %f
+        for _ in 0..2 {
+            writeln!(
+                writer,
+                "%i    0    0    0    0      0      0      0      0         0"
+            )?;
+        }
 
         Ok(())
     }
@@ -224,7 +252,8 @@ impl Header {
 #[cfg(test)]
 mod test {
     use crate::prelude::{
-        Constellation, DataType, Duration, Epoch, Header, OrbitType, TimeScale, Version, SV,
+        Constellation, DataType, Duration, Epoch, Header, OrbitType, TimeScale, TimeSystem,
+        Version, SV,
     };
     use crate::tests::formatting::Utf8Buffer;
 
@@ -245,11 +274,14 @@ mod test {
             agency: "GRGS".to_string(),
             constellation: Constellation::GPS,
             timescale: TimeScale::GPST,
+            time_system: TimeSystem::GPS,
             week: 1234,
             week_nanos: 5678,
             mjd: 12,
             mjd_fraction: 0.123,
             sampling_period: Duration::from_seconds(900.0),
+            position_velocity_base: 1.25,
+            clock_base: 1.025,
             satellites: "G01,G02,G03,G04,G05"
                 .split(',')
                 .map(|s| SV::from_str(s).unwrap())
@@ -270,7 +302,11 @@ mod test {
             "#cP2019 12 31 23 59 42.00000000      10 __u+U ITRF93 FIT  GRGS
 ## 1234      0.00000567   900.00000000 00012 33999999.0000000999999
 %c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
-%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc\n"
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%f  1.2500000  1.025000000  0.00000000000  0.000000000000000
+%f  0.0000000  0.000000000  0.00000000000  0.000000000000000
+%i    0    0    0    0      0      0      0      0         0
+%i    0    0    0    0      0      0      0      0         0\n"
         );
     }
 }
@@ -0,0 +1,199 @@
+//! SP3 time system descriptor
+use crate::prelude::{Duration, Epoch, ParsingError, TimeScale};
+
+use hifitime::Unit;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// MJD of J2000, used as reference when expressing MJDs in a [TimeScale]
+const MJD_J2000: f64 = 51_544.0;
+
+/// One week, in nanoseconds
+const WEEK_NANOS: i128 = 7 * 86_400 * 1_000_000_000;
+
+/// [TimeSystem] descriptor, as declared in the first `%c` line.
+/// Each [TimeSystem] is expressed in a [TimeScale], yet GLONASS time and UTC
+/// (respectively NavIC time and Galileo time) share the same [TimeScale]:
+/// the descriptor is preserved so files are written back as they were read.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimeSystem {
+    /// GPS time
+    #[default]
+    GPS,
+    /// GLONASS time, expressed as UTC(SU): subject to leap seconds
+    GLO,
+    /// Galileo System Time
+    GAL,
+    /// QZSS time
+    QZS,
+    /// BeiDou time
+    BDT,
+    /// IRNSS/NavIC time, aligned to Galileo System Time
+    IRN,
+    /// International Atomic Time
+    TAI,
+    /// Coordinated Universal Time: subject to leap seconds
+    UTC,
+}
+
+impl TimeSystem {
+    /// [TimeScale] in which [Epoch]s of this [TimeSystem] are expressed.
+    pub fn timescale(&self) -> TimeScale {
+        match self {
+            Self::GPS => TimeScale::GPST,
+            Self::GAL | Self::IRN => TimeScale::GST,
+            Self::QZS => TimeScale::QZSST,
+            Self::BDT => TimeScale::BDT,
+            Self::TAI => TimeScale::TAI,
+            Self::GLO | Self::UTC => TimeScale::UTC,
+        }
+    }
+
+    /// Returns the [TimeSystem] that describes this [TimeScale].
+    /// [TimeScale]s that SP3 cannot describe return None.
+    pub fn from_timescale(timescale: TimeScale) -> Option<Self> {
+        match timescale {
+            TimeScale::GPST => Some(Self::GPS),
+            TimeScale::GST => Some(Self::GAL),
+            TimeScale::QZSST => Some(Self::QZS),
+            TimeScale::BDT => Some(Self::BDT),
+            TimeScale::TAI => Some(Self::TAI),
+            TimeScale::UTC => Some(Self::UTC),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TimeSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::GPS => write!(f, "GPS"),
+            Self::GLO => write!(f, "GLO"),
+            Self::GAL => write!(f, "GAL"),
+            Self::QZS => write!(f, "QZS"),
+            Self::BDT => write!(f, "BDT"),
+            Self::IRN => write!(f, "IRN"),
+            Self::TAI => write!(f, "TAI"),
+            Self::UTC => write!(f, "UTC"),
+        }
+    }
+}
+
+impl std::str::FromStr for TimeSystem {
+    type Err = ParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "GPS" => Ok(Self::GPS),
+            "GLO" => Ok(Self::GLO),
+            "GAL" => Ok(Self::GAL),
+            "QZS" => Ok(Self::QZS),
+            "BDT" => Ok(Self::BDT),
+            "IRN" => Ok(Self::IRN),
+            "TAI" => Ok(Self::TAI),
+            "UTC" => Ok(Self::UTC),
+            _ => Err(ParsingError::UnknownTimeSystem(s.trim().to_string())),
+        }
+    }
+}
+
+/// Origin of the week counter in this [TimeScale]: the native week origin of GNSS
+/// [TimeScale]s, the GPS week origin (expressed in this [TimeScale]) otherwise.
+fn week_origin(timescale: TimeScale) -> Epoch {
+    match timescale {
+        TimeScale::GST => Epoch::from_gregorian_at_midnight(1999, 8, 22, timescale),
+        TimeScale::BDT => Epoch::from_gregorian_at_midnight(2006, 1, 1, timescale),
+        timescale => Epoch::from_gregorian_at_midnight(1980, 1, 6, timescale),
+    }
+}
+
+/// Interval between these [Epoch]s, counted by the clock of the [TimeScale]
+/// of `start`. This differs from `end - start` when a leap second is introduced
+/// in between, for UTC based [TimeScale]s.
+pub(crate) fn clock_interval(start: Epoch, end: Epoch) -> Duration {
+    let timescale = start.time_scale;
+    end.to_duration_in_time_scale(timescale) - start.to_duration_in_time_scale(timescale)
+}
+
+/// Expresses this [Epoch] as (week, nanoseconds of week) in [TimeScale]
+pub(crate) fn week_counter(t: Epoch, timescale: TimeScale) -> (u32, u64) {
+    let origin = week_origin(timescale);
+    let nanos = clock_interval(origin, t.to_time_scale(timescale)).total_nanoseconds();
+
+    (
+        nanos.div_euclid(WEEK_NANOS) as u32,
+        nanos.rem_euclid(WEEK_NANOS) as u64,
+    )
+}
+
+/// Returns the [Epoch] described by this week counter in [TimeScale]
+pub(crate) fn from_week_counter(week: u32, nanos: u64, timescale: TimeScale) -> Epoch {
+    let origin = week_origin(timescale);
+
+    let elapsed = Duration::from_total_nanoseconds(week as i128 * WEEK_NANOS + nanos as i128);
+
+    Epoch::from_duration(
+        origin.to_duration_in_time_scale(timescale) + elapsed,
+        timescale,
+    )
+}
+
+/// Expresses this [Epoch] as MJD (including day fraction) in [TimeScale]
+pub(crate) fn mjd_days(t: Epoch, timescale: TimeScale) -> f64 {
+    match timescale {
+        TimeScale::UTC => t.to_mjd_utc_days(),
+        timescale => {
+            let reference = Epoch::from_mjd_in_time_scale(MJD_J2000, timescale);
+            MJD_J2000 + (t - reference).to_unit(Unit::Day)
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_week_counter, mjd_days, week_counter, TimeSystem};
+    use crate::prelude::{Epoch, TimeScale};
+    use std::str::FromStr;
+
+    #[test]
+    fn time_system_counters() {
+        // Thursday 2020-06-25: GPS week 2111
+        let four_days_nanos = 4 * 86_400 * 1_000_000_000;
+
+        for (descriptor, week) in [
+            ("GPS", 2111),
+            ("QZS", 2111),
+            ("GAL", 2111 - 1024),
+            ("IRN", 2111 - 1024),
+            ("BDT", 2111 - 1356),
+            ("UTC", 2111),
+            ("GLO", 2111),
+            ("TAI", 2111),
+        ] {
+            let time_system = TimeSystem::from_str(descriptor).unwrap();
+            assert_eq!(time_system.to_string(), descriptor);
+
+            let timescale = time_system.timescale();
+            let t = Epoch::from_gregorian_at_midnight(2020, 6, 25, timescale);
+
+            assert_eq!(
+                week_counter(t, timescale),
+                (week, four_days_nanos),
+                "{}",
+                descriptor
+            );
+
+            assert_eq!(from_week_counter(week, four_days_nanos, timescale), t);
+            assert!((mjd_days(t, timescale) - 59_025.0).abs() < 1.0E-9);
+        }
+
+        assert_eq!(
+            TimeSystem::from_timescale(TimeScale::GST),
+            Some(TimeSystem::GAL)
+        );
+        assert_eq!(TimeSystem::from_timescale(TimeScale::TT), None);
+        assert!(TimeSystem::from_str("GPST").is_err());
+    }
+}
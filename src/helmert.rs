@@ -0,0 +1,336 @@
+//! Helmert (similarity) transformation
+use crate::{
+    prelude::{Epoch, SP3},
+    Vector3D,
+};
+
+use hifitime::Unit;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Milliarcsecond to radians conversion factor
+pub(crate) const MAS_TO_RAD: f64 = std::f64::consts::PI / 180.0 / 3600.0 / 1000.0;
+
+/// [HelmertTransform] describes the 14-parameter similarity transformation
+/// between two terrestrial reference frames, following the IERS conventions:
+///
+/// X2 = X1 + T + D.X1 + R.X1
+///
+/// where R is the (small angle) rotation matrix:
+///
+/// |  0  -R3  R2 |
+/// |  R3  0  -R1 |
+/// | -R2  R1  0  |
+///
+/// Each parameter P is propagated linearly from the reference epoch t0:
+/// P(t) = P(t0) + Ṗ.(t - t0). Null rates describe a 7-parameter transformation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HelmertTransform {
+    /// Translation (Tx, Ty, Tz) in millimeters
+    pub translation_mm: Vector3D,
+
+    /// Scale factor D, in parts per billion
+    pub scale_ppb: f64,
+
+    /// Rotation angles (R1, R2, R3) in milliarcseconds
+    pub rotation_mas: Vector3D,
+
+    /// Translation rates in millimeters per year
+    pub translation_rate_mm_yr: Vector3D,
+
+    /// Scale rate in parts per billion per year
+    pub scale_rate_ppb_yr: f64,
+
+    /// Rotation rates in milliarcseconds per year
+    pub rotation_rate_mas_yr: Vector3D,
+
+    /// Reference [Epoch] of the parameters
+    pub reference_epoch: Epoch,
+}
+
+impl Default for HelmertTransform {
+    /// Builds the identity [HelmertTransform], referenced to J2000.
+    fn default() -> Self {
+        Self {
+            translation_mm: Default::default(),
+            scale_ppb: Default::default(),
+            rotation_mas: Default::default(),
+            translation_rate_mm_yr: Default::default(),
+            scale_rate_ppb_yr: Default::default(),
+            rotation_rate_mas_yr: Default::default(),
+            reference_epoch: Epoch::from_gregorian_utc_at_midnight(2000, 1, 1),
+        }
+    }
+}
+
+impl HelmertTransform {
+    /// Builds a 7-parameter [HelmertTransform], valid at any [Epoch].
+    pub fn from_seven_parameters(
+        translation_mm: Vector3D,
+        scale_ppb: f64,
+        rotation_mas: Vector3D,
+    ) -> Self {
+        Self {
+            translation_mm,
+            scale_ppb,
+            rotation_mas,
+            ..Default::default()
+        }
+    }
+
+    /// Copies and returns [HelmertTransform] with desired rates, referenced to [Epoch].
+    pub fn with_rates(
+        &self,
+        translation_rate_mm_yr: Vector3D,
+        scale_rate_ppb_yr: f64,
+        rotation_rate_mas_yr: Vector3D,
+        reference_epoch: Epoch,
+    ) -> Self {
+        let mut s = *self;
+        s.translation_rate_mm_yr = translation_rate_mm_yr;
+        s.scale_rate_ppb_yr = scale_rate_ppb_yr;
+        s.rotation_rate_mas_yr = rotation_rate_mas_yr;
+        s.reference_epoch = reference_epoch;
+        s
+    }
+
+    /// Returns true if this [HelmertTransform] has non null rates.
+    pub fn has_rates(&self) -> bool {
+        self.translation_rate_mm_yr != (0.0, 0.0, 0.0)
+            || self.scale_rate_ppb_yr != 0.0
+            || self.rotation_rate_mas_yr != (0.0, 0.0, 0.0)
+    }
+
+    /// Returns the inverse [HelmertTransform], to first order
+    /// (all parameters and rates negated).
+    pub fn inverse(&self) -> Self {
+        let neg = |v: Vector3D| (-v.0, -v.1, -v.2);
+        Self {
+            translation_mm: neg(self.translation_mm),
+            scale_ppb: -self.scale_ppb,
+            rotation_mas: neg(self.rotation_mas),
+            translation_rate_mm_yr: neg(self.translation_rate_mm_yr),
+            scale_rate_ppb_yr: -self.scale_rate_ppb_yr,
+            rotation_rate_mas_yr: neg(self.rotation_rate_mas_yr),
+            reference_epoch: self.reference_epoch,
+        }
+    }
+
+    /// Chains two [HelmertTransform]s (self first, then rhs), to first order.
+    /// Parameters of `rhs` are first referenced to our own reference epoch.
+    pub fn then(&self, rhs: &Self) -> Self {
+        let rhs = rhs.at_epoch(self.reference_epoch);
+        let add = |a: Vector3D, b: Vector3D| (a.0 + b.0, a.1 + b.1, a.2 + b.2);
+        Self {
+            translation_mm: add(self.translation_mm, rhs.translation_mm),
+            scale_ppb: self.scale_ppb + rhs.scale_ppb,
+            rotation_mas: add(self.rotation_mas, rhs.rotation_mas),
+            translation_rate_mm_yr: add(self.translation_rate_mm_yr, rhs.translation_rate_mm_yr),
+            scale_rate_ppb_yr: self.scale_rate_ppb_yr + rhs.scale_rate_ppb_yr,
+            rotation_rate_mas_yr: add(self.rotation_rate_mas_yr, rhs.rotation_rate_mas_yr),
+            reference_epoch: self.reference_epoch,
+        }
+    }
+
+    /// Propagates this [HelmertTransform] to desired [Epoch], by
+    /// applying the rates. The returned transform is referenced to `epoch`.
+    pub fn at_epoch(&self, epoch: Epoch) -> Self {
+        let dt_yr = (epoch - self.reference_epoch).to_unit(Unit::Day) / 365.25;
+        let propagate =
+            |p: Vector3D, r: Vector3D| (p.0 + r.0 * dt_yr, p.1 + r.1 * dt_yr, p.2 + r.2 * dt_yr);
+
+        let mut s = *self;
+        s.translation_mm = propagate(self.translation_mm, self.translation_rate_mm_yr);
+        s.scale_ppb = self.scale_ppb + self.scale_rate_ppb_yr * dt_yr;
+        s.rotation_mas = propagate(self.rotation_mas, self.rotation_rate_mas_yr);
+        s.reference_epoch = epoch;
+        s
+    }
+
+    /// Applies the (T, D, R) parameters to `x` (any unit), `unit_mm`
+    /// being the number of millimeters per unit of `x`.
+    fn similarity(
+        x: Vector3D,
+        translation_mm: Vector3D,
+        scale_ppb: f64,
+        rotation_mas: Vector3D,
+        unit_mm: f64,
+    ) -> Vector3D {
+        let d = scale_ppb * 1.0E-9;
+        let (r1, r2, r3) = (
+            rotation_mas.0 * MAS_TO_RAD,
+            rotation_mas.1 * MAS_TO_RAD,
+            rotation_mas.2 * MAS_TO_RAD,
+        );
+        (
+            translation_mm.0 / unit_mm + d * x.0 - r3 * x.1 + r2 * x.2,
+            translation_mm.1 / unit_mm + r3 * x.0 + d * x.1 - r1 * x.2,
+            translation_mm.2 / unit_mm - r2 * x.0 + r1 * x.1 + d * x.2,
+        )
+    }
+
+    /// Transforms position vector (in km) expressed at [Epoch].
+    pub fn transform_position_km(&self, position_km: Vector3D, epoch: Epoch) -> Vector3D {
+        let t = self.at_epoch(epoch);
+        let dx = Self::similarity(
+            position_km,
+            t.translation_mm,
+            t.scale_ppb,
+            t.rotation_mas,
+            1.0E6,
+        );
+        (
+            position_km.0 + dx.0,
+            position_km.1 + dx.1,
+            position_km.2 + dx.2,
+        )
+    }
+
+    /// Transforms velocity vector (in km.s⁻¹) of a satellite located
+    /// at `position_km` at [Epoch]. The rates contribute to the velocity.
+    pub fn transform_velocity_km_s(
+        &self,
+        position_km: Vector3D,
+        velocity_km_s: Vector3D,
+        epoch: Epoch,
+    ) -> Vector3D {
+        let t = self.at_epoch(epoch);
+        let seconds_per_year = 365.25 * 86400.0;
+
+        let dv = Self::similarity(
+            velocity_km_s,
+            (0.0, 0.0, 0.0),
+            t.scale_ppb,
+            t.rotation_mas,
+            1.0E6,
+        );
+
+        let rates = Self::similarity(
+            position_km,
+            t.translation_rate_mm_yr,
+            t.scale_rate_ppb_yr,
+            t.rotation_rate_mas_yr,
+            1.0E6,
+        );
+
+        (
+            velocity_km_s.0 + dv.0 + rates.0 / seconds_per_year,
+            velocity_km_s.1 + dv.1 + rates.1 / seconds_per_year,
+            velocity_km_s.2 + dv.2 + rates.2 / seconds_per_year,
+        )
+    }
+}
+
+impl SP3 {
+    /// Applies [HelmertTransform] to all positions and velocities of this [SP3].
+    /// Each state vector is transformed at its own [Epoch].
+    /// Null (missing) positions are preserved, as well as their velocities.
+    /// The header is not modified: refer to the reference frame
+    /// transformation methods if you need to update the coordinates system.
+    pub fn helmert_transform(&self, transform: &HelmertTransform) -> Self {
+        let mut s = self.clone();
+        s.helmert_transform_mut(transform);
+        s
+    }
+
+    /// Applies [HelmertTransform] with mutable access. See [Self::helmert_transform].
+    pub fn helmert_transform_mut(&mut self, transform: &HelmertTransform) {
+        for (k, v) in self.data.iter_mut() {
+            if v.position_km == (0.0, 0.0, 0.0) {
+                continue;
+            }
+
+            if let Some(velocity_km_s) = v.velocity_km_s {
+                v.velocity_km_s =
+                    Some(transform.transform_velocity_km_s(v.position_km, velocity_km_s, k.epoch));
+            }
+
+            v.position_km = transform.transform_position_km(v.position_km, k.epoch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HelmertTransform;
+    use crate::prelude::{Epoch, SP3Entry, SP3Key, SP3, SV};
+    use hifitime::Unit;
+    use std::str::FromStr;
+
+    #[test]
+    fn helmert_position_transform() {
+        let t0 = Epoch::from_str("2015-01-01T00:00:00 UTC").unwrap();
+
+        // pure translation
+        let transform =
+            HelmertTransform::from_seven_parameters((1.0, -2.0, 3.0), 0.0, (0.0, 0.0, 0.0));
+        let x = transform.transform_position_km((20_000.0, 10_000.0, 5_000.0), t0);
+        assert!((x.0 - 20_000.000_001).abs() < 1.0E-9);
+        assert!((x.1 - 9_999.999_998).abs() < 1.0E-9);
+        assert!((x.2 - 5_000.000_003).abs() < 1.0E-9);
+
+        // 1 ppb scale on 20000 km is 2 cm
+        let transform =
+            HelmertTransform::from_seven_parameters((0.0, 0.0, 0.0), 1.0, (0.0, 0.0, 0.0));
+        let x = transform.transform_position_km((20_000.0, 0.0, 0.0), t0);
+        assert!((x.0 - 20_000.000_02).abs() < 1.0E-9);
+
+        // rates
+        let transform =
+            HelmertTransform::default().with_rates((10.0, 0.0, 0.0), 0.0, (0.0, 0.0, 0.0), t0);
+        assert!(transform.has_rates());
+
+        let propagated = transform.at_epoch(t0 + 730.5 * Unit::Day);
+        assert!((propagated.translation_mm.0 - 20.0).abs() < 1.0E-9);
+
+        // inverse round trip
+        let transform =
+            HelmertTransform::from_seven_parameters((1.4, 0.9, -1.4), 0.42, (0.1, 0.2, 0.3));
+        let x0 = (-12_000.0, 18_000.0, 15_000.0);
+        let x = transform.transform_position_km(x0, t0);
+        let x = transform.inverse().transform_position_km(x, t0);
+        assert!((x.0 - x0.0).abs() < 1.0E-9);
+        assert!((x.1 - x0.1).abs() < 1.0E-9);
+        assert!((x.2 - x0.2).abs() < 1.0E-9);
+    }
+
+    #[test]
+    fn helmert_null_positions() {
+        let t0 = Epoch::from_str("2015-01-01T00:00:00 UTC").unwrap();
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let mut sp3 = SP3::default();
+
+        sp3.data.insert(
+            SP3Key { sv: g01, epoch: t0 },
+            SP3Entry::from_position_velocity_km_km_s((20_000.0, 0.0, 0.0), (0.0, 3.0, 0.0)),
+        );
+
+        // missing data
+        sp3.data.insert(
+            SP3Key { sv: g02, epoch: t0 },
+            SP3Entry::from_position_velocity_km_km_s((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)),
+        );
+
+        let transform =
+            HelmertTransform::from_seven_parameters((10.0, -20.0, 30.0), 1.0, (0.1, 0.2, 0.3));
+
+        let transformed = sp3.helmert_transform(&transform);
+
+        let g01_entry = transformed
+            .data
+            .get(&SP3Key { sv: g01, epoch: t0 })
+            .unwrap();
+        assert_ne!(g01_entry.position_km, (20_000.0, 0.0, 0.0));
+
+        let g02_entry = transformed
+            .data
+            .get(&SP3Key { sv: g02, epoch: t0 })
+            .unwrap();
+        assert_eq!(g02_entry.position_km, (0.0, 0.0, 0.0));
+        assert_eq!(g02_entry.velocity_km_s, Some((0.0, 0.0, 0.0)));
+    }
+}
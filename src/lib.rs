@@ -22,11 +22,11 @@ use itertools::Itertools;
 extern crate gnss_qc_traits as qc_traits;
 
 use gnss::prelude::{Constellation, SV};
+use header::time_system::clock_interval;
 use hifitime::Epoch;
 use prelude::ProductionAttributes;
-use production::Campaign;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[cfg(feature = "anise")]
 #[cfg_attr(docsrs, doc(cfg(feature = "anise")))]
@@ -47,14 +47,27 @@ mod nyx;
 #[cfg(test)]
 mod tests;
 
+mod antenna;
+mod attitude;
+mod availability;
+mod broadcast;
+mod builder;
+mod catalog;
 mod dynamics;
 mod entry;
 mod errors;
 mod formatting;
+mod frame;
 mod header;
+mod helmert;
+mod lzw;
+mod math;
+mod oem;
 mod parsing;
 mod position;
 mod production;
+mod stitching;
+mod validation;
 mod velocity;
 
 #[cfg(feature = "serde")]
@@ -76,19 +89,56 @@ type Vector3D = (f64, f64, f64);
 
 pub mod prelude {
     pub use crate::{
+        antenna::{AntennaTable, SatelliteAntenna},
+        attitude::{SatelliteAttitude, SatelliteBlock, YawAttitude},
+        availability::{AvailabilityReport, DataArc, DataGap, SatelliteAvailability},
+        broadcast::{BroadcastEphemeris, GlonassEphemeris, KeplerianEphemeris},
+        builder::SP3Builder,
+        catalog::{CatalogEntry, CatalogPreference, SP3Catalog},
         entry::SP3Entry,
-        errors::{Error, FormattingError, ParsingError},
-        header::{version::Version, DataType, Header, OrbitType},
-        production::{Availability, ProductionAttributes, ReleaseDate, ReleasePeriod},
+        errors::{
+            BuildError, CatalogError, Error, FormattingError, OemError, ParsingError,
+            StitchingError,
+        },
+        frame::ReferenceFrame,
+        header::{time_system::TimeSystem, version::Version, DataType, Header, OrbitType},
+        helmert::HelmertTransform,
+        oem::{
+            Oem, OemCovariance, OemEncoding, OemInterpolation, OemMetadata, OemOptions, OemSegment,
+            OemState,
+        },
+        production::{
+            Availability, Campaign, FileCompression, ProductionAttributes, ReleaseDate,
+            ReleasePeriod,
+        },
+        stitching::StitchedOrbit,
+        validation::HeaderInconsistency,
         SP3Key, SP3,
     };
 
     #[cfg(feature = "qc")]
     pub use gnss_qc_traits::{Merge, Timeshift};
 
+    #[cfg(feature = "qc")]
+    pub use crate::qc::{Anomaly, AnomalyDetectionOptions, AnomalyKind, AnomalySeverity};
+
     #[cfg(feature = "processing")]
     pub use gnss_qc_traits::Split;
 
+    #[cfg(feature = "processing")]
+    pub use crate::{
+        math::Statistics,
+        processing::{
+            BoundaryDiscontinuity, BroadcastComparison, BroadcastResidual, BroadcastStatistics,
+            ClockComparison, ClockComparisonOptions, ClockDatum, ClockInterpolation,
+            ClockStability, ClockStabilityOptions, ClockStabilityReport, CombinationContribution,
+            CombinationOptions, CombinationReport, Detrending, GapFill, HelmertEstimate,
+            HelmertEstimationOptions, OrbitComparison, OrbitInterpolation, RTNResidual,
+            RTNStatistics, RepairOptions, RepairReport, ResampledOrbit, ResamplingMethod,
+            SP3MaskItem, StabilityPoint,
+        },
+    };
+
     // Pub re-export
     pub use gnss::prelude::{Constellation, SV};
     pub use hifitime::{Duration, Epoch, TimeScale};
@@ -128,7 +178,7 @@ pub struct SP3 {
     pub data: BTreeMap<SP3Key, SP3Entry>,
 }
 
-use crate::prelude::{Availability, DataType, ReleasePeriod};
+use crate::prelude::{DataType, ReleaseDate};
 
 // Lagrangian interpolator
 pub(crate) fn lagrange_interpolation(
@@ -163,6 +213,42 @@ pub(crate) fn lagrange_interpolation(
     Some(polynomials)
 }
 
+// Derivative of the Lagrangian interpolator passing through all points
+pub(crate) fn lagrange_derivative(t: Epoch, x: &[(Epoch, Vector3D)]) -> Option<Vector3D> {
+    if x.len() < 2 {
+        return None;
+    }
+
+    let mut derivative = Vector3D::default();
+
+    for (i, (t_i, (x_km_i, y_km_i, z_km_i))) in x.iter().enumerate() {
+        let mut dl_i = 0.0_f64;
+
+        for (m, (t_m, _)) in x.iter().enumerate() {
+            if m == i {
+                continue;
+            }
+
+            let mut term = 1.0 / (*t_i - *t_m).to_seconds();
+
+            for (j, (t_j, _)) in x.iter().enumerate() {
+                if j != i && j != m {
+                    term *= (t - *t_j).to_seconds();
+                    term /= (*t_i - *t_j).to_seconds();
+                }
+            }
+
+            dl_i += term;
+        }
+
+        derivative.0 += x_km_i * dl_i;
+        derivative.1 += y_km_i * dl_i;
+        derivative.2 += z_km_i * dl_i;
+    }
+
+    Some(derivative)
+}
+
 // // 2D Linear interpolation
 // pub(crate) fn linear_interpolation(
 //     order: usize,
@@ -186,12 +272,12 @@ pub(crate) fn lagrange_interpolation(
 impl SP3 {
     /// Returns [Epoch] of first entry
     pub fn first_epoch(&self) -> Option<Epoch> {
-        self.epochs_iter().nth(0)
+        self.data.keys().map(|k| k.epoch).min()
     }
 
     /// Returns last [Epoch] to be found in this record.
     pub fn last_epoch(&self) -> Option<Epoch> {
-        self.epochs_iter().last()
+        self.data.keys().map(|k| k.epoch).max()
     }
 
     /// Returns true if this [SP3] has satellites velocity vector
@@ -250,7 +336,7 @@ impl SP3 {
             if now > t {
                 // new epoch
                 if let Some(past_t) = past_t {
-                    if now - past_t != dt {
+                    if clock_interval(past_t, now) != dt {
                         return false;
                     }
                 }
@@ -265,46 +351,27 @@ impl SP3 {
     /// This is particularly useful in the context of sP3 data synthesis
     /// and production. It may also be used to generate a file name
     /// that would follow the conventions, while parsed from a file that did not.
+    /// When [ProductionAttributes] exist, the original file name is preserved,
+    /// whether it follows the long or legacy short convention.
     pub fn standardized_filename(&self) -> String {
-        let mut batch_id = 0;
-        let mut campaign = Campaign::default();
-        let mut avail = Availability::default();
-        let mut release_period = ReleasePeriod::default();
-        let mut agency = self.header.agency[..3].to_string();
-
-        let mut extension = "";
-
         if let Some(attributes) = &self.prod_attributes {
-            batch_id = attributes.batch_id;
-            avail = attributes.availability;
-            campaign = attributes.campaign;
-            release_period = attributes.release_period;
-            agency = attributes.agency.clone();
-            extension = ".gz";
+            return attributes.to_string();
         }
 
-        let (year, doy) = (
-            self.header.release_epoch.year(),
-            self.header.release_epoch.day_of_year() as u16,
-        );
-
-        let doy_padding = if doy < 100 { "00000" } else { "0000" };
-
-        let sampling_period_mins = (self.header.sampling_period.to_seconds() / 60.0).round() as u16;
+        self.default_production_attributes().to_string()
+    }
 
-        format!(
-            "{}{}{}{}_{}{:03}{}_{}_{:02}M_ORB.SP3{}",
-            agency,
-            batch_id,
-            campaign,
-            avail,
-            year,
-            doy as u16,
-            doy_padding,
-            release_period,
-            sampling_period_mins,
-            extension,
-        )
+    /// [ProductionAttributes] derived from the [Header], when the file name is unknown
+    pub(crate) fn default_production_attributes(&self) -> ProductionAttributes {
+        ProductionAttributes {
+            agency: format!("{:_<3}", self.header.agency)
+                .chars()
+                .take(3)
+                .collect(),
+            release_date: ReleaseDate::from(self.header.release_epoch),
+            sampling_period: self.header.sampling_period,
+            ..Default::default()
+        }
     }
 
     /// Returns total number of [Epoch] to be found
@@ -312,9 +379,14 @@ impl SP3 {
         self.epochs_iter().count()
     }
 
-    /// Returns [Epoch] [Iterator]
+    /// Returns unique [Epoch] [Iterator], in chronological order.
+    /// Note that the record is indexed by [SV] first.
     pub fn epochs_iter(&self) -> impl Iterator<Item = Epoch> + '_ {
-        self.data.keys().map(|k| k.epoch).unique()
+        self.data
+            .keys()
+            .map(|k| k.epoch)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
     }
 
     /// Returns a unique [Constellation] iterator
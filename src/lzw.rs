@@ -0,0 +1,261 @@
+//! Unix compress (`.Z`) LZW codec
+use std::collections::HashMap;
+use std::io::{Error as IoError, ErrorKind};
+
+/// `.Z` magic bytes
+pub(crate) const MAGIC: [u8; 2] = [0x1f, 0x9d];
+
+/// Block mode flag: code 256 clears the dictionary
+const BLOCK_MODE: u8 = 0x80;
+
+/// Dictionary clear code, in block mode
+const CLEAR: usize = 256;
+
+/// Initial code width
+const INIT_BITS: usize = 9;
+
+/// Maximal code width we generate
+const MAX_BITS: usize = 16;
+
+fn invalid_data(msg: &str) -> IoError {
+    IoError::new(ErrorKind::InvalidData, format!("lzw: {}", msg))
+}
+
+/// Codes are packed in groups of 8, so whenever the code width changes,
+/// the remaining of the current group is skipped.
+fn align(pos: usize, group_start: usize, n_bits: usize) -> usize {
+    let group = n_bits * 8;
+    group_start + (pos - group_start).div_ceil(group) * group
+}
+
+fn max_code(n_bits: usize, max_bits: usize) -> usize {
+    if n_bits == max_bits {
+        1 << max_bits
+    } else {
+        (1 << n_bits) - 1
+    }
+}
+
+/// Decompresses Unix compress (`.Z`) content
+pub(crate) fn decompress(input: &[u8]) -> Result<Vec<u8>, IoError> {
+    if input.len() < 3 || input[0..2] != MAGIC {
+        return Err(invalid_data("not a .Z stream"));
+    }
+
+    let max_bits = (input[2] & 0x1f) as usize;
+    let block_mode = input[2] & BLOCK_MODE > 0;
+
+    if !(INIT_BITS..=MAX_BITS).contains(&max_bits) {
+        return Err(invalid_data("invalid code width"));
+    }
+
+    let data = &input[3..];
+    let total_bits = data.len() * 8;
+    let max_max_code = 1 << max_bits;
+
+    let mut prefix = vec![0_usize; max_max_code];
+    let mut suffix = vec![0_u8; max_max_code];
+
+    for (code, value) in suffix.iter_mut().enumerate().take(256) {
+        *value = code as u8;
+    }
+
+    let mut n_bits = INIT_BITS;
+    let mut max = max_code(n_bits, max_bits);
+    let mut free_ent = if block_mode { CLEAR + 1 } else { CLEAR };
+
+    let (mut pos, mut group_start) = (0, 0);
+    let mut old_code = Option::<usize>::None;
+    let mut fin_char = 0_u8;
+
+    let mut stack = Vec::<u8>::new();
+    let mut output = Vec::<u8>::with_capacity(input.len() * 4);
+
+    while pos + n_bits <= total_bits {
+        if free_ent > max {
+            pos = align(pos, group_start, n_bits);
+            n_bits += 1;
+            max = max_code(n_bits, max_bits);
+            group_start = pos;
+            continue;
+        }
+
+        let byte = pos / 8;
+        let mut word = 0_u32;
+
+        for i in 0..3 {
+            if let Some(value) = data.get(byte + i) {
+                word |= (*value as u32) << (8 * i);
+            }
+        }
+
+        let code = ((word >> (pos % 8)) & ((1 << n_bits) - 1)) as usize;
+        pos += n_bits;
+
+        let Some(old) = old_code else {
+            if code >= 256 {
+                return Err(invalid_data("invalid first code"));
+            }
+
+            fin_char = code as u8;
+            output.push(fin_char);
+            old_code = Some(code);
+            continue;
+        };
+
+        if code == CLEAR && block_mode {
+            free_ent = CLEAR;
+            pos = align(pos, group_start, n_bits);
+            n_bits = INIT_BITS;
+            max = max_code(n_bits, max_bits);
+            group_start = pos;
+            continue;
+        }
+
+        let mut current = code;
+
+        if current >= free_ent {
+            // KwKwK case
+            if current > free_ent {
+                return Err(invalid_data("corrupt stream"));
+            }
+            stack.push(fin_char);
+            current = old;
+        }
+
+        while current >= 256 {
+            if stack.len() >= max_max_code {
+                return Err(invalid_data("corrupt stream"));
+            }
+            stack.push(suffix[current]);
+            current = prefix[current];
+        }
+
+        fin_char = suffix[current];
+        stack.push(fin_char);
+        output.extend(stack.drain(..).rev());
+
+        if free_ent < max_max_code {
+            prefix[free_ent] = old;
+            suffix[free_ent] = fin_char;
+            free_ent += 1;
+        }
+
+        old_code = Some(code);
+    }
+
+    Ok(output)
+}
+
+/// Bit packer, least significant bits first
+struct BitWriter {
+    bytes: Vec<u8>,
+    pos: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: usize, n_bits: usize) {
+        for bit in 0..n_bits {
+            let byte = (self.pos + bit) / 8;
+
+            if byte == self.bytes.len() {
+                self.bytes.push(0);
+            }
+
+            if (code >> bit) & 0x01 > 0 {
+                self.bytes[byte] |= 1 << ((self.pos + bit) % 8);
+            }
+        }
+        self.pos += n_bits;
+    }
+
+    fn pad(&mut self, pos: usize) {
+        self.pos = pos;
+        self.bytes.resize(pos.div_ceil(8), 0);
+    }
+}
+
+/// Compresses content to Unix compress (`.Z`) format, in block mode,
+/// without dictionary clearing.
+pub(crate) fn compress(input: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter {
+        bytes: vec![MAGIC[0], MAGIC[1], BLOCK_MODE | MAX_BITS as u8],
+        pos: 24,
+    };
+
+    let Some((first, rem)) = input.split_first() else {
+        return writer.bytes;
+    };
+
+    let max_max_code = 1 << MAX_BITS;
+    let mut dictionary = HashMap::<(usize, u8), usize>::new();
+
+    let mut n_bits = INIT_BITS;
+    let mut max = max_code(n_bits, MAX_BITS);
+    let mut free_ent = CLEAR + 1;
+    let mut group_start = writer.pos;
+
+    let mut output = |code: usize, free_ent: usize, writer: &mut BitWriter| {
+        writer.write(code, n_bits);
+
+        if free_ent > max {
+            writer.pad(align(writer.pos, group_start, n_bits));
+            n_bits += 1;
+            max = max_code(n_bits, MAX_BITS);
+            group_start = writer.pos;
+        }
+    };
+
+    let mut ent = *first as usize;
+
+    for c in rem.iter() {
+        if let Some(code) = dictionary.get(&(ent, *c)) {
+            ent = *code;
+            continue;
+        }
+
+        output(ent, free_ent, &mut writer);
+
+        if free_ent < max_max_code {
+            dictionary.insert((ent, *c), free_ent);
+            free_ent += 1;
+        }
+
+        ent = *c as usize;
+    }
+
+    output(ent, free_ent, &mut writer);
+    writer.bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compress, decompress};
+
+    #[test]
+    fn lzw_codec() {
+        // long enough to go through all code widths
+        let mut content = Vec::new();
+        let mut seed = 1_u32;
+
+        for i in 0..400_000 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let symbol = if i % 3 == 0 {
+                (seed >> 16) as u8
+            } else {
+                b'0' + (i % 10) as u8
+            };
+            content.push(symbol);
+        }
+
+        for input in [b"".as_slice(), b"a", b"abababababababab", &content] {
+            let compressed = compress(input);
+            assert_eq!(compressed[0..3], [0x1f, 0x9d, 0x90]);
+
+            let decompressed = decompress(&compressed).unwrap();
+            assert_eq!(decompressed, input);
+        }
+
+        assert!(decompress(&[0x1f, 0x8b, 0x08]).is_err());
+    }
+}
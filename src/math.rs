@@ -0,0 +1,207 @@
+//! Vector & statistics helpers
+#![cfg_attr(not(feature = "processing"), allow(dead_code))]
+
+use crate::Vector3D;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Earth rotation rate (rad.s⁻¹), as per IERS conventions
+pub(crate) const EARTH_ROTATION_RATE_RAD_S: f64 = 7.292_115_146_7E-5;
+
+pub(crate) fn add(a: Vector3D, b: Vector3D) -> Vector3D {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+pub(crate) fn sub(a: Vector3D, b: Vector3D) -> Vector3D {
+    (a.0 - b.0, a.1 - b.1, a.2 - b.2)
+}
+
+pub(crate) fn scale(a: Vector3D, k: f64) -> Vector3D {
+    (a.0 * k, a.1 * k, a.2 * k)
+}
+
+pub(crate) fn dot(a: Vector3D, b: Vector3D) -> f64 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+pub(crate) fn cross(a: Vector3D, b: Vector3D) -> Vector3D {
+    (
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+
+pub(crate) fn norm(a: Vector3D) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Returns unit vector, or None for null vectors
+pub(crate) fn unit(a: Vector3D) -> Option<Vector3D> {
+    let n = norm(a);
+    if n > 0.0 {
+        Some(scale(a, 1.0 / n))
+    } else {
+        None
+    }
+}
+
+/// Converts ECEF velocity to inertial velocity (expressed in ECEF axes),
+/// by compensating the Earth rotation.
+pub(crate) fn inertial_velocity(position: Vector3D, velocity: Vector3D) -> Vector3D {
+    let omega = (0.0, 0.0, EARTH_ROTATION_RATE_RAD_S);
+    add(velocity, cross(omega, position))
+}
+
+/// Radial, along-track and cross-track unit vectors,
+/// from position and (inertial) velocity vectors.
+pub(crate) fn rtn_basis(position: Vector3D, velocity: Vector3D) -> Option<[Vector3D; 3]> {
+    let r = unit(position)?;
+    let n = unit(cross(position, velocity))?;
+    let t = cross(n, r);
+    Some([r, t, n])
+}
+
+/// Projects `vector` onto the RTN basis.
+pub(crate) fn rtn_projection(vector: Vector3D, basis: &[Vector3D; 3]) -> Vector3D {
+    (
+        dot(vector, basis[0]),
+        dot(vector, basis[1]),
+        dot(vector, basis[2]),
+    )
+}
+
+/// Inverts square `matrix` (Gauss-Jordan elimination with partial pivoting).
+/// Returns None when the matrix is singular.
+pub(crate) fn invert(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let size = matrix.len();
+
+    let mut lhs = matrix.to_vec();
+    let mut rhs = (0..size)
+        .map(|i| (0..size).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect::<Vec<Vec<f64>>>();
+
+    for col in 0..size {
+        let pivot = (col..size).max_by(|a, b| lhs[*a][col].abs().total_cmp(&lhs[*b][col].abs()))?;
+
+        if lhs[pivot][col].abs() < 1.0E-12 {
+            return None;
+        }
+
+        lhs.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let p = lhs[col][col];
+        for j in 0..size {
+            lhs[col][j] /= p;
+            rhs[col][j] /= p;
+        }
+
+        for row in 0..size {
+            if row != col {
+                let k = lhs[row][col];
+                if k != 0.0 {
+                    for j in 0..size {
+                        lhs[row][j] -= k * lhs[col][j];
+                        rhs[row][j] -= k * rhs[col][j];
+                    }
+                }
+            }
+        }
+    }
+
+    Some(rhs)
+}
+
+/// Basic [Statistics] of a series of values.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Statistics {
+    /// Number of samples
+    pub count: usize,
+
+    /// Mean value
+    pub mean: f64,
+
+    /// Root mean square
+    pub rms: f64,
+
+    /// Maximal absolute value
+    pub max: f64,
+}
+
+impl Statistics {
+    /// Computes [Statistics] from a set of values.
+    pub(crate) fn from_values(values: &[f64]) -> Self {
+        let count = values.len();
+
+        if count == 0 {
+            return Self::default();
+        }
+
+        let (mut sum, mut sum_sq, mut max) = (0.0_f64, 0.0_f64, 0.0_f64);
+
+        for value in values {
+            sum += value;
+            sum_sq += value * value;
+            max = max.max(value.abs());
+        }
+
+        Self {
+            count,
+            max,
+            mean: sum / count as f64,
+            rms: (sum_sq / count as f64).sqrt(),
+        }
+    }
+
+    /// Standard deviation, deduced from RMS and mean values.
+    pub fn std_dev(&self) -> f64 {
+        (self.rms.powi(2) - self.mean.powi(2)).max(0.0).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rtn_projections() {
+        let position = (26_000.0, 0.0, 0.0);
+        let velocity = (0.0, 3.9, 0.0);
+
+        let basis = rtn_basis(position, velocity).unwrap();
+
+        let rtn = rtn_projection((1.0, 2.0, 3.0), &basis);
+        assert!((rtn.0 - 1.0).abs() < 1.0E-12);
+        assert!((rtn.1 - 2.0).abs() < 1.0E-12);
+        assert!((rtn.2 - 3.0).abs() < 1.0E-12);
+
+        assert!(rtn_basis(position, (1.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn matrix_inversion() {
+        let matrix = vec![vec![4.0, 7.0], vec![2.0, 6.0]];
+        let inverse = invert(&matrix).unwrap();
+        assert!((inverse[0][0] - 0.6).abs() < 1.0E-12);
+        assert!((inverse[0][1] + 0.7).abs() < 1.0E-12);
+        assert!((inverse[1][0] + 0.2).abs() < 1.0E-12);
+        assert!((inverse[1][1] - 0.4).abs() < 1.0E-12);
+
+        assert!(invert(&[vec![1.0, 2.0], vec![2.0, 4.0]]).is_none());
+    }
+
+    #[test]
+    fn statistics() {
+        let stats = Statistics::from_values(&[1.0, -1.0, 3.0, -3.0]);
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.mean, 0.0);
+        assert_eq!(stats.max, 3.0);
+        assert!((stats.rms - 5.0_f64.sqrt()).abs() < 1.0E-12);
+        assert!((stats.std_dev() - 5.0_f64.sqrt()).abs() < 1.0E-12);
+
+        assert_eq!(Statistics::from_values(&[]), Statistics::default());
+    }
+}
@@ -0,0 +1,278 @@
+//! CCSDS OEM Keyword = Value Notation
+use std::{
+    collections::HashMap,
+    io::{BufWriter, Result as IoResult, Write},
+};
+
+use super::{
+    format_epoch, parse_epoch, parse_number, Oem, OemCovariance, OemMetadata, OemSegment, OemState,
+};
+
+use crate::{errors::OemError, prelude::TimeScale};
+
+/// Splits this "KEYWORD = value" line
+fn keyword_value(line: &str) -> Option<(&str, &str)> {
+    let (keyword, value) = line.split_once('=')?;
+    Some((keyword.trim(), value.trim()))
+}
+
+/// Returns the content of this COMMENT line
+fn comment(line: &str) -> Option<&str> {
+    line.strip_prefix("COMMENT").map(|comment| comment.trim())
+}
+
+/// Formats this [Oem] in KVN
+pub(crate) fn format<W: Write>(oem: &Oem, w: &mut BufWriter<W>) -> IoResult<()> {
+    writeln!(w, "CCSDS_OEM_VERS = {}", oem.version)?;
+
+    for comment in oem.comments.iter() {
+        writeln!(w, "COMMENT {}", comment)?;
+    }
+
+    writeln!(
+        w,
+        "CREATION_DATE = {}",
+        format_epoch(oem.creation_date, TimeScale::UTC)
+    )?;
+    writeln!(w, "ORIGINATOR = {}", oem.originator)?;
+
+    for segment in oem.segments.iter() {
+        let metadata = &segment.metadata;
+
+        writeln!(w)?;
+        writeln!(w, "META_START")?;
+
+        for (keyword, value) in metadata.keywords() {
+            writeln!(w, "{} = {}", keyword, value)?;
+        }
+
+        writeln!(w, "META_STOP")?;
+        writeln!(w)?;
+
+        for comment in segment.comments.iter() {
+            writeln!(w, "COMMENT {}", comment)?;
+        }
+
+        for state in segment.states.iter() {
+            let (x_km, y_km, z_km) = state.position_km;
+            let (vx_km_s, vy_km_s, vz_km_s) = state.velocity_km_s;
+
+            write!(
+                w,
+                "{} {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}",
+                metadata.format_epoch(state.epoch),
+                x_km,
+                y_km,
+                z_km,
+                vx_km_s,
+                vy_km_s,
+                vz_km_s
+            )?;
+
+            if let Some((ax, ay, az)) = state.acceleration_km_s2 {
+                write!(w, " {:.12} {:.12} {:.12}", ax, ay, az)?;
+            }
+
+            writeln!(w)?;
+        }
+
+        if !segment.covariances.is_empty() {
+            writeln!(w)?;
+            writeln!(w, "COVARIANCE_START")?;
+
+            for covariance in segment.covariances.iter() {
+                writeln!(w, "EPOCH = {}", metadata.format_epoch(covariance.epoch))?;
+
+                if let Some(ref_frame) = &covariance.ref_frame {
+                    writeln!(w, "COV_REF_FRAME = {}", ref_frame)?;
+                }
+
+                for row in 0..6 {
+                    let values = (0..=row)
+                        .map(|col| format!("{:.7e}", covariance.element(row, col)))
+                        .collect::<Vec<_>>();
+
+                    writeln!(w, "{}", values.join(" "))?;
+                }
+            }
+
+            writeln!(w, "COVARIANCE_STOP")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parsing state
+enum Section {
+    Header,
+    Metadata(HashMap<String, String>),
+    Data,
+    Covariance,
+}
+
+/// Parses [Oem] from KVN content
+pub(crate) fn parse(content: &str) -> Result<Oem, OemError> {
+    let mut version = None;
+    let mut creation_date = None;
+    let mut originator = None;
+    let mut comments = Vec::new();
+    let mut metadata_comments = Vec::new();
+    let mut segments = Vec::<OemSegment>::new();
+
+    // covariance being parsed: (epoch, frame, values)
+    let mut covariance: Option<(String, Option<String>, Vec<f64>)> = None;
+
+    let mut section = Section::Header;
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = comment(line) {
+            match (&section, segments.last_mut()) {
+                (Section::Header, _) | (_, None) => comments.push(comment.to_string()),
+                (Section::Metadata(_), _) => metadata_comments.push(comment.to_string()),
+                (_, Some(segment)) => segment.comments.push(comment.to_string()),
+            }
+            continue;
+        }
+
+        match line {
+            "META_START" => {
+                section = Section::Metadata(HashMap::new());
+                continue;
+            },
+            "META_STOP" => {
+                let Section::Metadata(keywords) = &section else {
+                    return Err(OemError::Malformed(line.to_string()));
+                };
+
+                segments.push(OemSegment {
+                    metadata: OemMetadata::from_keywords(keywords)?,
+                    comments: std::mem::take(&mut metadata_comments),
+                    states: Vec::new(),
+                    covariances: Vec::new(),
+                });
+
+                section = Section::Data;
+                continue;
+            },
+            "COVARIANCE_START" => {
+                section = Section::Covariance;
+                continue;
+            },
+            "COVARIANCE_STOP" => {
+                let segment = segments
+                    .last_mut()
+                    .ok_or_else(|| OemError::Malformed(line.to_string()))?;
+
+                if let Some(pending) = covariance.take() {
+                    let timescale = segment.metadata.time_system;
+                    segment
+                        .covariances
+                        .push(build_covariance(pending, timescale)?);
+                }
+
+                section = Section::Data;
+                continue;
+            },
+            _ => {},
+        }
+
+        match &mut section {
+            Section::Header => {
+                let (keyword, value) =
+                    keyword_value(line).ok_or_else(|| OemError::Malformed(line.to_string()))?;
+
+                match keyword {
+                    "CCSDS_OEM_VERS" => version = Some(value.to_string()),
+                    "CREATION_DATE" => creation_date = Some(parse_epoch(value, TimeScale::UTC)?),
+                    "ORIGINATOR" => originator = Some(value.to_string()),
+                    _ => {},
+                }
+            },
+            Section::Metadata(keywords) => {
+                let (keyword, value) =
+                    keyword_value(line).ok_or_else(|| OemError::Malformed(line.to_string()))?;
+
+                keywords.insert(keyword.to_string(), value.to_string());
+            },
+            Section::Data => {
+                let segment = segments
+                    .last_mut()
+                    .ok_or_else(|| OemError::Malformed(line.to_string()))?;
+
+                let values = line.split_whitespace().collect::<Vec<_>>();
+                let state = OemState::from_values(&values, segment.metadata.time_system)?;
+                segment.states.push(state);
+            },
+            Section::Covariance => {
+                let segment = segments
+                    .last_mut()
+                    .ok_or_else(|| OemError::Malformed(line.to_string()))?;
+
+                if let Some((keyword, value)) = keyword_value(line) {
+                    match keyword {
+                        "EPOCH" => {
+                            if let Some(pending) = covariance.take() {
+                                let timescale = segment.metadata.time_system;
+                                segment
+                                    .covariances
+                                    .push(build_covariance(pending, timescale)?);
+                            }
+                            covariance = Some((value.to_string(), None, Vec::new()));
+                        },
+                        "COV_REF_FRAME" => {
+                            let (_, frame, _) = covariance
+                                .as_mut()
+                                .ok_or_else(|| OemError::Malformed(line.to_string()))?;
+                            *frame = Some(value.to_string());
+                        },
+                        _ => return Err(OemError::Malformed(line.to_string())),
+                    }
+                } else {
+                    let (_, _, values) = covariance
+                        .as_mut()
+                        .ok_or_else(|| OemError::Malformed(line.to_string()))?;
+
+                    for value in line.split_whitespace() {
+                        values.push(parse_number(value)?);
+                    }
+                }
+            },
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(OemError::NoData);
+    }
+
+    Ok(Oem {
+        version: version.ok_or_else(|| OemError::MissingKeyword("CCSDS_OEM_VERS".to_string()))?,
+        creation_date: creation_date
+            .ok_or_else(|| OemError::MissingKeyword("CREATION_DATE".to_string()))?,
+        originator: originator.ok_or_else(|| OemError::MissingKeyword("ORIGINATOR".to_string()))?,
+        comments,
+        segments,
+    })
+}
+
+/// Builds [OemCovariance] from parsed lower triangular values
+fn build_covariance(
+    (epoch, ref_frame, values): (String, Option<String>, Vec<f64>),
+    timescale: TimeScale,
+) -> Result<OemCovariance, OemError> {
+    let lower_triangle: [f64; 21] = values
+        .try_into()
+        .map_err(|_| OemError::Malformed(format!("covariance at {}", epoch)))?;
+
+    Ok(OemCovariance {
+        epoch: parse_epoch(&epoch, timescale)?,
+        ref_frame,
+        lower_triangle,
+    })
+}
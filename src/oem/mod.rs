@@ -0,0 +1,1245 @@
+//! CCSDS Orbit Ephemeris Message (OEM) import and export
+mod kvn;
+mod xml;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use hifitime::efmt::{Format, Formatter};
+
+use crate::{
+    errors::{FormattingError, OemError},
+    lagrange_derivative,
+    prelude::{Epoch, ReferenceFrame, SP3Builder, SP3Entry, TimeScale, TimeSystem, SP3, SV},
+    Vector3D,
+};
+
+/// CCSDS names of the state vector components
+const COMPONENTS: [&str; 6] = ["X", "Y", "Z", "X_DOT", "Y_DOT", "Z_DOT"];
+
+/// Comment that preserves the SP3 coordinate system, when it is not an ITRF realization
+const SP3_FRAME_COMMENT: &str = "SP3 coordinate system:";
+
+/// Number of positions of the Lagrange interpolant (8th degree) that is differentiated,
+/// when velocities are not published
+const VELOCITY_INTERPOLATION_POINTS: usize = 9;
+
+/// Two consecutive positions further apart than this many sampling periods
+/// are considered as a data gap, which we do not differentiate across.
+const VELOCITY_GAP_TOLERANCE: f64 = 1.5;
+
+/// CCSDS OEM [OemEncoding]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OemEncoding {
+    /// Keyword = Value Notation
+    #[default]
+    KVN,
+    /// XML
+    XML,
+}
+
+/// [OemInterpolation] hint, with polynomial degree
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OemInterpolation {
+    /// Lagrange interpolation of this degree
+    Lagrange(usize),
+    /// Hermite interpolation of this degree
+    Hermite(usize),
+    /// Linear interpolation
+    Linear,
+}
+
+impl Default for OemInterpolation {
+    /// 8th degree Lagrange interpolation (9 points)
+    fn default() -> Self {
+        Self::Lagrange(8)
+    }
+}
+
+impl OemInterpolation {
+    /// CCSDS INTERPOLATION keyword
+    fn method(&self) -> &'static str {
+        match self {
+            Self::Lagrange(_) => "LAGRANGE",
+            Self::Hermite(_) => "HERMITE",
+            Self::Linear => "LINEAR",
+        }
+    }
+
+    /// CCSDS INTERPOLATION_DEGREE keyword
+    fn degree(&self) -> usize {
+        match self {
+            Self::Lagrange(degree) | Self::Hermite(degree) => *degree,
+            Self::Linear => 1,
+        }
+    }
+
+    /// Parses [OemInterpolation] from CCSDS keywords
+    fn from_keywords(method: &str, degree: Option<&str>) -> Result<Self, OemError> {
+        let degree = match degree {
+            Some(degree) => degree
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| OemError::Malformed(degree.to_string()))?,
+            None => 1,
+        };
+
+        match method.trim().to_uppercase().as_str() {
+            "LAGRANGE" => Ok(Self::Lagrange(degree)),
+            "HERMITE" => Ok(Self::Hermite(degree)),
+            "LINEAR" => Ok(Self::Linear),
+            _ => Err(OemError::Malformed(method.to_string())),
+        }
+    }
+}
+
+/// [OemOptions] used by [SP3::to_oem] and [SP3::to_oem_per_satellite]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemOptions {
+    /// ORIGINATOR. Defaults to the SP3 agency.
+    pub originator: Option<String>,
+
+    /// OBJECT_ID of each [SV], typically the international designator.
+    /// The [SV] is used when not defined.
+    pub object_ids: BTreeMap<SV, String>,
+
+    /// [OemInterpolation] hint. None to omit the hint.
+    pub interpolation: Option<OemInterpolation>,
+
+    /// True to generate covariance blocks from the SP3 standard deviations
+    pub covariance: bool,
+}
+
+impl Default for OemOptions {
+    /// Builds [OemOptions] with 8th degree Lagrange interpolation hint,
+    /// and covariance blocks.
+    fn default() -> Self {
+        Self {
+            originator: None,
+            object_ids: Default::default(),
+            interpolation: Some(OemInterpolation::default()),
+            covariance: true,
+        }
+    }
+}
+
+impl OemOptions {
+    /// Copies and returns [OemOptions] with desired ORIGINATOR.
+    pub fn with_originator(&self, originator: &str) -> Self {
+        let mut s = self.clone();
+        s.originator = Some(originator.to_string());
+        s
+    }
+
+    /// Copies and returns [OemOptions] with OBJECT_ID of this [SV].
+    pub fn with_object_id(&self, sv: SV, object_id: &str) -> Self {
+        let mut s = self.clone();
+        s.object_ids.insert(sv, object_id.to_string());
+        s
+    }
+
+    /// Copies and returns [OemOptions] with desired [OemInterpolation] hint.
+    pub fn with_interpolation(&self, interpolation: OemInterpolation) -> Self {
+        let mut s = self.clone();
+        s.interpolation = Some(interpolation);
+        s
+    }
+
+    /// Copies and returns [OemOptions] without interpolation hint.
+    pub fn without_interpolation(&self) -> Self {
+        let mut s = self.clone();
+        s.interpolation = None;
+        s
+    }
+
+    /// Copies and returns [OemOptions] without covariance blocks.
+    pub fn without_covariance(&self) -> Self {
+        let mut s = self.clone();
+        s.covariance = false;
+        s
+    }
+}
+
+/// [OemMetadata] of one [OemSegment]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemMetadata {
+    /// OBJECT_NAME
+    pub object_name: String,
+
+    /// OBJECT_ID
+    pub object_id: String,
+
+    /// CENTER_NAME
+    pub center_name: String,
+
+    /// REF_FRAME
+    pub ref_frame: String,
+
+    /// TIME_SYSTEM, in which all [Epoch]s of this segment are expressed
+    pub time_system: TimeScale,
+
+    /// START_TIME
+    pub start_time: Epoch,
+
+    /// STOP_TIME
+    pub stop_time: Epoch,
+
+    /// USEABLE_START_TIME
+    pub useable_start_time: Option<Epoch>,
+
+    /// USEABLE_STOP_TIME
+    pub useable_stop_time: Option<Epoch>,
+
+    /// INTERPOLATION and INTERPOLATION_DEGREE
+    pub interpolation: Option<OemInterpolation>,
+}
+
+impl OemMetadata {
+    /// Builds [OemMetadata] from keyword values
+    fn from_keywords(keywords: &HashMap<String, String>) -> Result<Self, OemError> {
+        let keyword = |name: &str| {
+            keywords
+                .get(name)
+                .map(|value| value.trim().to_string())
+                .ok_or_else(|| OemError::MissingKeyword(name.to_string()))
+        };
+
+        let time_system = parse_time_system(&keyword("TIME_SYSTEM")?)?;
+
+        let optional_epoch = |name: &str| {
+            keywords
+                .get(name)
+                .map(|value| parse_epoch(value, time_system))
+                .transpose()
+        };
+
+        let interpolation = keywords
+            .get("INTERPOLATION")
+            .map(|method| {
+                OemInterpolation::from_keywords(
+                    method,
+                    keywords.get("INTERPOLATION_DEGREE").map(|s| s.as_str()),
+                )
+            })
+            .transpose()?;
+
+        Ok(Self {
+            object_name: keyword("OBJECT_NAME")?,
+            object_id: keyword("OBJECT_ID")?,
+            center_name: keyword("CENTER_NAME")?,
+            ref_frame: keyword("REF_FRAME")?,
+            time_system,
+            start_time: parse_epoch(&keyword("START_TIME")?, time_system)?,
+            stop_time: parse_epoch(&keyword("STOP_TIME")?, time_system)?,
+            useable_start_time: optional_epoch("USEABLE_START_TIME")?,
+            useable_stop_time: optional_epoch("USEABLE_STOP_TIME")?,
+            interpolation,
+        })
+    }
+
+    /// Returns all keyword values, in CCSDS order
+    fn keywords(&self) -> Vec<(&'static str, String)> {
+        let mut keywords = vec![
+            ("OBJECT_NAME", self.object_name.clone()),
+            ("OBJECT_ID", self.object_id.clone()),
+            ("CENTER_NAME", self.center_name.clone()),
+            ("REF_FRAME", self.ref_frame.clone()),
+            (
+                "TIME_SYSTEM",
+                ccsds_time_system(self.time_system).to_string(),
+            ),
+            ("START_TIME", self.format_epoch(self.start_time)),
+        ];
+
+        if let Some(t) = self.useable_start_time {
+            keywords.push(("USEABLE_START_TIME", self.format_epoch(t)));
+        }
+
+        if let Some(t) = self.useable_stop_time {
+            keywords.push(("USEABLE_STOP_TIME", self.format_epoch(t)));
+        }
+
+        keywords.push(("STOP_TIME", self.format_epoch(self.stop_time)));
+
+        if let Some(interpolation) = self.interpolation {
+            keywords.push(("INTERPOLATION", interpolation.method().to_string()));
+            keywords.push(("INTERPOLATION_DEGREE", interpolation.degree().to_string()));
+        }
+
+        keywords
+    }
+
+    /// Formats this [Epoch] in the TIME_SYSTEM of this segment
+    fn format_epoch(&self, t: Epoch) -> String {
+        format_epoch(t, self.time_system)
+    }
+}
+
+/// [OemState] is one state vector
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OemState {
+    /// [Epoch] of this state
+    pub epoch: Epoch,
+
+    /// Position, in kilometers
+    pub position_km: Vector3D,
+
+    /// Velocity, in kilometers per second
+    pub velocity_km_s: Vector3D,
+
+    /// Optional acceleration, in kilometers per second squared
+    pub acceleration_km_s2: Option<Vector3D>,
+}
+
+impl OemState {
+    /// Parses [OemState] from these values (epoch, position, velocity, acceleration)
+    fn from_values(values: &[&str], timescale: TimeScale) -> Result<Self, OemError> {
+        if values.len() != 7 && values.len() != 10 {
+            return Err(OemError::Malformed(values.join(" ")));
+        }
+
+        let epoch = parse_epoch(values[0], timescale)?;
+
+        let numbers = values[1..]
+            .iter()
+            .map(|value| parse_number(value))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            epoch,
+            position_km: (numbers[0], numbers[1], numbers[2]),
+            velocity_km_s: (numbers[3], numbers[4], numbers[5]),
+            acceleration_km_s2: if numbers.len() == 9 {
+                Some((numbers[6], numbers[7], numbers[8]))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// [OemCovariance] is the 6x6 covariance matrix of one state vector
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemCovariance {
+    /// [Epoch] of this covariance
+    pub epoch: Epoch,
+
+    /// COV_REF_FRAME, when it differs from the segment REF_FRAME
+    pub ref_frame: Option<String>,
+
+    /// Lower triangular part of the covariance matrix, row by row,
+    /// in km², km²/s and km²/s².
+    pub lower_triangle: [f64; 21],
+}
+
+impl OemCovariance {
+    /// Index of this element in [Self::lower_triangle]
+    fn index(row: usize, col: usize) -> usize {
+        let (row, col) = if row >= col { (row, col) } else { (col, row) };
+        row * (row + 1) / 2 + col
+    }
+
+    /// Name of this element, in XML notation
+    fn element_name(row: usize, col: usize) -> String {
+        format!("C{}_{}", COMPONENTS[row], COMPONENTS[col])
+    }
+
+    /// Builds a diagonal [OemCovariance] from standard deviations
+    fn from_std_devs(epoch: Epoch, position_km: Vector3D, velocity_km_s: Vector3D) -> Self {
+        let mut lower_triangle = [0.0; 21];
+
+        let std_devs = [
+            position_km.0,
+            position_km.1,
+            position_km.2,
+            velocity_km_s.0,
+            velocity_km_s.1,
+            velocity_km_s.2,
+        ];
+
+        for (i, std_dev) in std_devs.iter().enumerate() {
+            lower_triangle[Self::index(i, i)] = std_dev.powi(2);
+        }
+
+        Self {
+            epoch,
+            ref_frame: None,
+            lower_triangle,
+        }
+    }
+
+    /// Returns the covariance between these two state vector components (0..6)
+    pub fn element(&self, row: usize, col: usize) -> f64 {
+        self.lower_triangle[Self::index(row, col)]
+    }
+
+    /// Returns the position standard deviation, in kilometers
+    pub fn position_std_dev_km(&self) -> Vector3D {
+        (
+            self.element(0, 0).sqrt(),
+            self.element(1, 1).sqrt(),
+            self.element(2, 2).sqrt(),
+        )
+    }
+
+    /// Returns the velocity standard deviation, in kilometers per second
+    pub fn velocity_std_dev_km_s(&self) -> Vector3D {
+        (
+            self.element(3, 3).sqrt(),
+            self.element(4, 4).sqrt(),
+            self.element(5, 5).sqrt(),
+        )
+    }
+}
+
+/// [OemSegment] describes one object
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemSegment {
+    /// [OemMetadata]
+    pub metadata: OemMetadata,
+
+    /// Data comments
+    pub comments: Vec<String>,
+
+    /// [OemState]s, in chronological order
+    pub states: Vec<OemState>,
+
+    /// Optional [OemCovariance]s
+    pub covariances: Vec<OemCovariance>,
+}
+
+/// CCSDS Orbit Ephemeris Message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oem {
+    /// CCSDS_OEM_VERS
+    pub version: String,
+
+    /// CREATION_DATE
+    pub creation_date: Epoch,
+
+    /// ORIGINATOR
+    pub originator: String,
+
+    /// Header comments
+    pub comments: Vec<String>,
+
+    /// [OemSegment]s
+    pub segments: Vec<OemSegment>,
+}
+
+impl Oem {
+    /// Formats this [Oem] using desired [OemEncoding].
+    pub fn format<W: Write>(
+        &self,
+        encoding: OemEncoding,
+        writer: &mut BufWriter<W>,
+    ) -> Result<(), FormattingError> {
+        match encoding {
+            OemEncoding::KVN => kvn::format(self, writer)?,
+            OemEncoding::XML => xml::format(self, writer)?,
+        }
+        Ok(())
+    }
+
+    /// Dumps this [Oem] into a local file. [OemEncoding::XML] is used
+    /// when the file extension is `.xml`, [OemEncoding::KVN] otherwise.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), FormattingError> {
+        let xml = path
+            .as_ref()
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"));
+
+        let encoding = if xml {
+            OemEncoding::XML
+        } else {
+            OemEncoding::KVN
+        };
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.format(encoding, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Parses [Oem] from [Read]able I/O. Both [OemEncoding]s are supported.
+    pub fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Result<Self, OemError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        if content.trim_start().starts_with('<') {
+            xml::parse(&content)
+        } else {
+            kvn::parse(&content)
+        }
+    }
+
+    /// Parses [Oem] from local file. Both [OemEncoding]s are supported.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, OemError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        Self::from_reader(&mut reader)
+    }
+}
+
+/// CCSDS TIME_SYSTEM of this [TimeScale]
+fn ccsds_time_system(timescale: TimeScale) -> &'static str {
+    match timescale {
+        TimeScale::GPST => "GPS",
+        TimeScale::GST => "GST",
+        TimeScale::BDT => "BDT",
+        TimeScale::QZSST => "QZSST",
+        TimeScale::UTC => "UTC",
+        TimeScale::TT => "TT",
+        TimeScale::TDB => "TDB",
+        _ => "TAI",
+    }
+}
+
+/// Parses [TimeScale] from CCSDS TIME_SYSTEM
+fn parse_time_system(time_system: &str) -> Result<TimeScale, OemError> {
+    match time_system.trim().to_uppercase().as_str() {
+        "GPS" => Ok(TimeScale::GPST),
+        "GST" | "GAL" => Ok(TimeScale::GST),
+        "BDT" => Ok(TimeScale::BDT),
+        "QZSST" => Ok(TimeScale::QZSST),
+        "TAI" => Ok(TimeScale::TAI),
+        "UTC" => Ok(TimeScale::UTC),
+        "TT" => Ok(TimeScale::TT),
+        "TDB" => Ok(TimeScale::TDB),
+        _ => Err(OemError::TimeSystem(time_system.to_string())),
+    }
+}
+
+/// CCSDS REF_FRAME of this [ReferenceFrame]: the ITRF realization it is aligned to
+fn ccsds_ref_frame(frame: ReferenceFrame) -> String {
+    // SP3 labels are "ITRYY"
+    let label = frame.itrf().to_string();
+    let year = &label[3..];
+
+    if year >= "88" {
+        format!("ITRF-{}", year)
+    } else {
+        format!("ITRF20{}", year)
+    }
+}
+
+/// Returns the [ReferenceFrame] of this [OemSegment]
+fn segment_ref_frame(segment: &OemSegment) -> Result<ReferenceFrame, OemError> {
+    let sp3_frame = segment.comments.iter().find_map(|comment| {
+        comment
+            .strip_prefix(SP3_FRAME_COMMENT)
+            .and_then(|frame| ReferenceFrame::from_str(frame.trim()).ok())
+    });
+
+    if let Some(frame) = sp3_frame {
+        return Ok(frame);
+    }
+
+    let ref_frame = &segment.metadata.ref_frame;
+
+    ReferenceFrame::from_str(&ref_frame.replace('-', ""))
+        .map_err(|_| OemError::ReferenceFrame(ref_frame.to_string()))
+}
+
+/// Formats this [Epoch] in this [TimeScale]
+fn format_epoch(t: Epoch, timescale: TimeScale) -> String {
+    let efmt = Format::from_str("%Y-%m-%dT%H:%M:%S.%f").unwrap();
+    Formatter::new(t.to_time_scale(timescale), efmt).to_string()
+}
+
+/// Returns (month, day) of this day of year
+fn month_day(year: i32, day_of_year: u32) -> Option<(u32, u32)> {
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+
+    let month_days = [
+        31,
+        if leap { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+
+    let mut day = day_of_year;
+
+    for (month, days) in month_days.iter().enumerate() {
+        if day == 0 {
+            return None;
+        }
+
+        if day <= *days {
+            return Some((month as u32 + 1, day));
+        }
+
+        day -= days;
+    }
+
+    None
+}
+
+/// Parses [Epoch] in this [TimeScale], in either calendar ("YYYY-MM-DDThh:mm:ss.d")
+/// or day of year ("YYYY-DDDThh:mm:ss.d") format
+fn parse_epoch(content: &str, timescale: TimeScale) -> Result<Epoch, OemError> {
+    let trimmed = content.trim().trim_end_matches('Z');
+    let invalid = || OemError::Epoch(content.trim().to_string());
+
+    let calendar = match (trimmed.get(4..5), trimmed.get(8..9)) {
+        (Some("-"), Some("T")) => {
+            let year = trimmed[0..4].parse::<i32>().map_err(|_| invalid())?;
+            let day_of_year = trimmed[5..8].parse::<u32>().map_err(|_| invalid())?;
+            let (month, day) = month_day(year, day_of_year).ok_or_else(invalid)?;
+            format!("{:04}-{:02}-{:02}{}", year, month, day, &trimmed[8..])
+        },
+        _ => trimmed.to_string(),
+    };
+
+    Epoch::from_str(&format!("{} {}", calendar, timescale)).map_err(|_| invalid())
+}
+
+/// Parses one number
+fn parse_number(content: &str) -> Result<f64, OemError> {
+    f64::from_str(content.trim()).map_err(|_| OemError::Malformed(content.to_string()))
+}
+
+/// Velocity of each sample of this continuous arc (in km.s⁻¹), published or obtained
+/// by differentiating the Lagrange interpolant of the neighboring positions.
+/// Returns None for isolated samples, whose velocity cannot be estimated.
+fn arc_velocities_km_s(arc: &[(Epoch, SP3Entry)]) -> Vec<Option<Vector3D>> {
+    let size = arc.len().min(VELOCITY_INTERPOLATION_POINTS);
+
+    arc.iter()
+        .enumerate()
+        .map(|(i, (t, entry))| {
+            if entry.velocity_km_s.is_some() {
+                return entry.velocity_km_s;
+            }
+
+            // centered window, when feasible
+            let start = i.saturating_sub(size / 2).min(arc.len() - size);
+
+            let window = arc[start..start + size]
+                .iter()
+                .map(|(t_i, entry)| (*t_i, entry.position_km))
+                .collect::<Vec<_>>();
+
+            lagrange_derivative(*t, &window)
+        })
+        .collect()
+}
+
+impl SP3 {
+    /// Builds the [OemSegment] of this [SV], or None when no state can be exported
+    fn oem_segment(&self, sv: SV, options: &OemOptions) -> Result<Option<OemSegment>, OemError> {
+        let frame = self
+            .reference_frame()
+            .map_err(|_| OemError::ReferenceFrame(self.header.coord_system.clone()))?;
+
+        let mut comments = Vec::new();
+
+        if frame != frame.itrf() {
+            comments.push(format!("{} {}", SP3_FRAME_COMMENT, frame));
+        }
+
+        let mut states = Vec::new();
+        let mut covariances = Vec::new();
+
+        // null positions are missing data
+        let samples = self
+            .data
+            .iter()
+            .filter(|(k, v)| k.sv == sv && v.position_km != (0.0, 0.0, 0.0))
+            .map(|(k, v)| (k.epoch, *v))
+            .collect::<Vec<_>>();
+
+        let max_gap = self.nominal_sampling_period() * VELOCITY_GAP_TOLERANCE;
+
+        // continuous arcs
+        let mut arcs = Vec::<&[(Epoch, SP3Entry)]>::new();
+        let mut start = 0;
+
+        for i in 1..=samples.len() {
+            if i == samples.len() || samples[i].0 - samples[i - 1].0 > max_gap {
+                arcs.push(&samples[start..i]);
+                start = i;
+            }
+        }
+
+        for arc in arcs {
+            for ((epoch, entry), velocity_km_s) in arc.iter().zip(arc_velocities_km_s(arc)) {
+                // isolated positions are not exported
+                let Some(velocity_km_s) = velocity_km_s else {
+                    continue;
+                };
+
+                states.push(OemState {
+                    epoch: *epoch,
+                    position_km: entry.position_km,
+                    velocity_km_s,
+                    acceleration_km_s2: None,
+                });
+
+                if !options.covariance {
+                    continue;
+                }
+
+                // both are required, unknown variances cannot be described
+                if let (Some(std_dev_mm), Some(std_dev_mm_s)) =
+                    (entry.position_std_dev_mm, entry.velocity_std_dev_mm_s)
+                {
+                    covariances.push(OemCovariance::from_std_devs(
+                        *epoch,
+                        (
+                            std_dev_mm.0 * 1.0E-6,
+                            std_dev_mm.1 * 1.0E-6,
+                            std_dev_mm.2 * 1.0E-6,
+                        ),
+                        (
+                            std_dev_mm_s.0 * 1.0E-6,
+                            std_dev_mm_s.1 * 1.0E-6,
+                            std_dev_mm_s.2 * 1.0E-6,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let (first, last) = match (states.first(), states.last()) {
+            (Some(first), Some(last)) => (first.epoch, last.epoch),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(OemSegment {
+            metadata: OemMetadata {
+                object_name: sv.to_string(),
+                object_id: options
+                    .object_ids
+                    .get(&sv)
+                    .cloned()
+                    .unwrap_or_else(|| sv.to_string()),
+                center_name: "EARTH".to_string(),
+                ref_frame: ccsds_ref_frame(frame),
+                time_system: self.header.timescale,
+                start_time: first,
+                stop_time: last,
+                useable_start_time: None,
+                useable_stop_time: None,
+                interpolation: options.interpolation,
+            },
+            comments,
+            states,
+            covariances,
+        }))
+    }
+
+    /// Builds an [Oem] without segments
+    fn oem_header(&self, options: &OemOptions) -> Oem {
+        Oem {
+            version: "2.0".to_string(),
+            creation_date: self.header.release_epoch,
+            originator: options
+                .originator
+                .clone()
+                .unwrap_or_else(|| self.header.agency.trim().to_string()),
+            comments: self.comments.clone(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Converts this [SP3] to a multi-segment CCSDS [Oem], with one [OemSegment] per [SV].
+    /// REF_FRAME is the ITRF realization the SP3 coordinate system is aligned to
+    /// (the SP3 coordinate system is kept as comment), TIME_SYSTEM is the SP3 [TimeScale].
+    /// Velocities that are not published are obtained by differentiating the Lagrange
+    /// interpolant (8th degree) of the neighboring positions, within continuous arcs.
+    /// Null (missing) and isolated positions are not exported, and satellites without
+    /// any exportable state are dropped.
+    /// Covariance blocks are generated from the SP3 standard deviations, when
+    /// both position and velocity standard deviations exist.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let oem = sp3.to_oem(&OemOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(oem.segments.len(), sp3.header.satellites.len());
+    ///
+    /// for segment in oem.segments.iter() {
+    ///     assert_eq!(segment.metadata.center_name, "EARTH");
+    ///     assert_eq!(segment.metadata.time_system, TimeScale::GPST);
+    /// }
+    ///
+    /// oem.to_file("GRG0MGXFIN_20201770000.oem")
+    ///     .unwrap();
+    ///
+    /// // CCSDS OEM to SP3
+    /// let oem = Oem::from_file("GRG0MGXFIN_20201770000.oem")
+    ///     .unwrap();
+    ///
+    /// let parsed = SP3::from_oem(&oem)
+    ///     .unwrap();
+    ///
+    /// // same satellites, not necessarily in the same order
+    /// for sv in sp3.header.satellites.iter() {
+    ///     assert!(parsed.header.satellites.contains(sv));
+    /// }
+    ///
+    /// assert_eq!(parsed.header.satellites.len(), sp3.header.satellites.len());
+    ///
+    /// std::fs::remove_file("GRG0MGXFIN_20201770000.oem")
+    ///     .unwrap();
+    /// ```
+    pub fn to_oem(&self, options: &OemOptions) -> Result<Oem, OemError> {
+        let mut oem = self.oem_header(options);
+
+        for sv in self.satellites_iter() {
+            if let Some(segment) = self.oem_segment(sv, options)? {
+                oem.segments.push(segment);
+            }
+        }
+
+        if oem.segments.is_empty() {
+            return Err(OemError::NoData);
+        }
+
+        Ok(oem)
+    }
+
+    /// Converts this [SP3] to one CCSDS [Oem] per [SV]. See [Self::to_oem].
+    pub fn to_oem_per_satellite(
+        &self,
+        options: &OemOptions,
+    ) -> Result<BTreeMap<SV, Oem>, OemError> {
+        let mut messages = BTreeMap::new();
+
+        for sv in self.satellites_iter() {
+            if let Some(segment) = self.oem_segment(sv, options)? {
+                let mut oem = self.oem_header(options);
+                oem.segments.push(segment);
+                messages.insert(sv, oem);
+            }
+        }
+
+        if messages.is_empty() {
+            return Err(OemError::NoData);
+        }
+
+        Ok(messages)
+    }
+
+    /// Converts this CCSDS [Oem] to [SP3]. Each [OemSegment] describes one [SV],
+    /// identified by its OBJECT_NAME (or OBJECT_ID), like "G01". All segments must
+    /// be centered on the Earth, expressed in the same ITRF (or IGS) frame and in
+    /// the same time system, that SP3 can describe. The diagonal of the covariance
+    /// blocks is converted to the SP3 standard deviations (correlations are dropped),
+    /// unless expressed in another COV_REF_FRAME. Null variances are considered unknown.
+    /// Interpolation hints are not preserved.
+    pub fn from_oem(oem: &Oem) -> Result<Self, OemError> {
+        let first = oem.segments.first().ok_or(OemError::NoData)?;
+
+        let timescale = first.metadata.time_system;
+        let frame = segment_ref_frame(first)?;
+
+        let time_system = TimeSystem::from_timescale(timescale)
+            .ok_or_else(|| OemError::TimeSystem(ccsds_time_system(timescale).to_string()))?;
+
+        let mut builder = SP3Builder::default()
+            .with_agency(&oem.originator)
+            .with_reference_frame(frame)
+            .with_time_system(time_system)
+            .with_release_epoch(oem.creation_date);
+
+        for comment in oem.comments.iter() {
+            builder = builder.with_comment(comment);
+        }
+
+        for segment in oem.segments.iter() {
+            let metadata = &segment.metadata;
+
+            if metadata.time_system != timescale {
+                return Err(OemError::TimeSystemMismatch);
+            }
+
+            if segment_ref_frame(segment)? != frame {
+                return Err(OemError::ReferenceFrameMismatch);
+            }
+
+            if !metadata.center_name.eq_ignore_ascii_case("EARTH") {
+                return Err(OemError::CenterName(metadata.center_name.clone()));
+            }
+
+            let sv = SV::from_str(metadata.object_name.trim())
+                .or_else(|_| SV::from_str(metadata.object_id.trim()))
+                .map_err(|_| OemError::Object(metadata.object_name.clone()))?;
+
+            for state in segment.states.iter() {
+                let mut entry = SP3Entry::from_position_velocity_km_km_s(
+                    state.position_km,
+                    state.velocity_km_s,
+                );
+
+                let covariance = segment.covariances.iter().find(|covariance| {
+                    covariance.epoch == state.epoch
+                        && covariance
+                            .ref_frame
+                            .as_ref()
+                            .is_none_or(|ref_frame| ref_frame == &metadata.ref_frame)
+                });
+
+                if let Some(covariance) = covariance {
+                    let (x_km, y_km, z_km) = covariance.position_std_dev_km();
+                    let (vx_km_s, vy_km_s, vz_km_s) = covariance.velocity_std_dev_km_s();
+
+                    if x_km > 0.0 && y_km > 0.0 && z_km > 0.0 {
+                        entry = entry.with_position_std_dev_mm((
+                            x_km * 1.0E6,
+                            y_km * 1.0E6,
+                            z_km * 1.0E6,
+                        ));
+                    }
+
+                    if vx_km_s > 0.0 && vy_km_s > 0.0 && vz_km_s > 0.0 {
+                        entry = entry.with_velocity_std_dev_mm_s((
+                            vx_km_s * 1.0E6,
+                            vy_km_s * 1.0E6,
+                            vz_km_s * 1.0E6,
+                        ));
+                    }
+                }
+
+                builder.push(state.epoch, sv, entry);
+            }
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Oem, OemEncoding, OemInterpolation, OemOptions};
+    use crate::prelude::{
+        Duration, Epoch, ReferenceFrame, SP3Builder, SP3Entry, SP3Key, TimeScale, TimeSystem, SV,
+    };
+    use std::io::{BufReader, BufWriter};
+    use std::str::FromStr;
+
+    #[test]
+    fn oem_round_trip() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+
+        let mut builder = SP3Builder::default()
+            .with_agency("GRG")
+            .with_reference_frame(ReferenceFrame::IGS20)
+            .with_time_system(TimeSystem::GPS)
+            .with_comment("synthetic orbits");
+
+        for i in 0..8 {
+            let t = t0 + dt * i as f64;
+            let x_km = 20_000.0 + i as f64;
+
+            // G01: positions only
+            let entry = SP3Entry::from_position_km((x_km, 10_000.0, -5_000.0))
+                .with_clock_offset_us(1.0)
+                .with_position_std_dev_mm((10.0, 20.0, 30.0))
+                .with_velocity_std_dev_mm_s((0.1, 0.2, 0.3));
+
+            builder.push(t, g01, entry);
+
+            // E01: positions and velocities, velocity standard deviation is unknown
+            let entry =
+                SP3Entry::from_position_velocity_km_km_s((-x_km, 1.0, 2.0), (0.5, -0.25, 0.125))
+                    .with_clock_offset_us(2.0)
+                    .with_position_std_dev_mm((10.0, 20.0, 30.0));
+
+            builder.push(t, e01, entry);
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let options = OemOptions::default()
+            .with_object_id(g01, "1978-020A")
+            .with_interpolation(OemInterpolation::Hermite(5));
+
+        let oem = sp3.to_oem(&options).unwrap();
+
+        assert_eq!(oem.originator, "GRG");
+        assert_eq!(oem.segments.len(), 2);
+
+        // null positions are not exported
+        let mut with_null = sp3.clone();
+
+        with_null.data.insert(
+            SP3Key {
+                epoch: t0 + dt * 8.0,
+                sv: g01,
+            },
+            SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_clock_offset_us(1.0),
+        );
+
+        let g01_oem = with_null.to_oem_per_satellite(&options).unwrap()[&g01].clone();
+        assert_eq!(g01_oem.segments[0].states.len(), 8);
+        assert_eq!(g01_oem.segments[0].metadata.stop_time, t0 + dt * 7.0);
+
+        let last = g01_oem.segments[0].states[7];
+        assert!((last.velocity_km_s.0 - 1.0 / 900.0).abs() < 1.0E-9);
+
+        let per_satellite = sp3.to_oem_per_satellite(&options).unwrap();
+        assert_eq!(per_satellite.len(), 2);
+        assert_eq!(per_satellite[&g01].segments.len(), 1);
+
+        for segment in oem.segments.iter() {
+            assert_eq!(segment.metadata.ref_frame, "ITRF2020");
+            assert_eq!(segment.metadata.time_system, TimeScale::GPST);
+            assert_eq!(segment.metadata.start_time, t0);
+            assert_eq!(segment.metadata.stop_time, t0 + dt * 7.0);
+            assert_eq!(segment.states.len(), 8);
+
+            if segment.metadata.object_name == "G01" {
+                assert_eq!(segment.metadata.object_id, "1978-020A");
+                assert_eq!(segment.covariances.len(), 8);
+
+                // differentiated positions
+                let state = segment.states[4];
+                assert!((state.velocity_km_s.0 - 1.0 / 900.0).abs() < 1.0E-9);
+            } else {
+                assert_eq!(segment.metadata.object_id, "E01");
+
+                // unknown velocity variances are not described
+                assert!(segment.covariances.is_empty());
+            }
+        }
+
+        for encoding in [OemEncoding::KVN, OemEncoding::XML] {
+            let mut buffer = BufWriter::new(Vec::<u8>::new());
+            oem.format(encoding, &mut buffer).unwrap();
+
+            let content = buffer.into_inner().unwrap();
+            let parsed = Oem::from_reader(&mut BufReader::new(content.as_slice())).unwrap();
+
+            assert_eq!(parsed.segments.len(), 2, "{:?}", encoding);
+            assert_eq!(parsed.comments, oem.comments);
+
+            for (parsed, segment) in parsed.segments.iter().zip(oem.segments.iter()) {
+                assert_eq!(parsed.metadata, segment.metadata, "{:?}", encoding);
+                assert_eq!(parsed.comments, segment.comments);
+                assert_eq!(parsed.states.len(), segment.states.len());
+                assert_eq!(parsed.covariances.len(), segment.covariances.len());
+            }
+
+            let converted = crate::prelude::SP3::from_oem(&parsed).unwrap();
+
+            assert_eq!(converted.header.coord_system, "IGS20");
+            assert_eq!(converted.header.timescale, TimeScale::GPST);
+            assert_eq!(converted.header.satellites, sp3.header.satellites);
+            assert_eq!(converted.comments, sp3.comments);
+            assert_eq!(converted.total_epochs(), 8);
+
+            for (k, entry) in sp3.data.iter() {
+                let converted = converted.data.get(k).unwrap();
+
+                let error_km = (
+                    (converted.position_km.0 - entry.position_km.0).abs(),
+                    (converted.position_km.1 - entry.position_km.1).abs(),
+                    (converted.position_km.2 - entry.position_km.2).abs(),
+                );
+
+                assert!(error_km.0 < 1.0E-6 && error_km.1 < 1.0E-6 && error_km.2 < 1.0E-6);
+
+                if let (Some(std_dev_mm), Some(std_dev_mm_s)) =
+                    (entry.position_std_dev_mm, entry.velocity_std_dev_mm_s)
+                {
+                    let converted_mm = converted.position_std_dev_mm.unwrap();
+                    assert!((converted_mm.0 - std_dev_mm.0).abs() < 1.0E-3);
+                    assert!((converted_mm.1 - std_dev_mm.1).abs() < 1.0E-3);
+                    assert!((converted_mm.2 - std_dev_mm.2).abs() < 1.0E-3);
+
+                    let converted_mm_s = converted.velocity_std_dev_mm_s.unwrap();
+                    assert!((converted_mm_s.0 - std_dev_mm_s.0).abs() < 1.0E-6);
+                    assert!((converted_mm_s.1 - std_dev_mm_s.1).abs() < 1.0E-6);
+                    assert!((converted_mm_s.2 - std_dev_mm_s.2).abs() < 1.0E-6);
+                } else {
+                    assert!(converted.position_std_dev_mm.is_none());
+                    assert!(converted.velocity_std_dev_mm_s.is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn oem_circular_orbit_velocities() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let g03 = SV::from_str("G03").unwrap();
+
+        // circular GPS orbit
+        let (radius_km, rate_rad_s) = (26_560.0, 1.458_4E-4);
+        let speed_km_s = radius_km * rate_rad_s;
+
+        let mut builder = SP3Builder::default()
+            .with_agency("GRG")
+            .with_reference_frame(ReferenceFrame::IGS20)
+            .with_time_system(TimeSystem::GPS);
+
+        for i in 0..24 {
+            let t = t0 + dt * i as f64;
+            let phase = rate_rad_s * (t - t0).to_seconds();
+            let (sin, cos) = phase.sin_cos();
+
+            let position_km = (radius_km * cos, radius_km * sin, 0.0);
+
+            // G01: isolated sample between two gaps
+            if i != 10 && i != 12 {
+                builder.push(t, g01, SP3Entry::from_position_km(position_km));
+            }
+
+            // G02: isolated samples only
+            if i == 1 || i == 11 {
+                builder.push(t, g02, SP3Entry::from_position_km(position_km));
+            }
+
+            // G03: missing data only
+            builder.push(t, g03, SP3Entry::from_position_km((0.0, 0.0, 0.0)));
+        }
+
+        let sp3 = builder.build().unwrap();
+        let messages = sp3.to_oem_per_satellite(&OemOptions::default()).unwrap();
+
+        // satellites without any exportable state are dropped
+        assert!(!messages.contains_key(&g03));
+
+        // isolated samples are not exported
+        assert!(!messages.contains_key(&g02));
+
+        let oem = sp3.to_oem(&OemOptions::default()).unwrap();
+        assert_eq!(oem.segments.len(), 1);
+
+        let segment = &messages[&g01].segments[0];
+
+        // isolated G01 sample (#11) is not exported either
+        assert_eq!(segment.states.len(), 21);
+        assert!(segment
+            .states
+            .iter()
+            .all(|state| state.epoch != t0 + dt * 11.0));
+
+        for state in segment.states.iter() {
+            let phase = rate_rad_s * (state.epoch - t0).to_seconds();
+            let (sin, cos) = phase.sin_cos();
+            let expected = (-speed_km_s * sin, speed_km_s * cos, 0.0);
+
+            let error_m_s = (
+                (state.velocity_km_s.0 - expected.0) * 1.0E3,
+                (state.velocity_km_s.1 - expected.1) * 1.0E3,
+            );
+
+            // including the edges of each arc
+            assert!(
+                error_m_s.0.abs() < 1.0E-3 && error_m_s.1.abs() < 1.0E-3,
+                "{}: velocity error {:?} m/s",
+                state.epoch,
+                error_m_s
+            );
+        }
+    }
+
+    #[test]
+    fn oem_kvn_parsing() {
+        let content = "CCSDS_OEM_VERS = 2.0
+COMMENT  Example with day of year epochs
+CREATION_DATE = 2020-177T12:00:00
+ORIGINATOR = TEST
+
+META_START
+OBJECT_NAME = GPS BIIR-2
+OBJECT_ID = G13
+CENTER_NAME = EARTH
+REF_FRAME = ITRF-97
+TIME_SYSTEM = UTC
+START_TIME = 2020-177T00:00:00.000
+USEABLE_START_TIME = 2020-177T00:00:00.000
+USEABLE_STOP_TIME = 2020-177T00:30:00.000
+STOP_TIME = 2020-177T00:30:00.000
+INTERPOLATION = LAGRANGE
+INTERPOLATION_DEGREE = 7
+META_STOP
+
+COMMENT  state vectors
+2020-177T00:00:00.000 26000.0 1000.0 -2000.0 0.1 3.5 -0.2
+2020-177T00:15:00.000 26001.0 1001.0 -2001.0 0.1 3.5 -0.2 0.0 0.0 0.0
+2020-177T00:30:00.000 26002.0 1002.0 -2002.0 0.1 3.5 -0.2
+
+COVARIANCE_START
+EPOCH = 2020-177T00:00:00.000
+1.0e-4
+1.0e-7 4.0e-4
+0.0 0.0 9.0e-4
+0.0 0.0 0.0 1.0e-10
+0.0 0.0 0.0 0.0 1.0e-10
+0.0 0.0 0.0 0.0 0.0 1.0e-10
+EPOCH = 2020-177T00:15:00.000
+COV_REF_FRAME = RTN
+1.0
+0.0 1.0
+0.0 0.0 1.0
+0.0 0.0 0.0 1.0
+0.0 0.0 0.0 0.0 1.0
+0.0 0.0 0.0 0.0 0.0 1.0
+COVARIANCE_STOP
+";
+
+        let oem = Oem::from_reader(&mut BufReader::new(content.as_bytes())).unwrap();
+
+        assert_eq!(oem.originator, "TEST");
+        assert_eq!(oem.comments, vec!["Example with day of year epochs"]);
+
+        let segment = &oem.segments[0];
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 UTC").unwrap();
+
+        assert_eq!(segment.metadata.time_system, TimeScale::UTC);
+        assert_eq!(segment.metadata.start_time, t0);
+        assert_eq!(
+            segment.metadata.interpolation,
+            Some(OemInterpolation::Lagrange(7))
+        );
+        assert_eq!(segment.states.len(), 3);
+        assert!(segment.states[1].acceleration_km_s2.is_some());
+        assert_eq!(segment.covariances.len(), 2);
+        assert_eq!(segment.covariances[0].element(0, 1), 1.0E-7);
+        assert_eq!(segment.covariances[1].ref_frame.as_deref(), Some("RTN"));
+
+        let sp3 = crate::prelude::SP3::from_oem(&oem).unwrap();
+        let g13 = SV::from_str("G13").unwrap();
+
+        assert_eq!(sp3.header.coord_system, "ITR97");
+        assert_eq!(sp3.header.time_system, TimeSystem::UTC);
+        assert_eq!(sp3.header.satellites, vec![g13]);
+        assert_eq!(sp3.total_epochs(), 3);
+
+        let entries = sp3.data.values().collect::<Vec<_>>();
+
+        let std_dev_mm = entries[0].position_std_dev_mm.unwrap();
+        assert!((std_dev_mm.0 - 1.0E4).abs() < 1.0E-6);
+        assert!((std_dev_mm.2 - 3.0E4).abs() < 1.0E-6);
+
+        // covariance expressed in another frame
+        assert!(entries[1].position_std_dev_mm.is_none());
+        assert!(entries[2].position_std_dev_mm.is_none());
+    }
+}
@@ -0,0 +1,438 @@
+//! CCSDS OEM XML notation
+use std::{
+    collections::HashMap,
+    io::{BufWriter, Result as IoResult, Write},
+};
+
+use super::{
+    format_epoch, parse_epoch, parse_number, Oem, OemCovariance, OemMetadata, OemSegment, OemState,
+    COMPONENTS,
+};
+
+use crate::{errors::OemError, prelude::TimeScale};
+
+/// Acceleration components, in XML notation
+const ACCELERATIONS: [&str; 3] = ["X_DDOT", "Y_DDOT", "Z_DDOT"];
+
+/// Escapes this text content
+fn escape(content: &str) -> String {
+    content
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Unescapes this text content
+fn unescape(content: &str) -> String {
+    content
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Writes one leaf element
+fn write_leaf<W: Write>(
+    w: &mut BufWriter<W>,
+    indent: usize,
+    name: &str,
+    value: &str,
+) -> IoResult<()> {
+    writeln!(
+        w,
+        "{:indent$}<{name}>{}</{name}>",
+        "",
+        escape(value),
+        indent = indent,
+        name = name
+    )
+}
+
+/// Formats this [Oem] in XML
+pub(crate) fn format<W: Write>(oem: &Oem, w: &mut BufWriter<W>) -> IoResult<()> {
+    writeln!(w, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        w,
+        "<oem id=\"CCSDS_OEM_VERS\" version=\"{}\">",
+        escape(&oem.version)
+    )?;
+
+    writeln!(w, "  <header>")?;
+
+    for comment in oem.comments.iter() {
+        write_leaf(w, 4, "COMMENT", comment)?;
+    }
+
+    write_leaf(
+        w,
+        4,
+        "CREATION_DATE",
+        &format_epoch(oem.creation_date, TimeScale::UTC),
+    )?;
+    write_leaf(w, 4, "ORIGINATOR", &oem.originator)?;
+    writeln!(w, "  </header>")?;
+
+    writeln!(w, "  <body>")?;
+
+    for segment in oem.segments.iter() {
+        let metadata = &segment.metadata;
+
+        writeln!(w, "    <segment>")?;
+        writeln!(w, "      <metadata>")?;
+
+        for (keyword, value) in metadata.keywords() {
+            write_leaf(w, 8, keyword, &value)?;
+        }
+
+        writeln!(w, "      </metadata>")?;
+        writeln!(w, "      <data>")?;
+
+        for comment in segment.comments.iter() {
+            write_leaf(w, 8, "COMMENT", comment)?;
+        }
+
+        for state in segment.states.iter() {
+            let (x_km, y_km, z_km) = state.position_km;
+            let (vx_km_s, vy_km_s, vz_km_s) = state.velocity_km_s;
+
+            writeln!(w, "        <stateVector>")?;
+            write_leaf(w, 10, "EPOCH", &metadata.format_epoch(state.epoch))?;
+
+            for (name, value) in [("X", x_km), ("Y", y_km), ("Z", z_km)] {
+                write_leaf(w, 10, name, &format!("{:.6}", value))?;
+            }
+
+            for (name, value) in [("X_DOT", vx_km_s), ("Y_DOT", vy_km_s), ("Z_DOT", vz_km_s)] {
+                write_leaf(w, 10, name, &format!("{:.9}", value))?;
+            }
+
+            if let Some((ax, ay, az)) = state.acceleration_km_s2 {
+                for (name, value) in ACCELERATIONS.iter().zip([ax, ay, az]) {
+                    write_leaf(w, 10, name, &format!("{:.12}", value))?;
+                }
+            }
+
+            writeln!(w, "        </stateVector>")?;
+        }
+
+        for covariance in segment.covariances.iter() {
+            writeln!(w, "        <covarianceMatrix>")?;
+            write_leaf(w, 10, "EPOCH", &metadata.format_epoch(covariance.epoch))?;
+
+            if let Some(ref_frame) = &covariance.ref_frame {
+                write_leaf(w, 10, "COV_REF_FRAME", ref_frame)?;
+            }
+
+            for row in 0..6 {
+                for col in 0..=row {
+                    write_leaf(
+                        w,
+                        10,
+                        &OemCovariance::element_name(row, col),
+                        &format!("{:.7e}", covariance.element(row, col)),
+                    )?;
+                }
+            }
+
+            writeln!(w, "        </covarianceMatrix>")?;
+        }
+
+        writeln!(w, "      </data>")?;
+        writeln!(w, "    </segment>")?;
+    }
+
+    writeln!(w, "  </body>")?;
+    writeln!(w, "</oem>")?;
+    Ok(())
+}
+
+/// XML [Element], with its attributes, text content and children
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: HashMap<String, String>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    /// Returns first child named `name`
+    fn child(&self, name: &str) -> Option<&Self> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns all children named `name`
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Self> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Returns all COMMENT children
+    fn comments(&self) -> Vec<String> {
+        self.children("COMMENT")
+            .map(|comment| comment.text.trim().to_string())
+            .collect()
+    }
+
+    /// Returns leaf children, other than comments, as keyword values
+    fn keywords(&self) -> HashMap<String, String> {
+        self.children
+            .iter()
+            .filter(|child| child.children.is_empty() && child.name != "COMMENT")
+            .map(|child| (child.name.clone(), child.text.trim().to_string()))
+            .collect()
+    }
+}
+
+/// Minimal XML reader: elements, attributes, text, comments and declarations.
+struct Reader<'a> {
+    content: &'a str,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> &'a str {
+        &self.content[self.offset..]
+    }
+
+    fn malformed(&self) -> OemError {
+        let remaining = self.remaining();
+        let end = remaining
+            .char_indices()
+            .nth(32)
+            .map(|(i, _)| i)
+            .unwrap_or(remaining.len());
+        OemError::Malformed(remaining[..end].to_string())
+    }
+
+    /// Skips content up to and including `pattern`
+    fn skip_past(&mut self, pattern: &str) -> Result<(), OemError> {
+        let position = self
+            .remaining()
+            .find(pattern)
+            .ok_or_else(|| self.malformed())?;
+        self.offset += position + pattern.len();
+        Ok(())
+    }
+
+    /// Skips whitespaces, declarations and comments
+    fn skip_misc(&mut self) -> Result<(), OemError> {
+        loop {
+            let trimmed = self.remaining().trim_start();
+            self.offset = self.content.len() - trimmed.len();
+
+            if trimmed.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if trimmed.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if trimmed.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parses one element, starting at its opening tag
+    fn element(&mut self) -> Result<Element, OemError> {
+        if !self.remaining().starts_with('<') {
+            return Err(self.malformed());
+        }
+
+        let end = self.remaining().find('>').ok_or_else(|| self.malformed())?;
+        let tag = &self.remaining()[1..end];
+        self.offset += end + 1;
+
+        let (tag, closed) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+
+        let mut element = Element::default();
+
+        let (name, mut attributes) = tag
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((tag.trim(), ""));
+
+        // namespaces are ignored
+        element.name = local_name(name).to_string();
+
+        while let Some((key, value)) = attributes.split_once('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().ok_or_else(|| self.malformed())?;
+            let value = &value[1..];
+            let end = value.find(quote).ok_or_else(|| self.malformed())?;
+
+            element
+                .attributes
+                .insert(local_name(key.trim()).to_string(), unescape(&value[..end]));
+
+            attributes = &value[end + 1..];
+        }
+
+        if closed {
+            return Ok(element);
+        }
+
+        loop {
+            let next = self.remaining().find('<').ok_or_else(|| self.malformed())?;
+            element.text.push_str(&unescape(&self.remaining()[..next]));
+            self.offset += next;
+
+            let remaining = self.remaining();
+
+            if remaining.starts_with("</") {
+                let end = remaining.find('>').ok_or_else(|| self.malformed())?;
+
+                if local_name(remaining[2..end].trim()) != element.name {
+                    return Err(self.malformed());
+                }
+
+                self.offset += end + 1;
+                return Ok(element);
+            } else if remaining.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = remaining.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").ok_or_else(|| self.malformed())?;
+                element.text.push_str(&cdata[..end]);
+                self.offset += "<![CDATA[".len() + end + "]]>".len();
+            } else {
+                element.children.push(self.element()?);
+            }
+        }
+    }
+}
+
+/// Strips the namespace prefix of this name
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Parses [OemState] from this stateVector element
+fn state(element: &Element, timescale: TimeScale) -> Result<OemState, OemError> {
+    let keywords = element.keywords();
+
+    let keyword = |name: &str| {
+        keywords
+            .get(name)
+            .map(|value| value.as_str())
+            .ok_or_else(|| OemError::MissingKeyword(name.to_string()))
+    };
+
+    let mut values = vec![keyword("EPOCH")?];
+
+    for name in COMPONENTS {
+        values.push(keyword(name)?);
+    }
+
+    if keywords.contains_key(ACCELERATIONS[0]) {
+        for name in ACCELERATIONS {
+            values.push(keyword(name)?);
+        }
+    }
+
+    OemState::from_values(&values, timescale)
+}
+
+/// Parses [OemCovariance] from this covarianceMatrix element
+fn covariance(element: &Element, timescale: TimeScale) -> Result<OemCovariance, OemError> {
+    let keywords = element.keywords();
+
+    let epoch = keywords
+        .get("EPOCH")
+        .ok_or_else(|| OemError::MissingKeyword("EPOCH".to_string()))?;
+
+    let mut lower_triangle = [0.0; 21];
+
+    for row in 0..6 {
+        for col in 0..=row {
+            let name = OemCovariance::element_name(row, col);
+            let value = keywords.get(&name).ok_or(OemError::MissingKeyword(name))?;
+            lower_triangle[OemCovariance::index(row, col)] = parse_number(value)?;
+        }
+    }
+
+    Ok(OemCovariance {
+        epoch: parse_epoch(epoch, timescale)?,
+        ref_frame: keywords.get("COV_REF_FRAME").cloned(),
+        lower_triangle,
+    })
+}
+
+/// Parses [Oem] from XML content
+pub(crate) fn parse(content: &str) -> Result<Oem, OemError> {
+    let mut reader = Reader { content, offset: 0 };
+    reader.skip_misc()?;
+
+    let root = reader.element()?;
+
+    if root.name != "oem" {
+        return Err(OemError::Malformed(root.name));
+    }
+
+    let header = root
+        .child("header")
+        .ok_or_else(|| OemError::MissingKeyword("header".to_string()))?;
+
+    let keywords = header.keywords();
+
+    let keyword = |name: &str| {
+        keywords
+            .get(name)
+            .cloned()
+            .ok_or_else(|| OemError::MissingKeyword(name.to_string()))
+    };
+
+    let mut segments = Vec::new();
+
+    for element in root
+        .child("body")
+        .map(|body| body.children("segment").collect::<Vec<_>>())
+        .unwrap_or_default()
+    {
+        let metadata = element
+            .child("metadata")
+            .ok_or_else(|| OemError::MissingKeyword("metadata".to_string()))?;
+
+        let mut segment = OemSegment {
+            metadata: OemMetadata::from_keywords(&metadata.keywords())?,
+            comments: metadata.comments(),
+            states: Vec::new(),
+            covariances: Vec::new(),
+        };
+
+        let timescale = segment.metadata.time_system;
+
+        if let Some(data) = element.child("data") {
+            segment.comments.extend(data.comments());
+
+            for state_vector in data.children("stateVector") {
+                segment.states.push(state(state_vector, timescale)?);
+            }
+
+            for matrix in data.children("covarianceMatrix") {
+                segment.covariances.push(covariance(matrix, timescale)?);
+            }
+        }
+
+        segments.push(segment);
+    }
+
+    if segments.is_empty() {
+        return Err(OemError::NoData);
+    }
+
+    Ok(Oem {
+        version: root
+            .attributes
+            .get("version")
+            .cloned()
+            .ok_or_else(|| OemError::MissingKeyword("version".to_string()))?,
+        creation_date: parse_epoch(&keyword("CREATION_DATE")?, TimeScale::UTC)?,
+        originator: keyword("ORIGINATOR")?,
+        comments: header.comments(),
+        segments,
+    })
+}
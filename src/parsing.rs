@@ -1,7 +1,7 @@
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::{BufRead, BufReader, Read},
+    io::{BufRead, BufReader, Cursor, Read},
     path::Path,
     str::FromStr,
 };
@@ -9,15 +9,22 @@ use std::{
 #[cfg(feature = "flate2")]
 use flate2::read::GzDecoder;
 
+#[cfg(feature = "bzip2")]
+use bzip2::read::BzDecoder;
+
+#[cfg(feature = "zstd")]
+use zstd::stream::read::Decoder as ZstdDecoder;
+
 use crate::{
     header::{
         line1::{is_header_line1, Line1},
         line2::{is_header_line2, Line2},
     },
+    lzw,
     position::{position_entry, PositionEntry},
     prelude::{
-        Constellation, Epoch, Error, Header, ParsingError, ProductionAttributes, SP3Entry, SP3Key,
-        TimeScale, Version, SP3, SV,
+        Constellation, Epoch, Error, FileCompression, Header, ParsingError, ProductionAttributes,
+        SP3Entry, SP3Key, TimeScale, TimeSystem, Version, SP3, SV,
     },
     velocity::{velocity_entry, VelocityEntry},
 };
@@ -26,6 +33,29 @@ fn file_descriptor(content: &str) -> bool {
     content.starts_with("%c")
 }
 
+fn float_descriptor(content: &str) -> bool {
+    content.starts_with("%f")
+}
+
+/// Standard deviations from exponents in this base, scaled to the desired unit
+fn std_devs(
+    base: f64,
+    exponents: Option<(i32, i32, i32)>,
+    scaling: f64,
+) -> Option<(f64, f64, f64)> {
+    let (x, y, z) = exponents?;
+
+    if base > 0.0 {
+        Some((
+            base.powi(x) * scaling,
+            base.powi(y) * scaling,
+            base.powi(z) * scaling,
+        ))
+    } else {
+        None
+    }
+}
+
 fn sp3_comment(content: &str) -> bool {
     content.starts_with("/*")
 }
@@ -58,7 +88,7 @@ fn parse_epoch(content: &str, timescale: TimeScale) -> Result<Epoch, ParsingErro
 impl SP3 {
     /// Parse [SP3] data from local file.
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
-        let fd = File::open(&path).unwrap_or_else(|e| panic!("File open error: {}", e));
+        let fd = File::open(&path)?;
         let mut reader = BufReader::new(fd);
         let mut sp3 = Self::from_reader(&mut reader)?;
 
@@ -75,7 +105,7 @@ impl SP3 {
     #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
     /// Parse [SP3] data from gzip encoded local file.
     pub fn from_gzip_file(path: impl AsRef<Path>) -> Result<Self, Error> {
-        let fd = File::open(&path).unwrap_or_else(|e| panic!("File open error: {}", e));
+        let fd = File::open(&path)?;
         let fd = GzDecoder::new(fd);
         let mut reader = BufReader::new(fd);
 
@@ -90,9 +120,60 @@ impl SP3 {
         Ok(sp3)
     }
 
+    /// Parse [SP3] data from local file, that may be compressed or not.
+    /// The decompression algorithm is determined from the file content
+    /// (magic bytes), not the file extension. Unix compress (`.Z`) is always
+    /// supported, gzip requires the `flate2` feature, bzip2 the `bzip2` feature
+    /// and zstandard the `zstd` feature.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_path("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let attributes = sp3.prod_attributes.unwrap();
+    /// assert_eq!(attributes.compression, Some(FileCompression::Gzip));
+    ///
+    /// // plain files are supported as well
+    /// let sp3 = SP3::from_path("data/SP3/C/co108870.sp3")
+    ///     .unwrap();
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut fd = BufReader::new(File::open(&path)?);
+        let compression = FileCompression::from_magic_bytes(fd.fill_buf()?);
+
+        let reader: Box<dyn Read> = match compression {
+            None => Box::new(fd),
+            Some(FileCompression::Unix) => {
+                let mut content = Vec::new();
+                fd.read_to_end(&mut content)?;
+                Box::new(Cursor::new(lzw::decompress(&content)?))
+            },
+            #[cfg(feature = "flate2")]
+            Some(FileCompression::Gzip) => Box::new(GzDecoder::new(fd)),
+            #[cfg(feature = "bzip2")]
+            Some(FileCompression::Bzip2) => Box::new(BzDecoder::new(fd)),
+            #[cfg(feature = "zstd")]
+            Some(FileCompression::Zstd) => Box::new(ZstdDecoder::with_buffer(fd)?),
+            #[allow(unreachable_patterns)]
+            Some(compression) => return Err(Error::UnsupportedCompression(compression)),
+        };
+
+        let mut sp3 = Self::from_reader(&mut BufReader::new(reader))?;
+
+        if let Some(filename) = path.as_ref().file_name() {
+            if let Ok(attributes) = ProductionAttributes::from_str(&filename.to_string_lossy()) {
+                sp3.prod_attributes = Some(attributes);
+            }
+        }
+
+        Ok(sp3)
+    }
+
     /// Parse [SP3] data from [Read]able I/O.
     pub fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Result<Self, Error> {
         let mut pc_count = 0_u8;
+        let mut pf_count = 0_u8;
         let mut header = Header::default();
         let mut timescale = TimeScale::default();
 
@@ -151,20 +232,43 @@ impl SP3 {
                 // no need to parse this line, since Rev-A is limited
                 // to GPS-Only
                 if header.version == Version::A {
+                    timescale = TimeScale::GPST;
                     header.constellation = Constellation::GPS;
-                    header.timescale = TimeScale::GPST;
+                    header.timescale = timescale;
+                    header.time_system = TimeSystem::GPS;
                 } else {
                     // Constellation identification needs to pass
                     if pc_count == 0 {
                         header.constellation = Constellation::from_str(line[3..5].trim())?;
-                        timescale = TimeScale::from_str(line[9..12].trim())?;
+                        let time_system = TimeSystem::from_str(line[9..12].trim())?;
+                        timescale = time_system.timescale();
                         header.timescale = timescale;
+                        header.time_system = time_system;
                     }
                 }
 
                 pc_count += 1;
             }
 
+            if float_descriptor(line) {
+                // only the first line is defined
+                if pf_count == 0 {
+                    let mut bases = line[2..].split_whitespace();
+
+                    header.position_velocity_base = bases
+                        .next()
+                        .and_then(|base| f64::from_str(base).ok())
+                        .unwrap_or_default();
+
+                    header.clock_base = bases
+                        .next()
+                        .and_then(|base| f64::from_str(base).ok())
+                        .unwrap_or_default();
+                }
+
+                pf_count += 1;
+            }
+
             if new_epoch(line) {
                 epoch = parse_epoch(&line[3..], timescale)?;
             }
@@ -226,6 +330,20 @@ impl SP3 {
 
                         data.insert(key, value);
                     }
+
+                    // mm
+                    let std_dev_mm =
+                        std_devs(header.position_velocity_base, entry.std_dev_exponents, 1.0);
+
+                    if let (Some(e), Some(std_dev_mm)) = (
+                        data.get_mut(&SP3Key {
+                            epoch,
+                            sv: entry.sv,
+                        }),
+                        std_dev_mm,
+                    ) {
+                        e.position_std_dev_mm = Some(std_dev_mm);
+                    }
                 }
             }
 
@@ -276,6 +394,19 @@ impl SP3 {
                             );
                         }
                     }
+
+                    // 10⁻⁴ mm/s
+                    let std_dev_mm_s = std_devs(
+                        header.position_velocity_base,
+                        entry.std_dev_exponents(),
+                        1.0E-4,
+                    );
+
+                    if let (Some(e), Some(std_dev_mm_s)) =
+                        (data.get_mut(&SP3Key { epoch, sv }), std_dev_mm_s)
+                    {
+                        e.velocity_std_dev_mm_s = Some(std_dev_mm_s);
+                    }
                 }
             }
         }
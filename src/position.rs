@@ -10,6 +10,15 @@ pub fn position_entry(content: &str) -> bool {
     content.starts_with('P')
 }
 
+/// Parses the (X, Y, Z) standard deviation exponents of a P or V record.
+/// Blank fields mean the standard deviations are unknown.
+pub fn std_dev_exponents(line: &str) -> Option<(i32, i32, i32)> {
+    let x = line.get(61..63)?.trim().parse::<i32>().ok()?;
+    let y = line.get(64..66)?.trim().parse::<i32>().ok()?;
+    let z = line.get(67..69)?.trim().parse::<i32>().ok()?;
+    Some((x, y, z))
+}
+
 pub struct PositionEntry {
     pub sv: SV,
     pub x_km: f64,
@@ -20,6 +29,7 @@ pub struct PositionEntry {
     pub clock_prediction: bool,
     pub maneuver: bool,
     pub orbit_prediction: bool,
+    pub std_dev_exponents: Option<(i32, i32, i32)>,
 }
 
 impl PositionEntry {
@@ -55,7 +65,9 @@ impl PositionEntry {
         let z = f64::from_str(line[32..46].trim())
             .or(Err(ParsingError::Coordinates(line[32..46].to_string())))?;
 
-        if line_len > 51 && !line[45..52].trim().eq("999999.") {
+        let clock_blank = line.get(46..60).is_none_or(|clock| clock.trim().is_empty());
+
+        if line_len > 51 && !clock_blank && !line[45..52].trim().eq("999999.") {
             // clock data present
             let clk_data = f64::from_str(line[46..60].trim())
                 .or(Err(ParsingError::Clock(line[46..60].to_string())))?;
@@ -85,6 +97,7 @@ impl PositionEntry {
             clock_prediction,
             orbit_prediction,
             maneuver,
+            std_dev_exponents: std_dev_exponents(line),
             x_km: x,
             y_km: y,
             z_km: z,
@@ -197,6 +210,22 @@ mod test {
             assert_eq!(entry.maneuver, maneuver);
             assert_eq!(entry.orbit_prediction, orbit_prediction);
         }
+
+        let entry = PositionEntry::parse(
+            "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102",
+            Version::C,
+        )
+        .unwrap();
+
+        assert_eq!(entry.std_dev_exponents, Some((10, 9, 11)));
+
+        let entry = PositionEntry::parse(
+            "PC01 -32312.652253  27060.656563    205.195454     63.035497",
+            Version::C,
+        )
+        .unwrap();
+
+        assert_eq!(entry.std_dev_exponents, None);
     }
 
     #[test]
@@ -0,0 +1,486 @@
+use crate::{
+    prelude::{
+        Epoch, HelmertEstimate, HelmertEstimationOptions, OrbitType, SP3Entry, SP3Key, SP3, SV,
+    },
+    Vector3D,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [CombinationOptions] to tune [SP3::combine].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CombinationOptions {
+    /// Agency to be declared in the combined product
+    pub agency: String,
+
+    /// [HelmertEstimationOptions] used when aligning each product
+    /// to the preliminary combination. Alignment is skipped when None.
+    pub helmert: Option<HelmertEstimationOptions>,
+
+    /// Outlier rejection threshold: the contribution of one product to one [SV]
+    /// is rejected when its RMS exceeds this factor times the median RMS
+    /// of all products for that [SV].
+    pub outlier_threshold: f64,
+
+    /// Number of weighting / rejection iterations
+    pub iterations: usize,
+}
+
+impl Default for CombinationOptions {
+    fn default() -> Self {
+        Self {
+            agency: "IGS".to_string(),
+            helmert: Some(HelmertEstimationOptions::default()),
+            outlier_threshold: 5.0,
+            iterations: 3,
+        }
+    }
+}
+
+impl CombinationOptions {
+    /// Copies and returns [CombinationOptions] with desired agency.
+    pub fn with_agency(&self, agency: &str) -> Self {
+        let mut s = self.clone();
+        s.agency = agency.to_string();
+        s
+    }
+
+    /// Copies and returns [CombinationOptions] without Helmert alignment.
+    pub fn without_helmert_alignment(&self) -> Self {
+        let mut s = self.clone();
+        s.helmert = None;
+        s
+    }
+}
+
+/// [CombinationContribution] describes how one product contributed to the combination.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CombinationContribution {
+    /// Agency that published this product
+    pub agency: String,
+
+    /// [HelmertEstimate] used to align this product, if any
+    pub helmert: Option<HelmertEstimate>,
+
+    /// Position residual RMS with respect to the combined orbits, in meters
+    pub rms_m: f64,
+
+    /// Normalized weight of this product
+    pub weight: f64,
+
+    /// [SV]s for which this product was rejected
+    pub rejected: Vec<SV>,
+}
+
+/// [CombinationReport] obtained with [SP3::combine].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CombinationReport {
+    /// One [CombinationContribution] per input product, in input order
+    pub contributions: Vec<CombinationContribution>,
+}
+
+/// Weighted mean position per [SP3Key], of all non rejected contributions.
+fn weighted_positions(
+    products: &[SP3],
+    weights: &[f64],
+    rejected: &[BTreeSet<SV>],
+) -> BTreeMap<SP3Key, Vector3D> {
+    let mut sums = BTreeMap::<SP3Key, (Vector3D, f64)>::new();
+
+    for (i, product) in products.iter().enumerate() {
+        for (k, v) in product.data.iter() {
+            if rejected[i].contains(&k.sv) || v.position_km == (0.0, 0.0, 0.0) {
+                continue;
+            }
+
+            let (sum, w) = sums.entry(k.clone()).or_default();
+            sum.0 += v.position_km.0 * weights[i];
+            sum.1 += v.position_km.1 * weights[i];
+            sum.2 += v.position_km.2 * weights[i];
+            *w += weights[i];
+        }
+    }
+
+    sums.into_iter()
+        .filter(|(_, (_, w))| *w > 0.0)
+        .map(|(k, (sum, w))| (k, (sum.0 / w, sum.1 / w, sum.2 / w)))
+        .collect()
+}
+
+/// Position residual RMS (in meters) of one product per [SV], with respect to `mean`.
+fn rms_per_satellite(product: &SP3, mean: &BTreeMap<SP3Key, Vector3D>) -> BTreeMap<SV, f64> {
+    let mut sums = BTreeMap::<SV, (f64, usize)>::new();
+
+    for (k, v) in product.data.iter() {
+        if v.position_km == (0.0, 0.0, 0.0) {
+            continue;
+        }
+
+        if let Some(x) = mean.get(k) {
+            let dx_m = (
+                (v.position_km.0 - x.0) * 1.0E3,
+                (v.position_km.1 - x.1) * 1.0E3,
+                (v.position_km.2 - x.2) * 1.0E3,
+            );
+
+            let (sum, count) = sums.entry(k.sv).or_default();
+            *sum += dx_m.0.powi(2) + dx_m.1.powi(2) + dx_m.2.powi(2);
+            *count += 1;
+        }
+    }
+
+    sums.into_iter()
+        .map(|(sv, (sum, count))| (sv, (sum / count as f64).sqrt()))
+        .collect()
+}
+
+impl SP3 {
+    /// Combines several [SP3] products (typically, from different analysis centers
+    /// and for the same day) into a single combined [SP3], the way the IGS does:
+    /// - products are expressed in the reference frame of the first product
+    /// - each product is aligned to a preliminary (unweighted) combination
+    ///   with a Helmert transformation
+    /// - contributions of one product to one [SV] whose RMS exceeds
+    ///   [CombinationOptions::outlier_threshold] times the median RMS are rejected
+    /// - each product is weighted by the inverse of its squared residual RMS
+    /// - positions are averaged, as well as clock offsets once each product clock
+    ///   datum has been aligned to the first product providing clocks.
+    ///   Sentinel clock values (999999.999999) do not contribute.
+    ///
+    /// The combined [SP3] is declared as [OrbitType::FIT] and published by
+    /// [CombinationOptions::agency]. Returns None when no products are provided.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let products = vec![sp3.clone(), sp3.clone(), sp3.clone()];
+    ///
+    /// let (combined, report) = SP3::combine(&products, &CombinationOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(combined.header.agency, "IGS");
+    /// assert_eq!(combined.header.orbit_type, OrbitType::FIT);
+    /// assert_eq!(report.contributions.len(), 3);
+    /// ```
+    pub fn combine(
+        products: &[SP3],
+        options: &CombinationOptions,
+    ) -> Option<(SP3, CombinationReport)> {
+        let first = products.first()?;
+
+        // express all products in the same frame, when feasible
+        let mut aligned = products
+            .iter()
+            .map(|product| match first.reference_frame() {
+                Ok(frame) => product
+                    .to_reference_frame(frame)
+                    .unwrap_or_else(|_| product.clone()),
+                Err(_) => product.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let size = aligned.len();
+        let mut weights = vec![1.0; size];
+        let mut rejected = vec![BTreeSet::<SV>::new(); size];
+        let mut helmert = vec![None; size];
+
+        // Helmert alignment to preliminary combination
+        if let Some(helmert_options) = &options.helmert {
+            let preliminary = weighted_positions(&aligned, &weights, &rejected);
+
+            let reference = SP3 {
+                data: preliminary
+                    .iter()
+                    .map(|(k, x)| (k.clone(), SP3Entry::from_position_km(*x)))
+                    .collect(),
+                ..Default::default()
+            };
+
+            for (i, product) in aligned.iter_mut().enumerate() {
+                helmert[i] = product.helmert_align_mut(&reference, helmert_options);
+            }
+        }
+
+        // iterative weighting and rejection
+        let mut rms_m = vec![0.0; size];
+        let mut combined = weighted_positions(&aligned, &weights, &rejected);
+
+        for _ in 0..options.iterations {
+            let per_satellite = aligned
+                .iter()
+                .map(|product| rms_per_satellite(product, &combined))
+                .collect::<Vec<_>>();
+
+            let satellites = per_satellite
+                .iter()
+                .flat_map(|rms| rms.keys().copied())
+                .collect::<BTreeSet<_>>();
+
+            for sv in satellites.iter() {
+                let mut values = per_satellite
+                    .iter()
+                    .filter_map(|rms| rms.get(sv).copied())
+                    .collect::<Vec<_>>();
+
+                values.sort_by(|a, b| a.total_cmp(b));
+
+                let median = values[values.len() / 2];
+
+                for (i, rms) in per_satellite.iter().enumerate() {
+                    if let Some(rms) = rms.get(sv) {
+                        // residuals below the SP3 resolution are never rejected
+                        if *rms > (options.outlier_threshold * median).max(1.0E-3) {
+                            rejected[i].insert(*sv);
+                        }
+                    }
+                }
+            }
+
+            for (i, rms) in per_satellite.iter().enumerate() {
+                let (sum, count) = rms
+                    .iter()
+                    .filter(|(sv, _)| !rejected[i].contains(sv))
+                    .fold((0.0, 0), |(sum, count), (_, rms)| {
+                        (sum + rms.powi(2), count + 1)
+                    });
+
+                rms_m[i] = if count > 0 {
+                    (sum / count as f64).sqrt()
+                } else {
+                    0.0
+                };
+            }
+
+            // SP3 resolution (1 mm) prevents infinite weights
+            for (i, rms_m) in rms_m.iter().enumerate() {
+                weights[i] = 1.0 / rms_m.max(1.0E-3).powi(2);
+            }
+
+            combined = weighted_positions(&aligned, &weights, &rejected);
+        }
+
+        let total_weight = weights.iter().sum::<f64>();
+
+        // clock datum of each product, per epoch, with respect to the first product with clocks
+        let clock_reference = aligned.iter().position(|p| p.has_satellite_clock_offset());
+        let mut clock_datums = vec![BTreeMap::<Epoch, f64>::new(); size];
+
+        if let Some(reference) = clock_reference {
+            for (i, product) in aligned.iter().enumerate() {
+                let mut sums = BTreeMap::<Epoch, (f64, usize)>::new();
+
+                for (k, v) in product.data.iter() {
+                    if let Some(rhs) = aligned[reference].data.get(k) {
+                        if let (Some(lhs_us), Some(rhs_us)) =
+                            (v.valid_clock_offset_us(), rhs.valid_clock_offset_us())
+                        {
+                            if !v.clock_event && !rhs.clock_event {
+                                let (sum, count) = sums.entry(k.epoch).or_default();
+                                *sum += lhs_us - rhs_us;
+                                *count += 1;
+                            }
+                        }
+                    }
+                }
+
+                clock_datums[i] = sums
+                    .into_iter()
+                    .map(|(t, (sum, count))| (t, sum / count as f64))
+                    .collect();
+            }
+        }
+
+        // combined product
+        let mut sp3 = SP3 {
+            header: first.header.clone(),
+            comments: Default::default(),
+            prod_attributes: None,
+            data: Default::default(),
+        };
+
+        for (k, position_km) in combined.iter() {
+            let contributions = aligned
+                .iter()
+                .enumerate()
+                .filter_map(|(i, product)| {
+                    if rejected[i].contains(&k.sv) {
+                        None
+                    } else {
+                        Some((i, product.data.get(k)?))
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // null (missing) positions do not contribute to the orbit
+            let orbits = contributions
+                .iter()
+                .filter(|(_, v)| v.position_km != (0.0, 0.0, 0.0))
+                .collect::<Vec<_>>();
+
+            let mut entry = SP3Entry::from_position_km(*position_km);
+
+            entry.predicted_orbit = orbits.iter().all(|(_, v)| v.predicted_orbit);
+            entry.maneuver = orbits.iter().any(|(_, v)| v.maneuver);
+            entry.clock_event = contributions.iter().any(|(_, v)| v.clock_event);
+
+            if orbits.iter().all(|(_, v)| v.velocity_km_s.is_some()) {
+                let (mut sum, mut w) = ((0.0, 0.0, 0.0), 0.0);
+                for (i, v) in orbits.iter() {
+                    if let Some(velocity_km_s) = v.velocity_km_s {
+                        sum.0 += velocity_km_s.0 * weights[*i];
+                        sum.1 += velocity_km_s.1 * weights[*i];
+                        sum.2 += velocity_km_s.2 * weights[*i];
+                        w += weights[*i];
+                    }
+                }
+                entry.velocity_km_s = Some((sum.0 / w, sum.1 / w, sum.2 / w));
+            }
+
+            let clocks = contributions
+                .iter()
+                .filter_map(|(i, v)| {
+                    if v.clock_event {
+                        return None;
+                    }
+                    let datum_us = clock_datums[*i].get(&k.epoch)?;
+                    Some((*i, v.valid_clock_offset_us()? - datum_us, v.predicted_clock))
+                })
+                .collect::<Vec<_>>();
+
+            if !clocks.is_empty() {
+                let (sum, w) = clocks
+                    .iter()
+                    .fold((0.0, 0.0), |(sum, w), (i, clock_us, _)| {
+                        (sum + clock_us * weights[*i], w + weights[*i])
+                    });
+
+                entry.clock_us = Some(sum / w);
+                entry.predicted_clock = clocks.iter().all(|(_, _, predicted)| *predicted);
+            }
+
+            sp3.data.insert(k.clone(), entry);
+        }
+
+        // header
+        sp3.header.agency = options.agency.clone();
+        sp3.header.orbit_type = OrbitType::FIT;
+        sp3.rebuild_header_mut();
+
+        let report = CombinationReport {
+            contributions: aligned
+                .iter()
+                .enumerate()
+                .map(|(i, product)| CombinationContribution {
+                    agency: product.header.agency.clone(),
+                    helmert: helmert[i].clone(),
+                    rms_m: rms_m[i],
+                    weight: weights[i] / total_weight,
+                    rejected: rejected[i].iter().copied().collect(),
+                })
+                .collect(),
+        };
+
+        Some((sp3, report))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{CombinationOptions, Epoch, OrbitType, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    fn product(agency: &str, offset_km: f64, clock_datum_us: f64) -> SP3 {
+        let mut sp3 = SP3::default();
+        sp3.header.agency = agency.to_string();
+        sp3.header.orbit_type = OrbitType::BHN;
+
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+
+        for (prn, position_km) in [
+            (1, (15_000.0, 20_000.0, 5_000.0)),
+            (2, (-20_000.0, 10_000.0, 12_000.0)),
+            (3, (5_000.0, -25_000.0, 8_000.0)),
+        ] {
+            let sv = SV::from_str(&format!("G{:02}", prn)).unwrap();
+            sp3.data.insert(
+                SP3Key { sv, epoch: t0 },
+                SP3Entry::from_position_km((
+                    position_km.0 + offset_km,
+                    position_km.1,
+                    position_km.2,
+                ))
+                .with_clock_offset_us(prn as f64 + clock_datum_us),
+            );
+        }
+
+        sp3
+    }
+
+    #[test]
+    fn orbit_combination() {
+        let mut products = vec![
+            product("AAA", 0.0, 0.0),
+            product("BBB", 2.0E-6, 1.0),
+            product("CCC", -2.0E-6, -1.0),
+            product("DDD", 1.0E-6, 2.0),
+            product("EEE", 1.0, 0.5), // 1 km outlier
+        ];
+
+        // sentinel clock value: does not contribute
+        for (k, v) in products[1].data.iter_mut() {
+            if k.sv.prn == 2 {
+                v.clock_us = Some(999999.999999);
+            }
+        }
+
+        // null (missing) position: does not contribute to the orbit
+        for (k, v) in products[2].data.iter_mut() {
+            if k.sv.prn == 1 {
+                v.position_km = (0.0, 0.0, 0.0);
+                v.velocity_km_s = Some((0.0, 0.0, 0.0));
+                v.predicted_orbit = true;
+                v.maneuver = true;
+            }
+        }
+
+        let options = CombinationOptions::default()
+            .with_agency("COM")
+            .without_helmert_alignment();
+
+        let (combined, report) = SP3::combine(&products, &options).unwrap();
+
+        assert_eq!(combined.header.agency, "COM");
+        assert_eq!(combined.header.orbit_type, OrbitType::FIT);
+        assert_eq!(combined.header.satellites.len(), 3);
+        assert_eq!(combined.header.num_epochs, 1);
+
+        assert_eq!(report.contributions.len(), 5);
+        assert_eq!(report.contributions[4].rejected.len(), 3);
+        assert!(report.contributions[0].rejected.is_empty());
+        assert!(report.contributions[2].rejected.is_empty());
+
+        // best products have the largest weight
+        assert!(report.contributions[0].weight > report.contributions[1].weight);
+
+        for (k, v) in combined.data.iter() {
+            let reference = products[0].data.get(k).unwrap();
+
+            assert!((v.position_km.0 - reference.position_km.0).abs() < 1.0E-5);
+            assert!((v.position_km.1 - reference.position_km.1).abs() < 1.0E-9);
+            assert!(!v.predicted_orbit && !v.maneuver);
+            assert!(v.velocity_km_s.is_none());
+
+            // clock datums are aligned to the first product
+            let clock_us = v.clock_us.unwrap();
+            assert!((clock_us - reference.clock_us.unwrap()).abs() < 1.0E-9);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{
+    lagrange_interpolation,
+    math::{inertial_velocity, rtn_basis, rtn_projection, scale, sub},
+    prelude::{Duration, Epoch, RTNResidual, RTNStatistics, SP3Entry, SP3Key, SP3, SV},
+    Vector3D,
+};
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [BoundaryDiscontinuity] between two consecutive orbit products,
+/// obtained with [SP3::boundary_discontinuity] or [SP3::day_boundaries_discontinuity].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundaryDiscontinuity {
+    /// [Epoch] both orbits were extrapolated to: halfway
+    /// between the last epoch of the first product and the first epoch of the following one.
+    pub epoch: Epoch,
+
+    /// Orbit discontinuity (following - previous product), per [SV],
+    /// in the radial, along-track and cross-track frame of the previous orbit.
+    pub satellites: BTreeMap<SV, RTNResidual>,
+
+    /// [RTNStatistics] over all satellites
+    pub statistics: RTNStatistics,
+}
+
+/// Extrapolates position (km) and velocity (km.s⁻¹) to `t`,
+/// using Lagrange interpolation over all provided points.
+fn extrapolate(points: &[(Epoch, Vector3D)], t: Epoch) -> Option<(Vector3D, Vector3D)> {
+    let order = points.len().checked_sub(1)?;
+    let dt = Duration::from_milliseconds(500.0);
+
+    let position_km = lagrange_interpolation(order, t, points.to_vec())?;
+    let p0 = lagrange_interpolation(order, t - dt, points.to_vec())?;
+    let p1 = lagrange_interpolation(order, t + dt, points.to_vec())?;
+
+    Some((position_km, sub(p1, p0)))
+}
+
+/// Evaluates the discontinuity between the data of `previous` strictly prior `boundary`,
+/// and the data of `next` past `boundary` (included).
+fn discontinuity(
+    previous: &BTreeMap<SP3Key, SP3Entry>,
+    next: &BTreeMap<SP3Key, SP3Entry>,
+    boundary: Epoch,
+    order: usize,
+) -> Option<BoundaryDiscontinuity> {
+    let mut before = BTreeMap::<SV, Vec<(Epoch, Vector3D)>>::new();
+    let mut after = BTreeMap::<SV, Vec<(Epoch, Vector3D)>>::new();
+
+    for (k, v) in previous.iter() {
+        if k.epoch < boundary && !v.maneuver && v.position_km != (0.0, 0.0, 0.0) {
+            before
+                .entry(k.sv)
+                .or_default()
+                .push((k.epoch, v.position_km));
+        }
+    }
+
+    for (k, v) in next.iter() {
+        if k.epoch >= boundary && !v.maneuver && v.position_km != (0.0, 0.0, 0.0) {
+            after
+                .entry(k.sv)
+                .or_default()
+                .push((k.epoch, v.position_km));
+        }
+    }
+
+    let last_before = before
+        .values()
+        .filter_map(|p| p.last())
+        .map(|p| p.0)
+        .max()?;
+    let first_after = after
+        .values()
+        .filter_map(|p| p.first())
+        .map(|p| p.0)
+        .min()?;
+
+    let epoch = last_before + (first_after - last_before) * 0.5;
+
+    let mut satellites = BTreeMap::<SV, RTNResidual>::new();
+
+    for (sv, before) in before.iter() {
+        let Some(after) = after.get(sv) else {
+            continue;
+        };
+
+        // both sides must be available right next to the boundary
+        if before.len() < order + 1 || after.len() < order + 1 {
+            continue;
+        }
+
+        if before[before.len() - 1].0 != last_before || after[0].0 != first_after {
+            continue;
+        }
+
+        let Some((previous_km, previous_km_s)) =
+            extrapolate(&before[before.len() - order - 1..], epoch)
+        else {
+            continue;
+        };
+
+        let Some((next_km, _)) = extrapolate(&after[..order + 1], epoch) else {
+            continue;
+        };
+
+        let velocity_km_s = inertial_velocity(previous_km, previous_km_s);
+
+        let Some(basis) = rtn_basis(previous_km, velocity_km_s) else {
+            continue;
+        };
+
+        let residual_m = scale(sub(next_km, previous_km), 1.0E3);
+        let (radial_m, along_track_m, cross_track_m) = rtn_projection(residual_m, &basis);
+
+        satellites.insert(
+            *sv,
+            RTNResidual {
+                radial_m,
+                along_track_m,
+                cross_track_m,
+            },
+        );
+    }
+
+    let residuals = satellites.values().copied().collect::<Vec<_>>();
+
+    Some(BoundaryDiscontinuity {
+        epoch,
+        satellites,
+        statistics: RTNStatistics::from_residuals(&residuals),
+    })
+}
+
+impl SP3 {
+    /// Evaluates the orbit discontinuity between this [SP3] and the `next`
+    /// (consecutive) product. Each orbit is extrapolated to the middle of the boundary,
+    /// with Lagrange interpolation of this order, using the `order + 1`
+    /// closest epochs of its own side. Epochs of this product that are also
+    /// covered by `next` are not used. Satellites being maneuvered next to the boundary,
+    /// or not present on both sides right next to the boundary, are not reported.
+    /// Null positions are considered missing.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let day1 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let day2 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let boundary = day1.boundary_discontinuity(&day2, 9)
+    ///     .unwrap();
+    ///
+    /// for (sv, discontinuity) in boundary.satellites.iter() {
+    ///     println!("{}: {:.3} m", sv, discontinuity.norm_m());
+    /// }
+    /// ```
+    pub fn boundary_discontinuity(
+        &self,
+        next: &Self,
+        order: usize,
+    ) -> Option<BoundaryDiscontinuity> {
+        let boundary = next.first_epoch()?;
+        discontinuity(&self.data, &next.data, boundary, order)
+    }
+
+    /// Evaluates the orbit discontinuity at each day boundary (midnight, in the
+    /// [crate::prelude::TimeScale] of this record) of this multi-day [SP3],
+    /// typically obtained by merging consecutive daily products.
+    /// See [Self::boundary_discontinuity] for more information.
+    pub fn day_boundaries_discontinuity(&self, order: usize) -> Vec<BoundaryDiscontinuity> {
+        let (Some(first), Some(last)) = (self.first_epoch(), self.last_epoch()) else {
+            return Vec::new();
+        };
+
+        let day = Duration::from_days(1.0);
+        let mut boundary = first.floor(day) + day;
+        let mut discontinuities = Vec::new();
+
+        while boundary <= last {
+            if let Some(value) = discontinuity(&self.data, &self.data, boundary, order) {
+                discontinuities.push(value);
+            }
+            boundary += day;
+        }
+
+        discontinuities
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn boundary_discontinuity() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+        let (radius_km, rate_rad_s) = (26_560.0, 1.458E-4);
+
+        let (mut day1, mut day2) = (SP3::default(), SP3::default());
+
+        // 2 days, day 2 is 10cm higher
+        for i in 0..192 {
+            let epoch = t0 + dt * i as f64;
+            let phase = rate_rad_s * 900.0 * i as f64;
+
+            let radius_km = if i < 96 {
+                radius_km
+            } else {
+                radius_km + 1.0E-4
+            };
+
+            let (sin, cos) = phase.sin_cos();
+            let position_km = (
+                radius_km * cos * 0.6,
+                radius_km * sin,
+                radius_km * cos * 0.8,
+            );
+
+            let entry = SP3Entry::from_position_km(position_km);
+
+            // G02: missing position right before the boundary
+            let g02_entry = if i == 95 {
+                SP3Entry::from_position_km((0.0, 0.0, 0.0))
+            } else {
+                entry
+            };
+
+            if i < 96 {
+                day1.data.insert(SP3Key { sv: g01, epoch }, entry);
+                day1.data.insert(SP3Key { sv: g02, epoch }, g02_entry);
+            } else {
+                day2.data.insert(SP3Key { sv: g01, epoch }, entry);
+                day2.data.insert(SP3Key { sv: g02, epoch }, g02_entry);
+            }
+        }
+
+        let boundary = day1.boundary_discontinuity(&day2, 9).unwrap();
+
+        assert_eq!(boundary.epoch, t0 + dt * 95.5);
+
+        let g01_discontinuity = boundary.satellites.get(&g01).unwrap();
+
+        assert!((g01_discontinuity.radial_m - 0.1).abs() < 0.02);
+        assert!(g01_discontinuity.along_track_m.abs() < 0.02);
+        assert!(g01_discontinuity.cross_track_m.abs() < 1.0E-3);
+        assert_eq!(boundary.statistics.norm_3d_m.count, 1);
+        assert!(!boundary.satellites.contains_key(&g02));
+
+        // merged dataset
+        let mut merged = day1.clone();
+        merged.data.extend(day2.data.clone());
+
+        let boundaries = merged.day_boundaries_discontinuity(9);
+        assert_eq!(boundaries.len(), 1);
+        assert_eq!(boundaries[0], boundary);
+
+        // missing data next to the boundary
+        let boundary = day1.boundary_discontinuity(&day2, 100).unwrap();
+        assert!(boundary.satellites.is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    math::{inertial_velocity, rtn_basis, rtn_projection, scale, sub, Statistics},
+    prelude::{BroadcastEphemeris, Constellation, Epoch, RTNResidual, RTNStatistics, SP3, SV},
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Speed of light in m.s⁻¹
+const SPEED_OF_LIGHT_M_S: f64 = 299_792_458.0;
+
+/// [BroadcastResidual] between broadcast and precise states (broadcast - precise).
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BroadcastResidual {
+    /// Orbit error, projected onto the precise RTN frame
+    pub orbit: RTNResidual,
+
+    /// Clock error in meters, once the per-epoch mean of the
+    /// constellation has been removed. None when clocks are not available.
+    pub clock_m: Option<f64>,
+
+    /// Orbit only signal-in-space range error, in meters
+    pub sisre_orbit_m: f64,
+
+    /// Signal-in-space range error, in meters.
+    /// None when clocks are not available.
+    pub sisre_m: Option<f64>,
+}
+
+/// [BroadcastStatistics] of each [BroadcastResidual] component.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BroadcastStatistics {
+    /// Orbit error [RTNStatistics], in meters
+    pub orbit: RTNStatistics,
+
+    /// Clock error [Statistics], in meters
+    pub clock_m: Statistics,
+
+    /// Orbit only SISRE [Statistics], in meters
+    pub sisre_orbit_m: Statistics,
+
+    /// SISRE [Statistics], in meters
+    pub sisre_m: Statistics,
+}
+
+impl BroadcastStatistics {
+    fn from_residuals(residuals: &[BroadcastResidual]) -> Self {
+        let orbit = residuals.iter().map(|r| r.orbit).collect::<Vec<_>>();
+        let clock = residuals
+            .iter()
+            .filter_map(|r| r.clock_m)
+            .collect::<Vec<_>>();
+        let sisre_orbit = residuals
+            .iter()
+            .map(|r| r.sisre_orbit_m)
+            .collect::<Vec<_>>();
+        let sisre = residuals
+            .iter()
+            .filter_map(|r| r.sisre_m)
+            .collect::<Vec<_>>();
+
+        Self {
+            orbit: RTNStatistics::from_residuals(&orbit),
+            clock_m: Statistics::from_values(&clock),
+            sisre_orbit_m: Statistics::from_values(&sisre_orbit),
+            sisre_m: Statistics::from_values(&sisre),
+        }
+    }
+}
+
+/// [BroadcastComparison] report, obtained with [SP3::broadcast_comparison].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BroadcastComparison {
+    /// Number of [Epoch]s that contributed
+    pub epochs: usize,
+
+    /// [BroadcastStatistics] per [SV]
+    pub satellites: BTreeMap<SV, BroadcastStatistics>,
+
+    /// [BroadcastStatistics] per [Constellation]
+    pub constellations: BTreeMap<Constellation, BroadcastStatistics>,
+}
+
+/// SISRE weight factors (radial, along and cross-track squared),
+/// for typical orbit altitudes and Earth visibility cones.
+fn sisre_weights(sv: SV) -> (f64, f64) {
+    match sv.constellation {
+        Constellation::Glonass => (0.98, 1.0 / 45.0),
+        Constellation::Galileo => (0.98, 1.0 / 61.0),
+        Constellation::QZSS => (0.99, 1.0 / 126.0),
+        Constellation::BeiDou => match sv.prn {
+            // GEO & IGSO
+            1..=10 | 13 | 16 | 31 | 38..=40 | 56 | 59..=63 => (0.99, 1.0 / 126.0),
+            _ => (0.98, 1.0 / 54.0),
+        },
+        _ => (0.98, 1.0 / 49.0),
+    }
+}
+
+impl SP3 {
+    /// Forms an [Iterator] of [BroadcastResidual]s, evaluating the broadcast ephemerides
+    /// against this (precise) [SP3], for each [Epoch] and [SV] for which a valid
+    /// message exists. Satellites being maneuvered are excluded.
+    /// Broadcast and precise clocks refer to different datums: the per-epoch
+    /// mean clock difference of each constellation is removed prior SISRE evaluation.
+    /// Note that broadcast orbits usually refer to the antenna phase center,
+    /// while precise orbits refer to the center of mass.
+    ///
+    /// ## Output
+    /// - [Epoch] : sampling epoch
+    /// - [SV] : satellite identity
+    /// - [BroadcastResidual]
+    pub fn satellites_broadcast_residuals_iter(
+        &self,
+        ephemerides: &[BroadcastEphemeris],
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, BroadcastResidual)> + '_> {
+        let mut residuals = Vec::<(Epoch, SV, BroadcastResidual, Option<f64>)>::new();
+        let mut clock_datums = BTreeMap::<(Epoch, Constellation), (f64, usize)>::new();
+
+        for (k, v) in self.data.iter() {
+            if v.maneuver {
+                continue;
+            }
+
+            let Some(eph) = BroadcastEphemeris::select(ephemerides, k.sv, k.epoch) else {
+                continue;
+            };
+
+            let Some(velocity_km_s) = self.satellite_velocity_km_s(k.sv, k.epoch) else {
+                continue;
+            };
+
+            let velocity_km_s = inertial_velocity(v.position_km, velocity_km_s);
+
+            let Some(basis) = rtn_basis(v.position_km, velocity_km_s) else {
+                continue;
+            };
+
+            let error_m = scale(sub(eph.position_km(k.epoch), v.position_km), 1.0E3);
+            let (radial_m, along_track_m, cross_track_m) = rtn_projection(error_m, &basis);
+
+            let (w_r, w_ac2) = sisre_weights(k.sv);
+
+            let residual = BroadcastResidual {
+                orbit: RTNResidual {
+                    radial_m,
+                    along_track_m,
+                    cross_track_m,
+                },
+                clock_m: None,
+                sisre_orbit_m: ((w_r * radial_m).powi(2)
+                    + w_ac2 * (along_track_m.powi(2) + cross_track_m.powi(2)))
+                .sqrt(),
+                sisre_m: None,
+            };
+
+            let clock_m = match v.valid_clock_offset_us() {
+                Some(clock_us) if !v.clock_event => {
+                    let clock_m =
+                        (eph.clock_offset_s(k.epoch) - clock_us * 1.0E-6) * SPEED_OF_LIGHT_M_S;
+
+                    let (sum, count) = clock_datums
+                        .entry((k.epoch, k.sv.constellation))
+                        .or_default();
+
+                    *sum += clock_m;
+                    *count += 1;
+
+                    Some(clock_m)
+                },
+                _ => None,
+            };
+
+            residuals.push((k.epoch, k.sv, residual, clock_m));
+        }
+
+        Box::new(
+            residuals
+                .into_iter()
+                .map(move |(epoch, sv, mut residual, clock_m)| {
+                    if let Some(clock_m) = clock_m {
+                        if let Some((sum, count)) = clock_datums.get(&(epoch, sv.constellation)) {
+                            let clock_m = clock_m - sum / *count as f64;
+                            let (w_r, w_ac2) = sisre_weights(sv);
+
+                            residual.clock_m = Some(clock_m);
+                            residual.sisre_m = Some(
+                                ((w_r * residual.orbit.radial_m - clock_m).powi(2)
+                                    + w_ac2
+                                        * (residual.orbit.along_track_m.powi(2)
+                                            + residual.orbit.cross_track_m.powi(2)))
+                                .sqrt(),
+                            );
+                        }
+                    }
+                    (epoch, sv, residual)
+                }),
+        )
+    }
+
+    /// Compares broadcast ephemerides to this (precise) [SP3], returning the
+    /// [BroadcastComparison] report. See [Self::satellites_broadcast_residuals_iter]
+    /// for more information.
+    pub fn broadcast_comparison(&self, ephemerides: &[BroadcastEphemeris]) -> BroadcastComparison {
+        let mut epochs = BTreeSet::<Epoch>::new();
+        let mut satellites = BTreeMap::<SV, Vec<BroadcastResidual>>::new();
+        let mut constellations = BTreeMap::<Constellation, Vec<BroadcastResidual>>::new();
+
+        for (epoch, sv, residual) in self.satellites_broadcast_residuals_iter(ephemerides) {
+            epochs.insert(epoch);
+
+            satellites.entry(sv).or_default().push(residual);
+
+            constellations
+                .entry(sv.constellation)
+                .or_default()
+                .push(residual);
+        }
+
+        BroadcastComparison {
+            epochs: epochs.len(),
+            satellites: satellites
+                .iter()
+                .map(|(sv, residuals)| (*sv, BroadcastStatistics::from_residuals(residuals)))
+                .collect(),
+            constellations: constellations
+                .iter()
+                .map(|(c, residuals)| (*c, BroadcastStatistics::from_residuals(residuals)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{
+        BroadcastEphemeris, Duration, Epoch, KeplerianEphemeris, SP3Entry, SP3Key, SP3, SV,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn broadcast_self_consistency() {
+        let toe = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+
+        let ephemerides = ["G01", "G02"]
+            .iter()
+            .enumerate()
+            .map(|(i, sv)| {
+                BroadcastEphemeris::from(KeplerianEphemeris {
+                    sv: SV::from_str(sv).unwrap(),
+                    toe,
+                    toc: toe,
+                    sqrt_a: 5153.6,
+                    e: 0.01,
+                    i0: 0.96,
+                    m0: i as f64,
+                    af0: 1.0E-4 * (i + 1) as f64,
+                    ..Default::default()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // precise = broadcast, with a common clock datum
+        let mut sp3 = SP3::default();
+
+        for i in 0..8 {
+            let epoch = toe + Duration::from_seconds(900.0 * i as f64);
+
+            for eph in ephemerides.iter() {
+                let clock_us = eph.clock_offset_s(epoch) * 1.0E6 + 1.0;
+
+                sp3.data.insert(
+                    SP3Key {
+                        sv: eph.sv(),
+                        epoch,
+                    },
+                    SP3Entry::from_position_km(eph.position_km(epoch))
+                        .with_clock_offset_us(clock_us),
+                );
+            }
+        }
+
+        let report = sp3.broadcast_comparison(&ephemerides);
+
+        // 2h validity
+        assert_eq!(report.epochs, 8);
+        assert_eq!(report.satellites.len(), 2);
+
+        for (_, stats) in report.satellites.iter() {
+            assert_eq!(stats.sisre_m.count, 8);
+            assert!(stats.sisre_m.max < 1.0E-6);
+            assert!(stats.orbit.norm_3d_m.max < 1.0E-6);
+        }
+
+        // 1 m radial error on G01
+        let g01 = SV::from_str("G01").unwrap();
+        for (k, v) in sp3.data.iter_mut() {
+            if k.sv == g01 {
+                let r =
+                    (v.position_km.0.powi(2) + v.position_km.1.powi(2) + v.position_km.2.powi(2))
+                        .sqrt();
+                v.position_km.0 -= v.position_km.0 / r * 1.0E-3;
+                v.position_km.1 -= v.position_km.1 / r * 1.0E-3;
+                v.position_km.2 -= v.position_km.2 / r * 1.0E-3;
+            }
+        }
+
+        let report = sp3.broadcast_comparison(&ephemerides);
+        let stats = report.satellites.get(&g01).unwrap();
+
+        assert!((stats.orbit.radial_m.mean - 1.0).abs() < 1.0E-6);
+        assert!((stats.sisre_orbit_m.mean - 0.98).abs() < 1.0E-6);
+
+        // unknown clock (sentinel) on G02: clock residuals no longer available
+        let g02 = SV::from_str("G02").unwrap();
+        for (k, v) in sp3.data.iter_mut() {
+            if k.sv == g02 {
+                v.clock_us = Some(999_999.999999);
+            }
+        }
+
+        let report = sp3.broadcast_comparison(&ephemerides);
+        let stats = report.satellites.get(&g02).unwrap();
+
+        assert_eq!(stats.sisre_orbit_m.count, 8);
+        assert_eq!(stats.clock_m.count, 0);
+        assert_eq!(stats.sisre_m.count, 0);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{
+    math::Statistics,
+    prelude::{Epoch, SP3, SV},
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [ClockDatum] removed from the clock differences, prior comparison.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClockDatum {
+    /// Per-epoch mean of all common satellites is removed
+    #[default]
+    EpochMean,
+
+    /// Clock difference of this reference satellite is removed.
+    /// Epochs where it is not available are discarded.
+    ReferenceSatellite(SV),
+}
+
+/// [ClockComparisonOptions] to tune [SP3::clock_comparison].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockComparisonOptions {
+    /// [ClockDatum] to remove
+    pub datum: ClockDatum,
+
+    /// Remove constant bias of each satellite (over entire time frame)
+    pub remove_bias: bool,
+}
+
+impl ClockComparisonOptions {
+    /// Copies and returns [ClockComparisonOptions] using this reference [SV] as datum.
+    pub fn with_reference_satellite(&self, sv: SV) -> Self {
+        let mut s = *self;
+        s.datum = ClockDatum::ReferenceSatellite(sv);
+        s
+    }
+
+    /// Copies and returns [ClockComparisonOptions] with per satellite bias removal.
+    pub fn with_bias_removal(&self) -> Self {
+        let mut s = *self;
+        s.remove_bias = true;
+        s
+    }
+}
+
+/// [ClockComparison] report, obtained with [SP3::clock_comparison].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockComparison {
+    /// Number of [Epoch]s that contributed
+    pub epochs: usize,
+
+    /// Residual [Statistics] per [SV], in nanoseconds.
+    /// Use [Statistics::std_dev] for the standard deviation.
+    pub satellites: BTreeMap<SV, Statistics>,
+
+    /// Global residual [Statistics], in nanoseconds
+    pub global: Statistics,
+}
+
+impl SP3 {
+    /// Forms an [Iterator] of satellite clock residuals (Self - reference), in nanoseconds,
+    /// once the [ClockDatum] (and possibly, each satellite bias) has been removed.
+    /// Only the common [Epoch]s and [SV]s are considered, entries
+    /// affected by a clock event or a sentinel clock value (in either dataset) are discarded.
+    /// When using a reference satellite, it is not reported.
+    ///
+    /// ## Output
+    /// - [Epoch] : sampling epoch
+    /// - [SV] : satellite identity
+    /// - residual in nanoseconds
+    pub fn satellites_clock_residuals_ns_iter(
+        &self,
+        reference: &Self,
+        options: &ClockComparisonOptions,
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, f64)> + '_> {
+        let mut per_epoch = BTreeMap::<Epoch, Vec<(SV, f64)>>::new();
+
+        for (k, v) in self.data.iter() {
+            let Some(rhs) = reference.data.get(k) else {
+                continue;
+            };
+
+            if v.clock_event || rhs.clock_event {
+                continue;
+            }
+
+            if let (Some(lhs_us), Some(rhs_us)) =
+                (v.valid_clock_offset_us(), rhs.valid_clock_offset_us())
+            {
+                per_epoch
+                    .entry(k.epoch)
+                    .or_default()
+                    .push((k.sv, (lhs_us - rhs_us) * 1.0E3));
+            }
+        }
+
+        let mut residuals = Vec::<(Epoch, SV, f64)>::new();
+
+        for (epoch, diffs) in per_epoch.iter() {
+            let datum = match options.datum {
+                ClockDatum::EpochMean => {
+                    diffs.iter().map(|(_, dt)| dt).sum::<f64>() / diffs.len() as f64
+                },
+                ClockDatum::ReferenceSatellite(reference_sv) => {
+                    match diffs.iter().find(|(sv, _)| *sv == reference_sv) {
+                        Some((_, dt)) => *dt,
+                        None => continue,
+                    }
+                },
+            };
+
+            for (sv, dt) in diffs.iter() {
+                if options.datum != ClockDatum::ReferenceSatellite(*sv) {
+                    residuals.push((*epoch, *sv, dt - datum));
+                }
+            }
+        }
+
+        if options.remove_bias {
+            let mut biases = BTreeMap::<SV, (f64, usize)>::new();
+
+            for (_, sv, dt) in residuals.iter() {
+                let bias = biases.entry(*sv).or_default();
+                bias.0 += dt;
+                bias.1 += 1;
+            }
+
+            for (_, sv, dt) in residuals.iter_mut() {
+                if let Some((sum, count)) = biases.get(sv) {
+                    *dt -= sum / *count as f64;
+                }
+            }
+        }
+
+        Box::new(residuals.into_iter())
+    }
+
+    /// Compares the satellite clocks of this [SP3] to `reference` [SP3].
+    /// See [Self::satellites_clock_residuals_ns_iter] for more information.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let options = ClockComparisonOptions::default()
+    ///     .with_bias_removal();
+    ///
+    /// let report = sp3.clock_comparison(&sp3, &options);
+    ///
+    /// // self comparison is obviously null
+    /// assert_eq!(report.global.max, 0.0);
+    /// ```
+    pub fn clock_comparison(
+        &self,
+        reference: &Self,
+        options: &ClockComparisonOptions,
+    ) -> ClockComparison {
+        let mut epochs = BTreeSet::<Epoch>::new();
+        let mut global = Vec::<f64>::new();
+        let mut satellites = BTreeMap::<SV, Vec<f64>>::new();
+
+        for (epoch, sv, dt) in self.satellites_clock_residuals_ns_iter(reference, options) {
+            epochs.insert(epoch);
+            global.push(dt);
+            satellites.entry(sv).or_default().push(dt);
+        }
+
+        ClockComparison {
+            epochs: epochs.len(),
+            global: Statistics::from_values(&global),
+            satellites: satellites
+                .iter()
+                .map(|(sv, residuals)| (*sv, Statistics::from_values(residuals)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{ClockComparisonOptions, Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn clock_datum_and_bias_removal() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+
+        let svs = ["G01", "G02", "G03"]
+            .iter()
+            .map(|sv| SV::from_str(sv).unwrap())
+            .collect::<Vec<_>>();
+
+        let (mut lhs, mut rhs) = (SP3::default(), SP3::default());
+
+        for i in 0..4 {
+            let epoch = t0 + dt * i as f64;
+
+            // datum: 1 us per epoch, biases: 1/2/3 ns
+            for (j, sv) in svs.iter().enumerate() {
+                let clock_us = 100.0 + j as f64;
+                let datum_us = i as f64;
+                let bias_us = (j + 1) as f64 * 1.0E-3;
+
+                rhs.data.insert(
+                    SP3Key { sv: *sv, epoch },
+                    SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_clock_offset_us(clock_us),
+                );
+
+                let mut entry = SP3Entry::from_position_km((0.0, 0.0, 0.0))
+                    .with_clock_offset_us(clock_us + datum_us + bias_us);
+
+                // this one should be discarded
+                if i == 3 && j == 2 {
+                    entry.clock_event = true;
+                    entry.clock_us = Some(1.0E6);
+                }
+
+                // sentinel value: discarded as well
+                if i == 2 && j == 2 {
+                    entry.clock_us = Some(999999.999999);
+                }
+
+                lhs.data.insert(SP3Key { sv: *sv, epoch }, entry);
+            }
+        }
+
+        // reference satellite datum: residuals are bias differences
+        let options = ClockComparisonOptions::default().with_reference_satellite(svs[0]);
+        let report = lhs.clock_comparison(&rhs, &options);
+
+        assert_eq!(report.epochs, 4);
+        assert!(!report.satellites.contains_key(&svs[0]));
+
+        let g02 = report.satellites.get(&svs[1]).unwrap();
+        assert_eq!(g02.count, 4);
+        assert!((g02.mean - 1.0).abs() < 1.0E-6);
+        assert!(g02.std_dev() < 1.0E-6);
+
+        let g03 = report.satellites.get(&svs[2]).unwrap();
+        assert_eq!(g03.count, 2);
+        assert!((g03.mean - 2.0).abs() < 1.0E-6);
+
+        // bias removal: null residuals
+        let options = options.with_bias_removal();
+        let report = lhs.clock_comparison(&rhs, &options);
+        assert!(report.global.max < 1.0E-6);
+
+        // epoch mean datum
+        let report = lhs.clock_comparison(&rhs, &ClockComparisonOptions::default());
+        assert_eq!(report.satellites.len(), 3);
+
+        let g01 = report.satellites.get(&svs[0]).unwrap();
+        assert!((g01.rms - 0.625_f64.sqrt()).abs() < 1.0E-6);
+    }
+}
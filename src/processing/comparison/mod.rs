@@ -0,0 +1,9 @@
+mod boundary;
+mod broadcast;
+mod clock;
+mod orbit;
+
+pub use boundary::BoundaryDiscontinuity;
+pub use broadcast::{BroadcastComparison, BroadcastResidual, BroadcastStatistics};
+pub use clock::{ClockComparison, ClockComparisonOptions, ClockDatum};
+pub use orbit::{OrbitComparison, RTNResidual, RTNStatistics};
@@ -0,0 +1,247 @@
+use crate::{
+    math::{inertial_velocity, rtn_basis, rtn_projection, scale, sub, Statistics},
+    prelude::{Constellation, Epoch, SP3, SV},
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Position residual projected onto the radial, along-track
+/// and cross-track (RTN) frame of the reference orbit.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RTNResidual {
+    /// Radial component, in meters
+    pub radial_m: f64,
+
+    /// Along-track component, in meters
+    pub along_track_m: f64,
+
+    /// Cross-track component, in meters
+    pub cross_track_m: f64,
+}
+
+impl RTNResidual {
+    /// Returns 3D norm of this [RTNResidual], in meters
+    pub fn norm_m(&self) -> f64 {
+        (self.radial_m.powi(2) + self.along_track_m.powi(2) + self.cross_track_m.powi(2)).sqrt()
+    }
+}
+
+/// [Statistics] of each [RTNResidual] component.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RTNStatistics {
+    /// Radial [Statistics], in meters
+    pub radial_m: Statistics,
+
+    /// Along-track [Statistics], in meters
+    pub along_track_m: Statistics,
+
+    /// Cross-track [Statistics], in meters
+    pub cross_track_m: Statistics,
+
+    /// 3D norm [Statistics], in meters
+    pub norm_3d_m: Statistics,
+}
+
+impl RTNStatistics {
+    pub(crate) fn from_residuals(residuals: &[RTNResidual]) -> Self {
+        let radial = residuals.iter().map(|r| r.radial_m).collect::<Vec<_>>();
+        let along_track = residuals
+            .iter()
+            .map(|r| r.along_track_m)
+            .collect::<Vec<_>>();
+        let cross_track = residuals
+            .iter()
+            .map(|r| r.cross_track_m)
+            .collect::<Vec<_>>();
+        let norm_3d = residuals.iter().map(|r| r.norm_m()).collect::<Vec<_>>();
+
+        Self {
+            radial_m: Statistics::from_values(&radial),
+            along_track_m: Statistics::from_values(&along_track),
+            cross_track_m: Statistics::from_values(&cross_track),
+            norm_3d_m: Statistics::from_values(&norm_3d),
+        }
+    }
+}
+
+/// [OrbitComparison] report, obtained with [SP3::orbit_comparison].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrbitComparison {
+    /// Number of common [Epoch]s
+    pub epochs: usize,
+
+    /// [RTNStatistics] per [SV]
+    pub satellites: BTreeMap<SV, RTNStatistics>,
+
+    /// [RTNStatistics] per [Constellation]
+    pub constellations: BTreeMap<Constellation, RTNStatistics>,
+
+    /// Global [RTNStatistics]
+    pub global: RTNStatistics,
+}
+
+impl SP3 {
+    /// Forms an [Iterator] of position residuals (Self - reference) projected
+    /// onto the radial, along-track and cross-track frame of the `reference` orbit.
+    /// Only the common [Epoch]s and [SV]s are considered.
+    /// Satellites being maneuvered and null (missing) positions (in either dataset) are excluded.
+    /// The reference velocity is used to design the local frame. When not
+    /// published, it is estimated from the reference positions.
+    ///
+    /// ## Output
+    /// - [Epoch] : sampling epoch
+    /// - [SV] : satellite identity
+    /// - [RTNResidual] : residual in meters
+    pub fn satellites_rtn_residuals_iter<'a>(
+        &'a self,
+        reference: &'a Self,
+    ) -> Box<dyn Iterator<Item = (Epoch, SV, RTNResidual)> + 'a> {
+        Box::new(self.data.iter().filter_map(move |(k, v)| {
+            let reference_entry = reference.data.get(k)?;
+
+            if v.maneuver || reference_entry.maneuver {
+                return None;
+            }
+
+            if v.position_km == (0.0, 0.0, 0.0) || reference_entry.position_km == (0.0, 0.0, 0.0) {
+                return None;
+            }
+
+            let velocity_km_s = reference.satellite_velocity_km_s(k.sv, k.epoch)?;
+            let velocity_km_s = inertial_velocity(reference_entry.position_km, velocity_km_s);
+
+            let basis = rtn_basis(reference_entry.position_km, velocity_km_s)?;
+
+            let residual_m = scale(sub(v.position_km, reference_entry.position_km), 1.0E3);
+            let (radial_m, along_track_m, cross_track_m) = rtn_projection(residual_m, &basis);
+
+            Some((
+                k.epoch,
+                k.sv,
+                RTNResidual {
+                    radial_m,
+                    along_track_m,
+                    cross_track_m,
+                },
+            ))
+        }))
+    }
+
+    /// Compares the orbits of this [SP3] to `reference` [SP3], in the radial,
+    /// along-track and cross-track frame. See [Self::satellites_rtn_residuals_iter]
+    /// for more information.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let report = sp3.orbit_comparison(&sp3);
+    ///
+    /// // self comparison is obviously null
+    /// assert_eq!(report.global.norm_3d_m.max, 0.0);
+    /// ```
+    pub fn orbit_comparison(&self, reference: &Self) -> OrbitComparison {
+        let mut epochs = BTreeSet::<Epoch>::new();
+        let mut global = Vec::<RTNResidual>::new();
+        let mut satellites = BTreeMap::<SV, Vec<RTNResidual>>::new();
+        let mut constellations = BTreeMap::<Constellation, Vec<RTNResidual>>::new();
+
+        for (epoch, sv, residual) in self.satellites_rtn_residuals_iter(reference) {
+            epochs.insert(epoch);
+            global.push(residual);
+
+            satellites.entry(sv).or_default().push(residual);
+
+            constellations
+                .entry(sv.constellation)
+                .or_default()
+                .push(residual);
+        }
+
+        OrbitComparison {
+            epochs: epochs.len(),
+            global: RTNStatistics::from_residuals(&global),
+            satellites: satellites
+                .iter()
+                .map(|(sv, residuals)| (*sv, RTNStatistics::from_residuals(residuals)))
+                .collect(),
+            constellations: constellations
+                .iter()
+                .map(|(c, residuals)| (*c, RTNStatistics::from_residuals(residuals)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    fn circular_orbit(sv: SV, t0: Epoch, dt: Duration, n: usize, radial_offset_km: f64) -> SP3 {
+        let mut sp3 = SP3::default();
+        let radius_km = 26_560.0 + radial_offset_km;
+        let rate_rad_s = 1.458_4E-4;
+
+        for i in 0..n {
+            let epoch = t0 + dt * i as f64;
+            let phase = rate_rad_s * (epoch - t0).to_seconds();
+
+            sp3.data.insert(
+                SP3Key { sv, epoch },
+                SP3Entry::from_position_km((radius_km * phase.cos(), 0.0, radius_km * phase.sin())),
+            );
+        }
+
+        sp3.header.satellites.push(sv);
+        sp3
+    }
+
+    #[test]
+    fn radial_offset_comparison() {
+        let g01 = SV::from_str("G01").unwrap();
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+
+        let reference = circular_orbit(g01, t0, dt, 8, 0.0);
+        let offset = circular_orbit(g01, t0, dt, 8, 1.0E-3);
+
+        let report = offset.orbit_comparison(&reference);
+
+        assert_eq!(report.epochs, 8);
+        assert_eq!(report.satellites.len(), 1);
+        assert_eq!(report.constellations.len(), 1);
+
+        let stats = report.satellites.get(&g01).unwrap();
+        assert_eq!(stats.radial_m.count, 8);
+        assert!((stats.radial_m.rms - 1.0).abs() < 1.0E-6);
+        assert!((stats.radial_m.mean - 1.0).abs() < 1.0E-6);
+        assert!(stats.along_track_m.max < 1.0E-6);
+        assert!(stats.cross_track_m.max < 1.0E-6);
+        assert!((report.global.norm_3d_m.max - 1.0).abs() < 1.0E-6);
+
+        // self comparison
+        let report = reference.orbit_comparison(&reference);
+        assert_eq!(report.global.norm_3d_m.max, 0.0);
+
+        // null positions are not compared
+        let mut offset = offset;
+        let null = SP3Key {
+            sv: g01,
+            epoch: t0 + dt * 3.0,
+        };
+
+        offset.data.get_mut(&null).unwrap().position_km = (0.0, 0.0, 0.0);
+
+        let report = offset.orbit_comparison(&reference);
+        assert_eq!(report.epochs, 7);
+        assert!((report.global.norm_3d_m.max - 1.0).abs() < 1.0E-6);
+    }
+}
@@ -1,6 +1,8 @@
-use qc_traits::{Decimate, DecimationFilter, DecimationFilterType};
+use qc_traits::{Decimate, DecimationFilter, DecimationFilterType, FilterItem};
 
-use crate::prelude::{Epoch, Header, SP3};
+use crate::prelude::{Constellation, Epoch, Header, SP3, SV};
+
+use std::collections::BTreeSet;
 
 impl Decimate for Header {
     fn decimate(&self, f: &DecimationFilter) -> Self {
@@ -9,55 +11,90 @@ impl Decimate for Header {
         s
     }
     fn decimate_mut(&mut self, f: &DecimationFilter) {
-        match f.filter {
+        // targeted decimation does not modify the declared time frame
+        if f.item.is_some() {
+            return;
+        }
+
+        let modulo = match f.filter {
             DecimationFilterType::Duration(interval) => {
-                self.sampling_period = std::cmp::max(self.sampling_period, interval);
-            },
-            DecimationFilterType::Modulo(modulo) => {
-                self.sampling_period = self.sampling_period * modulo as f64;
+                if self.sampling_period.to_seconds() > 0.0 {
+                    (interval.to_seconds() / self.sampling_period.to_seconds()).ceil() as u64
+                } else {
+                    1
+                }
             },
+            DecimationFilterType::Modulo(modulo) => modulo as u64,
+        };
+
+        if modulo > 1 {
+            self.sampling_period = self.sampling_period * modulo as f64;
+            self.num_epochs = self.num_epochs.div_ceil(modulo);
         }
     }
 }
 
+/// Returns true if this [SV] is targeted by this [FilterItem]
+fn targeted(item: &Option<FilterItem>, sv: SV) -> bool {
+    match item {
+        None => true,
+        Some(FilterItem::SvItem(svs)) => svs.contains(&sv),
+        Some(FilterItem::ConstellationItem(constellations)) => {
+            let broad_sbas_filter = constellations.contains(&Constellation::SBAS);
+            constellations.contains(&sv.constellation)
+                || (broad_sbas_filter && sv.constellation.is_sbas())
+        },
+        _ => false, // does not apply
+    }
+}
+
 impl Decimate for SP3 {
     fn decimate(&self, f: &DecimationFilter) -> Self {
         let mut s = self.clone();
-        s.decimate_mut(&f);
+        s.decimate_mut(f);
         s
     }
+
+    /// Decimates this [SP3] record, epoch wise: all satellites are retained
+    /// (or dropped) on the same epochs. When [DecimationFilter::item] is defined,
+    /// only the satellites or constellations it describes are decimated,
+    /// others are preserved.
     fn decimate_mut(&mut self, f: &DecimationFilter) {
-        if f.item.is_some() {
-            todo!("targetted decimation not supported yet");
-        }
-        self.header.decimate_mut(f);
+        let mut retained = BTreeSet::<Epoch>::new();
 
         match f.filter {
             DecimationFilterType::Modulo(modulo) => {
-                let mut i = 0;
-                self.data.retain(|_, _| {
-                    let retained = (i % modulo) == 0;
-                    i += 1;
-                    retained
-                });
+                if modulo < 2 {
+                    return;
+                }
+
+                for (i, epoch) in self.epochs_iter().enumerate() {
+                    if i % modulo as usize == 0 {
+                        retained.insert(epoch);
+                    }
+                }
             },
             DecimationFilterType::Duration(interval) => {
                 let mut last_retained = Option::<Epoch>::None;
-                self.data.retain(|k, _| {
-                    if let Some(last) = last_retained {
-                        let dt = k.epoch - last;
-                        if dt >= interval {
-                            last_retained = Some(k.epoch);
-                            true
-                        } else {
-                            false
-                        }
-                    } else {
-                        last_retained = Some(k.epoch);
-                        true
+
+                for epoch in self.epochs_iter() {
+                    let retain = match last_retained {
+                        Some(last) => epoch - last >= interval,
+                        None => true,
+                    };
+
+                    if retain {
+                        last_retained = Some(epoch);
+                        retained.insert(epoch);
                     }
-                });
+                }
             },
         }
+
+        self.data
+            .retain(|k, _| retained.contains(&k.epoch) || !targeted(&f.item, k.sv));
+
+        self.header.decimate_mut(f);
+        self.rebuild_header_mut();
     }
 }
@@ -0,0 +1,489 @@
+use crate::{
+    helmert::{HelmertTransform, MAS_TO_RAD},
+    math::invert,
+    prelude::{Epoch, SP3, SV},
+    Vector3D,
+};
+
+use hifitime::Unit;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [HelmertEstimationOptions] to tune [SP3::helmert_estimate].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HelmertEstimationOptions {
+    /// Estimate the parameter rates as well (14-parameter transformation).
+    /// Only applies to multi-epoch estimation.
+    pub rates: bool,
+
+    /// Outlier rejection threshold, in units of the a posteriori
+    /// standard deviation of unit weight. Any satellite position
+    /// with at least one residual component exceeding this threshold
+    /// is rejected and the solution is iterated. Residuals below the
+    /// SP3 format resolution (1 mm) are never rejected.
+    pub outlier_threshold: f64,
+
+    /// Maximal number of outlier rejection iterations
+    pub max_iterations: usize,
+}
+
+impl Default for HelmertEstimationOptions {
+    fn default() -> Self {
+        Self {
+            rates: false,
+            outlier_threshold: 3.0,
+            max_iterations: 10,
+        }
+    }
+}
+
+impl HelmertEstimationOptions {
+    /// Copies and returns [HelmertEstimationOptions] with rates estimation.
+    pub fn with_rates(&self) -> Self {
+        let mut s = *self;
+        s.rates = true;
+        s
+    }
+
+    /// Copies and returns [HelmertEstimationOptions] with desired outlier threshold.
+    pub fn with_outlier_threshold(&self, threshold: f64) -> Self {
+        let mut s = *self;
+        s.outlier_threshold = threshold;
+        s
+    }
+}
+
+/// [HelmertEstimate] obtained with [SP3::helmert_estimate].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HelmertEstimate {
+    /// Estimated [HelmertTransform], that maps this dataset onto the reference.
+    pub transform: HelmertTransform,
+
+    /// Formal errors (1 sigma) of each parameter, expressed as [HelmertTransform].
+    pub sigmas: HelmertTransform,
+
+    /// A posteriori standard deviation of unit weight, in millimeters
+    pub sigma0_mm: f64,
+
+    /// Number of satellite positions that contributed to the solution
+    pub points: usize,
+
+    /// Satellite positions rejected as outliers
+    pub rejected: Vec<(Epoch, SV)>,
+}
+
+struct Observation {
+    epoch: Epoch,
+    sv: SV,
+    position_mm: Vector3D,
+    residual_mm: Vector3D,
+}
+
+/// Design matrix rows for one position. Parameters are sorted as
+/// (Tx, Ty, Tz, D, R1, R2, R3) followed by the rates, when estimated.
+fn design_rows(x: Vector3D, dt_yr: Option<f64>) -> [Vec<f64>; 3] {
+    let (d, r) = (1.0E-9, MAS_TO_RAD);
+
+    let mut rows = [
+        vec![1.0, 0.0, 0.0, x.0 * d, 0.0, x.2 * r, -x.1 * r],
+        vec![0.0, 1.0, 0.0, x.1 * d, -x.2 * r, 0.0, x.0 * r],
+        vec![0.0, 0.0, 1.0, x.2 * d, x.1 * r, -x.0 * r, 0.0],
+    ];
+
+    if let Some(dt_yr) = dt_yr {
+        for row in rows.iter_mut() {
+            let rates = row.iter().map(|a| a * dt_yr).collect::<Vec<_>>();
+            row.extend(rates);
+        }
+    }
+
+    rows
+}
+
+/// Least squares solution: returns parameters, their covariance matrix
+/// (scaled by sigma0²) and sigma0.
+fn solve(
+    observations: &[Observation],
+    reference_epoch: Epoch,
+    rates: bool,
+) -> Option<(Vec<f64>, Vec<Vec<f64>>, f64)> {
+    let size = if rates { 14 } else { 7 };
+    let dof = (3 * observations.len()).checked_sub(size)?;
+
+    if dof == 0 {
+        return None;
+    }
+
+    let mut normal = vec![vec![0.0; size]; size];
+    let mut rhs = vec![0.0; size];
+
+    for obs in observations.iter() {
+        let dt_yr = if rates {
+            Some((obs.epoch - reference_epoch).to_unit(Unit::Day) / 365.25)
+        } else {
+            None
+        };
+
+        let rows = design_rows(obs.position_mm, dt_yr);
+        let y = [obs.residual_mm.0, obs.residual_mm.1, obs.residual_mm.2];
+
+        for (row, y) in rows.iter().zip(y) {
+            for i in 0..size {
+                rhs[i] += row[i] * y;
+                for j in 0..size {
+                    normal[i][j] += row[i] * row[j];
+                }
+            }
+        }
+    }
+
+    let inverse = invert(&normal)?;
+
+    let params = (0..size)
+        .map(|i| (0..size).map(|j| inverse[i][j] * rhs[j]).sum::<f64>())
+        .collect::<Vec<_>>();
+
+    let mut sum_sq = 0.0;
+    for obs in observations.iter() {
+        let v = post_fit_residual(obs, &params, reference_epoch, rates);
+        sum_sq += v.0.powi(2) + v.1.powi(2) + v.2.powi(2);
+    }
+
+    let sigma0 = (sum_sq / dof as f64).sqrt();
+
+    let covariance = inverse
+        .iter()
+        .map(|row| row.iter().map(|q| q * sigma0.powi(2)).collect())
+        .collect();
+
+    Some((params, covariance, sigma0))
+}
+
+fn post_fit_residual(
+    obs: &Observation,
+    params: &[f64],
+    reference_epoch: Epoch,
+    rates: bool,
+) -> Vector3D {
+    let dt_yr = if rates {
+        Some((obs.epoch - reference_epoch).to_unit(Unit::Day) / 365.25)
+    } else {
+        None
+    };
+
+    let rows = design_rows(obs.position_mm, dt_yr);
+
+    let model = rows
+        .iter()
+        .map(|row| row.iter().zip(params).map(|(a, p)| a * p).sum::<f64>())
+        .collect::<Vec<_>>();
+
+    (
+        obs.residual_mm.0 - model[0],
+        obs.residual_mm.1 - model[1],
+        obs.residual_mm.2 - model[2],
+    )
+}
+
+fn to_transform(params: &[f64], reference_epoch: Epoch) -> HelmertTransform {
+    let transform = HelmertTransform {
+        translation_mm: (params[0], params[1], params[2]),
+        scale_ppb: params[3],
+        rotation_mas: (params[4], params[5], params[6]),
+        reference_epoch,
+        ..Default::default()
+    };
+
+    if params.len() == 14 {
+        transform.with_rates(
+            (params[7], params[8], params[9]),
+            params[10],
+            (params[11], params[12], params[13]),
+            reference_epoch,
+        )
+    } else {
+        transform
+    }
+}
+
+/// Estimates [HelmertEstimate] from given observations, with outlier rejection.
+fn estimate(
+    mut observations: Vec<Observation>,
+    reference_epoch: Epoch,
+    options: &HelmertEstimationOptions,
+) -> Option<HelmertEstimate> {
+    let mut rejected = Vec::<(Epoch, SV)>::new();
+
+    for iteration in 0..=options.max_iterations {
+        let (params, covariance, sigma0) = solve(&observations, reference_epoch, options.rates)?;
+
+        // residuals below the SP3 resolution (1 mm) are never rejected
+        let threshold = (options.outlier_threshold * sigma0).max(1.0);
+
+        let outliers = observations
+            .iter()
+            .filter_map(|obs| {
+                let v = post_fit_residual(obs, &params, reference_epoch, options.rates);
+                if v.0.abs() > threshold || v.1.abs() > threshold || v.2.abs() > threshold {
+                    Some((obs.epoch, obs.sv))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if outliers.is_empty() || iteration == options.max_iterations {
+            let sigmas = (0..params.len())
+                .map(|i| covariance[i][i].sqrt())
+                .collect::<Vec<_>>();
+
+            return Some(HelmertEstimate {
+                transform: to_transform(&params, reference_epoch),
+                sigmas: to_transform(&sigmas, reference_epoch),
+                sigma0_mm: sigma0,
+                points: observations.len(),
+                rejected,
+            });
+        }
+
+        observations.retain(|obs| !outliers.contains(&(obs.epoch, obs.sv)));
+        rejected.extend(outliers);
+    }
+
+    None
+}
+
+impl SP3 {
+    /// Collects the common (non maneuvered) satellite positions, in millimeters.
+    fn helmert_observations(&self, reference: &Self) -> Vec<Observation> {
+        self.data
+            .iter()
+            .filter_map(|(k, v)| {
+                let rhs = reference.data.get(k)?;
+
+                if v.maneuver || rhs.maneuver {
+                    return None;
+                }
+
+                if v.position_km == (0.0, 0.0, 0.0) || rhs.position_km == (0.0, 0.0, 0.0) {
+                    return None;
+                }
+
+                let position_mm = (
+                    v.position_km.0 * 1.0E6,
+                    v.position_km.1 * 1.0E6,
+                    v.position_km.2 * 1.0E6,
+                );
+
+                Some(Observation {
+                    epoch: k.epoch,
+                    sv: k.sv,
+                    position_mm,
+                    residual_mm: (
+                        rhs.position_km.0 * 1.0E6 - position_mm.0,
+                        rhs.position_km.1 * 1.0E6 - position_mm.1,
+                        rhs.position_km.2 * 1.0E6 - position_mm.2,
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Estimates the Helmert transformation that maps this [SP3] onto the `reference` [SP3],
+    /// over all common satellites and epochs (typically, one daily solution).
+    /// This is the 7-parameter transformation, or the 14-parameter transformation
+    /// (referenced to the middle of the common time frame) when rates are requested.
+    /// Maneuvered satellites are not taken into account, and outliers
+    /// are iteratively rejected. Returns None when there are not enough common
+    /// positions to solve the problem.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let shift = HelmertTransform::from_seven_parameters((10.0, -5.0, 2.0), 0.5, (0.0, 0.0, 0.1));
+    /// let shifted = sp3.helmert_transform(&shift);
+    ///
+    /// let estimate = shifted
+    ///     .helmert_estimate(&sp3, &HelmertEstimationOptions::default())
+    ///     .unwrap();
+    ///
+    /// // we recover the inverse transformation
+    /// assert!((estimate.transform.translation_mm.0 + 10.0).abs() < 1.0E-3);
+    /// assert!((estimate.transform.scale_ppb + 0.5).abs() < 1.0E-3);
+    ///
+    /// // apply to one dataset
+    /// let aligned = shifted.helmert_transform(&estimate.transform);
+    /// ```
+    pub fn helmert_estimate(
+        &self,
+        reference: &Self,
+        options: &HelmertEstimationOptions,
+    ) -> Option<HelmertEstimate> {
+        let observations = self.helmert_observations(reference);
+
+        let t0 = observations.iter().map(|obs| obs.epoch).min()?;
+        let t1 = observations.iter().map(|obs| obs.epoch).max()?;
+
+        estimate(observations, t0 + (t1 - t0) / 2, options)
+    }
+
+    /// Estimates one 7-parameter Helmert transformation per common [Epoch].
+    /// See [Self::helmert_estimate] for more information. Rates are never
+    /// estimated in this case. Epochs that cannot be solved are not reported.
+    pub fn helmert_estimates_per_epoch(
+        &self,
+        reference: &Self,
+        options: &HelmertEstimationOptions,
+    ) -> BTreeMap<Epoch, HelmertEstimate> {
+        let mut options = *options;
+        options.rates = false;
+
+        let mut per_epoch = BTreeMap::<Epoch, Vec<Observation>>::new();
+
+        for obs in self.helmert_observations(reference) {
+            per_epoch.entry(obs.epoch).or_default().push(obs);
+        }
+
+        per_epoch
+            .into_iter()
+            .filter_map(|(epoch, observations)| {
+                let estimate = estimate(observations, epoch, &options)?;
+                Some((epoch, estimate))
+            })
+            .collect()
+    }
+
+    /// Estimates the Helmert transformation to the `reference` [SP3]
+    /// (see [Self::helmert_estimate]) and applies it to Self, so both products are aligned.
+    /// Returns the [HelmertEstimate] on success, Self is not modified otherwise.
+    pub fn helmert_align_mut(
+        &mut self,
+        reference: &Self,
+        options: &HelmertEstimationOptions,
+    ) -> Option<HelmertEstimate> {
+        let estimate = self.helmert_estimate(reference, options)?;
+        self.helmert_transform_mut(&estimate.transform);
+        Some(estimate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{
+        Duration, Epoch, HelmertEstimationOptions, HelmertTransform, SP3Entry, SP3Key, SP3, SV,
+    };
+    use std::str::FromStr;
+
+    fn constellation(t0: Epoch, epochs: usize) -> SP3 {
+        let mut sp3 = SP3::default();
+        let dt = Duration::from_seconds(900.0);
+
+        for prn in 1..=24 {
+            let sv = SV::from_str(&format!("G{:02}", prn)).unwrap();
+            let (plane, slot) = ((prn % 6) as f64, (prn / 6) as f64);
+
+            for i in 0..epochs {
+                let epoch = t0 + dt * i as f64;
+                let u = slot * 1.57 + 1.4584E-4 * 900.0 * i as f64;
+                let (raan, inc) = (plane * 1.047, 0.96_f64);
+                let r = 26_560.0;
+
+                let (x, y) = (r * u.cos(), r * u.sin());
+                let position_km = (
+                    x * raan.cos() - y * inc.cos() * raan.sin(),
+                    x * raan.sin() + y * inc.cos() * raan.cos(),
+                    y * inc.sin(),
+                );
+
+                sp3.data.insert(
+                    SP3Key { sv, epoch },
+                    SP3Entry::from_position_km(position_km),
+                );
+            }
+        }
+
+        sp3
+    }
+
+    #[test]
+    fn helmert_estimation() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let reference = constellation(t0, 4);
+
+        let transform =
+            HelmertTransform::from_seven_parameters((12.0, -8.0, 3.0), 1.2, (0.2, -0.1, 0.3));
+
+        let mut shifted = reference.helmert_transform(&transform);
+
+        // introduce one outlier (1 m)
+        let g05 = SV::from_str("G05").unwrap();
+        let key = SP3Key { sv: g05, epoch: t0 };
+        shifted.data.get_mut(&key).unwrap().position_km.0 += 1.0E-3;
+
+        let estimate = reference
+            .helmert_estimate(&shifted, &HelmertEstimationOptions::default())
+            .unwrap();
+
+        assert_eq!(estimate.rejected, vec![(t0, g05)]);
+        assert_eq!(estimate.points, 24 * 4 - 1);
+
+        let tr = estimate.transform;
+        assert!((tr.translation_mm.0 - 12.0).abs() < 1.0E-3);
+        assert!((tr.translation_mm.1 + 8.0).abs() < 1.0E-3);
+        assert!((tr.translation_mm.2 - 3.0).abs() < 1.0E-3);
+        assert!((tr.scale_ppb - 1.2).abs() < 1.0E-4);
+        assert!((tr.rotation_mas.0 - 0.2).abs() < 1.0E-4);
+        assert!((tr.rotation_mas.1 + 0.1).abs() < 1.0E-4);
+        assert!((tr.rotation_mas.2 - 0.3).abs() < 1.0E-4);
+        assert!(estimate.sigma0_mm < 1.0E-3);
+
+        let per_epoch =
+            reference.helmert_estimates_per_epoch(&shifted, &HelmertEstimationOptions::default());
+
+        assert_eq!(per_epoch.len(), 4);
+
+        for (_, estimate) in per_epoch.iter() {
+            assert!((estimate.transform.scale_ppb - 1.2).abs() < 1.0E-4);
+        }
+
+        // alignment: null (missing) positions are preserved
+        let null = SP3Key {
+            sv: SV::from_str("G25").unwrap(),
+            epoch: t0,
+        };
+
+        let mut aligned = reference.clone();
+        aligned
+            .data
+            .insert(null.clone(), SP3Entry::from_position_km((0.0, 0.0, 0.0)));
+
+        aligned
+            .helmert_align_mut(&shifted, &HelmertEstimationOptions::default())
+            .unwrap();
+
+        let k = SP3Key {
+            sv: SV::from_str("G01").unwrap(),
+            epoch: t0,
+        };
+
+        let (a, b) = (
+            aligned.data.get(&k).unwrap().position_km,
+            shifted.data.get(&k).unwrap().position_km,
+        );
+
+        assert!((a.0 - b.0).abs() < 1.0E-8);
+        assert!((a.1 - b.1).abs() < 1.0E-8);
+        assert!((a.2 - b.2).abs() < 1.0E-8);
+
+        assert_eq!(
+            aligned.data.get(&null).unwrap().position_km,
+            (0.0, 0.0, 0.0)
+        );
+    }
+}
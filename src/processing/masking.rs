@@ -1,110 +1,262 @@
-use crate::prelude::{Constellation, Epoch, Header, SP3};
-use hifitime::Unit;
+use crate::prelude::{Constellation, Duration, Epoch, Header, SP3Entry, SP3, SV};
 use qc_traits::{FilterItem, MaskFilter, MaskOperand, Masking};
 
-impl Masking for Header {
-    fn mask_mut(&mut self, mask: &MaskFilter) {
-        match mask.operand {
-            MaskOperand::Equals => match &mask.item {
-                FilterItem::EpochItem(epoch) => {
-                    let mut mjd = Epoch::from_mjd_utc(self.mjd as f64);
-                    mjd += self.mjd_fraction * Unit::Day;
-
-                    if self.timescale.is_gnss() {
-                        mjd = Epoch::from_duration(
-                            mjd - self.timescale.reference_epoch(),
-                            self.timescale,
-                        );
-                    }
+use std::collections::BTreeSet;
+
+/// SP3 specific [SP3MaskItem]s, that describe entry flags and content.
+/// They are converted to [FilterItem::ComplexItem] to form a [MaskFilter].
+/// [MaskOperand::Equals] retains the matching data,
+/// [MaskOperand::NotEquals] drops the matching data, other operands do not apply.
+/// ```
+/// use sp3::prelude::*;
+/// use gnss_qc_traits::{MaskFilter, MaskOperand, Masking};
+///
+/// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+///     .unwrap();
+///
+/// // exclude maneuvering satellites
+/// let sp3 = sp3.mask(&MaskFilter {
+///     item: SP3MaskItem::Maneuver.into(),
+///     operand: MaskOperand::NotEquals,
+/// });
+///
+/// // retain entries that come with a clock state
+/// let sp3 = sp3.mask(&MaskFilter {
+///     item: SP3MaskItem::Clock.into(),
+///     operand: MaskOperand::Equals,
+/// });
+///
+/// assert!(sp3.data.values().all(|entry| entry.valid_clock_offset_us().is_some() && !entry.maneuver));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SP3MaskItem {
+    /// Fitted entries: neither orbit nor clock is predicted
+    Fitted,
+    /// Predicted entries: orbit or clock is predicted
+    Predicted,
+    /// Satellites being maneuvered at some point in the record
+    Maneuver,
+    /// Entries flagged with a clock event
+    ClockEvent,
+    /// Entries that come with a velocity vector
+    Velocity,
+    /// Entries that come with a clock offset (not the unknown clock sentinel)
+    Clock,
+}
+
+impl SP3MaskItem {
+    /// Keyword used in [FilterItem::ComplexItem]
+    fn keyword(&self) -> &'static str {
+        match self {
+            Self::Fitted => "fitted",
+            Self::Predicted => "predicted",
+            Self::Maneuver => "maneuver",
+            Self::ClockEvent => "clock_event",
+            Self::Velocity => "velocity",
+            Self::Clock => "clock",
+        }
+    }
+
+    /// Parses [SP3MaskItem] from this keyword
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        [
+            Self::Fitted,
+            Self::Predicted,
+            Self::Maneuver,
+            Self::ClockEvent,
+            Self::Velocity,
+            Self::Clock,
+        ]
+        .into_iter()
+        .find(|item| item.keyword().eq_ignore_ascii_case(keyword.trim()))
+    }
+
+    /// Returns true if this [SP3Entry] matches this [SP3MaskItem].
+    /// [SP3MaskItem::Maneuver] is applied per satellite, see [SP3::mask_mut].
+    fn matches(&self, entry: &SP3Entry) -> bool {
+        match self {
+            Self::Fitted => !entry.predicted_orbit && !entry.predicted_clock,
+            Self::Predicted => entry.predicted_orbit || entry.predicted_clock,
+            Self::Maneuver => entry.maneuver,
+            Self::ClockEvent => entry.clock_event,
+            Self::Velocity => entry.velocity_km_s.is_some(),
+            Self::Clock => entry.valid_clock_offset_us().is_some(),
+        }
+    }
+}
+
+impl std::fmt::Display for SP3MaskItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.keyword())
+    }
+}
+
+impl From<SP3MaskItem> for FilterItem {
+    fn from(item: SP3MaskItem) -> Self {
+        FilterItem::ComplexItem(vec![item.keyword().to_string()])
+    }
+}
+
+/// Returns true if this [SV] passes this [SV] mask.
+/// Ordering operands only apply to [SV]s of the same [Constellation]:
+/// other satellites are preserved.
+fn sv_mask(operand: MaskOperand, svs: &[SV], sv: SV) -> bool {
+    match operand {
+        MaskOperand::Equals => svs.contains(&sv),
+        MaskOperand::NotEquals => !svs.contains(&sv),
+        _ => svs
+            .iter()
+            .filter(|item| item.constellation == sv.constellation)
+            .all(|item| match operand {
+                MaskOperand::GreaterThan => sv.prn > item.prn,
+                MaskOperand::GreaterEquals => sv.prn >= item.prn,
+                MaskOperand::LowerThan => sv.prn < item.prn,
+                _ => sv.prn <= item.prn,
+            }),
+    }
+}
+
+/// Returns true if this [SV] passes this [Constellation] mask.
+/// [Constellation::SBAS] describes all SBAS systems.
+fn constellation_mask(operand: MaskOperand, constellations: &[Constellation], sv: SV) -> bool {
+    let broad_sbas_filter = constellations.contains(&Constellation::SBAS);
+
+    let matches = constellations.contains(&sv.constellation)
+        || (broad_sbas_filter && sv.constellation.is_sbas());
+
+    match operand {
+        MaskOperand::Equals => matches,
+        MaskOperand::NotEquals => !matches,
+        _ => true, // does not apply
+    }
+}
+
+/// Returns true if this interval passes this sampling mask
+fn interval_mask(operand: MaskOperand, interval: Duration, dt: Duration) -> bool {
+    match operand {
+        MaskOperand::Equals | MaskOperand::GreaterEquals => interval >= dt,
+        MaskOperand::GreaterThan => interval > dt,
+        MaskOperand::LowerThan => interval < dt,
+        MaskOperand::LowerEquals => interval <= dt,
+        MaskOperand::NotEquals => interval != dt,
+    }
+}
+
+impl Header {
+    /// Number of declared epochs prior to (and possibly including) this [Epoch]
+    fn epochs_prior(&self, t: Epoch, inclusive: bool) -> u64 {
+        let start = self.start_epoch();
+
+        if t < start || self.sampling_period <= Duration::ZERO {
+            return 0;
+        }
+
+        let periods = (t - start).to_seconds() / self.sampling_period.to_seconds();
 
-                    if *epoch < mjd {
-                        // TODO
+        let epochs = if inclusive {
+            periods.floor() as u64 + 1
+        } else {
+            periods.ceil() as u64
+        };
+
+        std::cmp::min(epochs, self.num_epochs)
+    }
+
+    /// Skips this number of epochs, at the beginning of the file
+    fn skip_epochs_mut(&mut self, epochs: u64) {
+        if epochs > 0 {
+            let start = self.start_epoch() + self.sampling_period * epochs as f64;
+            self.set_start_epoch_mut(start);
+            self.num_epochs -= epochs;
+        }
+    }
+
+    /// Applies this [Epoch] mask to the declared time frame
+    pub(crate) fn mask_epochs_mut(&mut self, operand: MaskOperand, t: Epoch) {
+        match operand {
+            MaskOperand::Equals => {
+                self.set_start_epoch_mut(t);
+                self.num_epochs = std::cmp::min(self.num_epochs, 1);
+            },
+            MaskOperand::NotEquals => {
+                let declared = self.epochs_prior(t, true) - self.epochs_prior(t, false) == 1;
+
+                if declared {
+                    if t == self.start_epoch() {
+                        self.skip_epochs_mut(1);
+                    } else {
+                        self.num_epochs -= 1;
                     }
-                },
-                FilterItem::SvItem(svs) => {
-                    self.satellites.retain(|sv| svs.contains(&sv));
-                },
-                FilterItem::ConstellationItem(constellations) => {
-                    self.satellites
-                        .retain(|sv| constellations.contains(&sv.constellation));
-                },
-                FilterItem::DurationItem(dt) => {
-                    self.sampling_period = std::cmp::max(self.sampling_period, *dt);
-                },
-                _ => {},
-            },
-            MaskOperand::GreaterEquals => match &mask.item {
-                FilterItem::SvItem(svs) => {
-                    self.satellites.retain(|sv| {
-                        let mut retained = true;
-                        for item in svs {
-                            if item.constellation == sv.constellation {
-                                retained &= sv.prn >= item.prn;
-                            }
-                        }
-                        retained
-                    });
-                },
-                _ => {},
-            },
-            MaskOperand::GreaterThan => match &mask.item {
-                FilterItem::SvItem(svs) => {
-                    self.satellites.retain(|sv| {
-                        let mut retained = true;
-                        for item in svs {
-                            if item.constellation == sv.constellation {
-                                retained &= sv.prn > item.prn;
-                            }
-                        }
-                        retained
-                    });
-                },
-                _ => {},
-            },
-            MaskOperand::LowerEquals => match &mask.item {
-                FilterItem::SvItem(svs) => {
-                    self.satellites.retain(|sv| {
-                        let mut retained = true;
-                        for item in svs {
-                            if item.constellation == sv.constellation {
-                                retained &= sv.prn <= item.prn;
-                            }
-                        }
-                        retained
-                    });
-                },
-                _ => {},
-            },
-            MaskOperand::LowerThan => match &mask.item {
-                FilterItem::SvItem(svs) => {
-                    self.satellites.retain(|sv| {
-                        let mut retained = true;
-                        for item in svs {
-                            if item.constellation == sv.constellation {
-                                retained &= sv.prn < item.prn;
-                            }
-                        }
-                        retained
-                    });
-                },
-                _ => {},
-            },
-            MaskOperand::NotEquals => match &mask.item {
-                FilterItem::SvItem(svs) => {
-                    self.satellites.retain(|sv| {
-                        let mut retained = true;
-                        for item in svs {
-                            if item.constellation == sv.constellation {
-                                retained &= sv.prn != item.prn;
-                            }
-                        }
-                        retained
-                    });
-                },
-                _ => {},
+                }
+            },
+            MaskOperand::GreaterThan => {
+                self.skip_epochs_mut(self.epochs_prior(t, true));
+            },
+            MaskOperand::GreaterEquals => {
+                self.skip_epochs_mut(self.epochs_prior(t, false));
+            },
+            MaskOperand::LowerThan => {
+                self.num_epochs = self.epochs_prior(t, false);
+            },
+            MaskOperand::LowerEquals => {
+                self.num_epochs = self.epochs_prior(t, true);
             },
         }
     }
+
+    /// Applies this sampling mask to the declared time frame, see [SP3::mask_mut].
+    fn mask_sampling_mut(&mut self, operand: MaskOperand, dt: Duration) {
+        let sampling_s = self.sampling_period.to_seconds();
+
+        if sampling_s <= 0.0 {
+            return;
+        }
+
+        match operand {
+            MaskOperand::Equals | MaskOperand::GreaterEquals | MaskOperand::GreaterThan => {
+                // smallest multiple of the sampling period that passes the mask
+                let ratio = dt.to_seconds() / sampling_s;
+
+                let modulo = if operand == MaskOperand::GreaterThan {
+                    ratio.floor() as u64 + 1
+                } else {
+                    ratio.ceil() as u64
+                };
+
+                if modulo > 1 {
+                    self.sampling_period = self.sampling_period * modulo as f64;
+                    self.num_epochs = self.num_epochs.div_ceil(modulo);
+                }
+            },
+            _ => {
+                // only the first epoch remains when the sampling does not pass
+                if !interval_mask(operand, self.sampling_period, dt) {
+                    self.num_epochs = std::cmp::min(self.num_epochs, 1);
+                }
+            },
+        }
+    }
+}
+
+impl Masking for Header {
+    fn mask_mut(&mut self, mask: &MaskFilter) {
+        match &mask.item {
+            FilterItem::EpochItem(epoch) => {
+                self.mask_epochs_mut(mask.operand, *epoch);
+            },
+            FilterItem::DurationItem(dt) => {
+                self.mask_sampling_mut(mask.operand, *dt);
+            },
+            FilterItem::SvItem(svs) => {
+                self.satellites.retain(|sv| sv_mask(mask.operand, svs, *sv));
+            },
+            FilterItem::ConstellationItem(constellations) => {
+                self.satellites
+                    .retain(|sv| constellation_mask(mask.operand, constellations, *sv));
+            },
+            _ => {}, // does not apply
+        }
+    }
     fn mask(&self, mask: &MaskFilter) -> Self {
         let mut s = self.clone();
         s.mask_mut(mask);
@@ -112,125 +264,262 @@ impl Masking for Header {
     }
 }
 
+impl SP3 {
+    /// Applies this sampling mask to the record, see [SP3::mask_mut].
+    fn mask_sampling_mut(&mut self, operand: MaskOperand, dt: Duration) {
+        let mut retained = BTreeSet::<Epoch>::new();
+
+        let mut previous = Option::<Epoch>::None;
+        let mut last_retained = Option::<Epoch>::None;
+
+        for epoch in self.epochs_iter() {
+            let reference = match operand {
+                MaskOperand::Equals | MaskOperand::GreaterEquals | MaskOperand::GreaterThan => {
+                    last_retained
+                },
+                _ => previous,
+            };
+
+            let retain = match reference {
+                Some(reference) => interval_mask(operand, epoch - reference, dt),
+                None => true,
+            };
+
+            if retain {
+                retained.insert(epoch);
+                last_retained = Some(epoch);
+            }
+
+            previous = Some(epoch);
+        }
+
+        self.data.retain(|k, _| retained.contains(&k.epoch));
+    }
+
+    /// Applies this [SP3MaskItem] to the record.
+    fn mask_flags_mut(&mut self, operand: MaskOperand, item: SP3MaskItem) {
+        let keep = match operand {
+            MaskOperand::Equals => true,
+            MaskOperand::NotEquals => false,
+            _ => return, // does not apply
+        };
+
+        if item == SP3MaskItem::Maneuver {
+            let maneuvering = self
+                .data
+                .iter()
+                .filter_map(|(k, v)| if v.maneuver { Some(k.sv) } else { None })
+                .collect::<BTreeSet<_>>();
+
+            self.data.retain(|k, _| maneuvering.contains(&k.sv) == keep);
+        } else {
+            self.data.retain(|_, v| item.matches(v) == keep);
+        }
+    }
+}
+
 impl Masking for SP3 {
     fn mask(&self, f: &MaskFilter) -> Self {
         let mut s = self.clone();
-        s.mask_mut(&f);
+        s.mask_mut(f);
         s
     }
+
+    /// Applies this [MaskFilter] to the record, then rebuilds the [Header].
+    /// - [FilterItem::EpochItem] applies to all operands.
+    /// - [FilterItem::SvItem]: ordering operands compare the PRN
+    ///   of satellites of the same [Constellation], other satellites are preserved.
+    /// - [FilterItem::ConstellationItem] applies to [MaskOperand::Equals]
+    ///   and [MaskOperand::NotEquals].
+    /// - [FilterItem::DurationItem] masks the sampling interval. [MaskOperand::Equals],
+    ///   [MaskOperand::GreaterEquals] and [MaskOperand::GreaterThan] retain epochs
+    ///   that are separated by (at least) this interval, like a decimation. Other operands
+    ///   compare the interval to the previous epoch of the record, for example
+    ///   [MaskOperand::LowerEquals] drops the epochs that follow a data gap.
+    ///   The first epoch is always retained.
+    /// - [FilterItem::ClockItem] is equivalent to [SP3MaskItem::Clock].
+    /// - [FilterItem::ComplexItem] describes one or more [SP3MaskItem]s.
     fn mask_mut(&mut self, f: &MaskFilter) {
-        match f.operand {
-            MaskOperand::Equals => match &f.item {
-                FilterItem::EpochItem(epoch) => {
-                    self.data.retain(|k, _| k.epoch == *epoch);
-                },
-                FilterItem::SvItem(svs) => {
-                    self.data.retain(|k, _| svs.contains(&k.sv));
-                },
-                FilterItem::ConstellationItem(constells) => {
-                    let mut broad_sbas_filter = false;
-                    for c in constells {
-                        broad_sbas_filter |= *c == Constellation::SBAS;
-                    }
-                    self.data.retain(|k, _| {
-                        if broad_sbas_filter {
-                            k.sv.constellation.is_sbas() || constells.contains(&k.sv.constellation)
-                        } else {
-                            constells.contains(&k.sv.constellation)
-                        }
-                    });
-                },
-                _ => {}, // does not apply
+        match &f.item {
+            FilterItem::EpochItem(epoch) => match f.operand {
+                MaskOperand::Equals => self.data.retain(|k, _| k.epoch == *epoch),
+                MaskOperand::NotEquals => self.data.retain(|k, _| k.epoch != *epoch),
+                MaskOperand::GreaterThan => self.data.retain(|k, _| k.epoch > *epoch),
+                MaskOperand::GreaterEquals => self.data.retain(|k, _| k.epoch >= *epoch),
+                MaskOperand::LowerThan => self.data.retain(|k, _| k.epoch < *epoch),
+                MaskOperand::LowerEquals => self.data.retain(|k, _| k.epoch <= *epoch),
             },
-            MaskOperand::NotEquals => match &f.item {
-                FilterItem::EpochItem(epoch) => {
-                    self.data.retain(|k, _| k.epoch != *epoch);
-                },
-                FilterItem::SvItem(svs) => {
-                    self.data.retain(|k, _| !svs.contains(&k.sv));
-                },
-                FilterItem::ConstellationItem(constells) => {
-                    self.data
-                        .retain(|k, _| !constells.contains(&k.sv.constellation));
-                },
-                _ => {}, // does not apply
+            FilterItem::DurationItem(dt) => {
+                self.mask_sampling_mut(f.operand, *dt);
             },
-            MaskOperand::GreaterThan => match &f.item {
-                FilterItem::EpochItem(epoch) => {
-                    self.data.retain(|k, _| k.epoch > *epoch);
-                },
-                FilterItem::SvItem(svs) => {
-                    self.data.retain(|k, _| {
-                        let mut retain = false;
-                        for sv in svs {
-                            if k.sv.constellation == sv.constellation {
-                                retain = k.sv.prn > sv.prn
-                            } else {
-                                retain = false
-                            }
-                        }
-                        retain
-                    });
-                },
-                _ => {}, // does not apply
+            FilterItem::SvItem(svs) => {
+                self.data.retain(|k, _| sv_mask(f.operand, svs, k.sv));
             },
-            MaskOperand::GreaterEquals => match &f.item {
-                FilterItem::EpochItem(epoch) => {
-                    self.data.retain(|k, _| k.epoch >= *epoch);
-                },
-                FilterItem::SvItem(svs) => {
-                    self.data.retain(|k, _| {
-                        let mut retain = false;
-                        for sv in svs {
-                            if k.sv.constellation == sv.constellation {
-                                retain = k.sv.prn >= sv.prn
-                            } else {
-                                retain = false
-                            }
-                        }
-                        retain
-                    });
-                },
-                _ => {}, // does not apply
+            FilterItem::ConstellationItem(constellations) => {
+                self.data
+                    .retain(|k, _| constellation_mask(f.operand, constellations, k.sv));
             },
-            MaskOperand::LowerThan => match &f.item {
-                FilterItem::EpochItem(epoch) => {
-                    self.data.retain(|k, _| k.epoch < *epoch);
-                },
-                FilterItem::SvItem(svs) => {
-                    self.data.retain(|k, _| {
-                        let mut retain = false;
-                        for sv in svs {
-                            if k.sv.constellation == sv.constellation {
-                                retain = k.sv.prn < sv.prn
-                            } else {
-                                retain = false
-                            }
-                        }
-                        retain
-                    });
-                },
-                _ => {}, // does not apply
+            FilterItem::ClockItem => {
+                self.mask_flags_mut(f.operand, SP3MaskItem::Clock);
             },
-            MaskOperand::LowerEquals => match &f.item {
-                FilterItem::EpochItem(epoch) => {
-                    self.data.retain(|k, _| k.epoch <= *epoch);
-                },
-                FilterItem::SvItem(svs) => {
-                    self.data.retain(|k, _| {
-                        let mut retain = false;
-                        for sv in svs {
-                            if k.sv.constellation == sv.constellation {
-                                retain = k.sv.prn <= sv.prn
-                            } else {
-                                retain = false
-                            }
-                        }
-                        retain
-                    });
-                },
-                _ => {}, // does not apply
+            FilterItem::ComplexItem(keywords) => {
+                for item in keywords
+                    .iter()
+                    .filter_map(|keyword| SP3MaskItem::from_keyword(keyword))
+                {
+                    self.mask_flags_mut(f.operand, item);
+                }
             },
+            _ => {}, // does not apply
         }
+
+        self.rebuild_header_mut();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SP3MaskItem;
+    use crate::prelude::{Constellation, Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use qc_traits::{FilterItem, MaskFilter, MaskOperand, Masking};
+    use std::str::FromStr;
+
+    #[test]
+    fn sp3_masking() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g10 = SV::from_str("G10").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..8 {
+            let t = t0 + dt * i as f64;
+
+            // G01: predicted orbits after 01:00, maneuver at 01:30
+            let mut entry = SP3Entry::from_position_km((1.0, 2.0, 3.0)).with_clock_offset_us(1.0);
+            entry.predicted_orbit = i >= 4;
+            entry.maneuver = i == 6;
+            sp3.data.insert(SP3Key { epoch: t, sv: g01 }, entry);
+
+            // G10: no clock at 00:30, unknown clock at 01:15, with velocities
+            let mut entry =
+                SP3Entry::from_position_velocity_km_km_s((1.0, 2.0, 3.0), (0.1, 0.2, 0.3));
+            if i == 5 {
+                entry = entry.with_clock_offset_us(999_999.999999);
+            } else if i != 2 {
+                entry = entry.with_clock_offset_us(2.0);
+            }
+            sp3.data.insert(SP3Key { epoch: t, sv: g10 }, entry);
+
+            // E01: clock event at 00:45, 30' gap at 01:00
+            if i != 4 && i != 5 {
+                let mut entry =
+                    SP3Entry::from_position_km((1.0, 2.0, 3.0)).with_clock_offset_us(3.0);
+                entry.clock_event = i == 3;
+                sp3.data.insert(SP3Key { epoch: t, sv: e01 }, entry);
+            }
+        }
+
+        sp3.rebuild_header_mut();
+
+        // grouped by constellation
+        assert_eq!(sp3.header.satellites, vec![g01, g10, e01]);
+
+        let mask = |item: FilterItem, operand: MaskOperand| MaskFilter { item, operand };
+
+        // satellites
+        let masked = sp3.mask(&mask(
+            FilterItem::SvItem(vec![g01]),
+            MaskOperand::GreaterThan,
+        ));
+        assert_eq!(masked.header.satellites, vec![g10, e01]);
+
+        let masked = sp3.mask(&mask(
+            FilterItem::SvItem(vec![g10]),
+            MaskOperand::LowerEquals,
+        ));
+        assert_eq!(masked.header.satellites, vec![g01, g10, e01]);
+
+        let masked = sp3.mask(&mask(
+            FilterItem::ConstellationItem(vec![Constellation::GPS]),
+            MaskOperand::NotEquals,
+        ));
+        assert_eq!(masked.header.satellites, vec![e01]);
+
+        let header = sp3.header.mask(&mask(
+            FilterItem::ConstellationItem(vec![Constellation::Galileo]),
+            MaskOperand::NotEquals,
+        ));
+        assert_eq!(header.satellites, vec![g01, g10]);
+
+        // epochs
+        let masked = sp3.mask(&mask(
+            FilterItem::EpochItem(t0 + dt),
+            MaskOperand::GreaterThan,
+        ));
+        assert_eq!(masked.first_epoch(), Some(t0 + dt * 2.0));
+        assert!(masked.validate().is_empty());
+
+        // sampling
+        let masked = sp3.mask(&mask(
+            FilterItem::DurationItem(Duration::from_seconds(1800.0)),
+            MaskOperand::GreaterEquals,
+        ));
+        assert_eq!(masked.total_epochs(), 4);
+        assert_eq!(
+            masked.header.sampling_period,
+            Duration::from_seconds(1800.0)
+        );
+
+        let header = sp3.header.mask(&mask(
+            FilterItem::DurationItem(Duration::from_seconds(900.0)),
+            MaskOperand::GreaterThan,
+        ));
+        assert_eq!(header.sampling_period, Duration::from_seconds(1800.0));
+        assert_eq!(header.num_epochs, 4);
+
+        let e01_only = sp3.mask(&mask(FilterItem::SvItem(vec![e01]), MaskOperand::Equals));
+
+        let masked = e01_only.mask(&mask(
+            FilterItem::DurationItem(dt),
+            MaskOperand::LowerEquals,
+        ));
+        assert_eq!(masked.total_epochs(), 5);
+        assert!(!masked.epochs_iter().any(|t| t == t0 + dt * 6.0));
+
+        // flags
+        let masked = sp3.mask(&mask(SP3MaskItem::Fitted.into(), MaskOperand::Equals));
+        assert_eq!(masked.data.len(), sp3.data.len() - 4);
+
+        let masked = sp3.mask(&mask(SP3MaskItem::Predicted.into(), MaskOperand::Equals));
+        assert_eq!(masked.header.satellites, vec![g01]);
+        assert_eq!(masked.first_epoch(), Some(t0 + dt * 4.0));
+
+        let masked = sp3.mask(&mask(SP3MaskItem::Maneuver.into(), MaskOperand::NotEquals));
+        assert_eq!(masked.header.satellites, vec![g10, e01]);
+
+        let masked = sp3.mask(&mask(
+            SP3MaskItem::ClockEvent.into(),
+            MaskOperand::NotEquals,
+        ));
+        assert_eq!(masked.data.len(), sp3.data.len() - 1);
+
+        let masked = sp3.mask(&mask(SP3MaskItem::Velocity.into(), MaskOperand::Equals));
+        assert_eq!(masked.header.satellites, vec![g10]);
+
+        let masked = sp3.mask(&mask(FilterItem::ClockItem, MaskOperand::Equals));
+        assert_eq!(masked.data.len(), sp3.data.len() - 2);
+        assert_eq!(
+            masked,
+            sp3.mask(&mask(SP3MaskItem::Clock.into(), MaskOperand::Equals))
+        );
+
+        // not applicable
+        let masked = sp3.mask(&mask(SP3MaskItem::Clock.into(), MaskOperand::GreaterThan));
+        assert_eq!(masked.data, sp3.data);
     }
 }
@@ -1,9 +1,29 @@
+mod combination;
+mod comparison;
 mod decimation;
+mod helmert;
 mod masking;
+mod repair;
+mod resampling;
 mod split;
+mod stability;
 mod substract;
 mod timeshift;
 
+pub use combination::{CombinationContribution, CombinationOptions, CombinationReport};
+pub use comparison::{
+    BoundaryDiscontinuity, BroadcastComparison, BroadcastResidual, BroadcastStatistics,
+    ClockComparison, ClockComparisonOptions, ClockDatum, OrbitComparison, RTNResidual,
+    RTNStatistics,
+};
+pub use helmert::{HelmertEstimate, HelmertEstimationOptions};
+pub use masking::SP3MaskItem;
+pub use repair::{GapFill, RepairOptions, RepairReport};
+pub use resampling::{ClockInterpolation, OrbitInterpolation, ResampledOrbit, ResamplingMethod};
+pub use stability::{
+    ClockStability, ClockStabilityOptions, ClockStabilityReport, Detrending, StabilityPoint,
+};
+
 use crate::prelude::SP3;
 use qc_traits::Preprocessing;
 
@@ -0,0 +1,438 @@
+use crate::{
+    availability::{data_gap, periods},
+    prelude::{
+        ClockInterpolation, DataGap, Duration, Epoch, OrbitInterpolation, SP3Entry, SP3Key, SP3, SV,
+    },
+};
+
+use super::resampling::{arcs, interpolate_clock, interpolate_orbit};
+
+use itertools::Itertools;
+
+/// [RepairOptions] used by [SP3::repair].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepairOptions {
+    /// Longest data gap to be filled
+    pub max_gap: Duration,
+
+    /// [OrbitInterpolation] used to fill positions (and velocities)
+    pub orbit: OrbitInterpolation,
+
+    /// [ClockInterpolation] used to fill clock offsets (and drifts).
+    /// Clocks are not filled when undefined.
+    pub clock: Option<ClockInterpolation>,
+}
+
+impl Default for RepairOptions {
+    /// Builds [RepairOptions] that fill gaps up to one hour,
+    /// with 9th order Lagrange interpolation and linear clock interpolation.
+    fn default() -> Self {
+        Self {
+            max_gap: Duration::from_hours(1.0),
+            orbit: OrbitInterpolation::default(),
+            clock: Some(ClockInterpolation::default()),
+        }
+    }
+}
+
+impl RepairOptions {
+    /// Copies and returns [RepairOptions] with desired longest gap to be filled.
+    pub fn with_max_gap(&self, max_gap: Duration) -> Self {
+        let mut s = *self;
+        s.max_gap = max_gap;
+        s
+    }
+
+    /// Copies and returns [RepairOptions] with desired [OrbitInterpolation].
+    pub fn with_orbit_interpolation(&self, orbit: OrbitInterpolation) -> Self {
+        let mut s = *self;
+        s.orbit = orbit;
+        s
+    }
+
+    /// Copies and returns [RepairOptions] with desired [ClockInterpolation].
+    pub fn with_clock_interpolation(&self, clock: ClockInterpolation) -> Self {
+        let mut s = *self;
+        s.clock = Some(clock);
+        s
+    }
+
+    /// Copies and returns [RepairOptions] that do not fill clocks.
+    pub fn without_clock_repair(&self) -> Self {
+        let mut s = *self;
+        s.clock = None;
+        s
+    }
+}
+
+/// [GapFill] describes one interpolated entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GapFill {
+    /// Filled [Epoch]
+    pub epoch: Epoch,
+
+    /// Filled [SV]
+    pub sv: SV,
+
+    /// True when the clock was filled as well
+    pub clock: bool,
+
+    /// True when the interpolation window could not be centered
+    pub off_centered: bool,
+}
+
+/// [RepairReport] obtained with [SP3::repair]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepairReport {
+    /// Sampling period that defined the missing [Epoch]s
+    pub sampling_period: Duration,
+
+    /// Every [GapFill] that was made, in chronological order
+    pub fills: Vec<GapFill>,
+
+    /// [DataGap]s that could not be filled, because they are too long,
+    /// because they contain a maneuver, or because there is not
+    /// enough data around them.
+    pub unrepaired: Vec<(SV, DataGap)>,
+}
+
+impl SP3 {
+    /// Fills the data gaps of each [SV], by interpolation. See [Self::repair].
+    pub fn repair_mut(&mut self, options: &RepairOptions) -> RepairReport {
+        let sampling_period = self.nominal_sampling_period();
+
+        let mut report = RepairReport {
+            sampling_period,
+            ..Default::default()
+        };
+
+        if sampling_period == Duration::ZERO {
+            return report;
+        }
+
+        // consecutive samples further apart cannot be used in the same window
+        let max_separation = options.max_gap + sampling_period;
+
+        for sv in self.data.keys().map(|k| k.sv).unique().sorted() {
+            let records = self
+                .data
+                .iter()
+                .filter_map(|(k, v)| {
+                    if k.sv == sv {
+                        Some((k.epoch, *v))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // null positions and unknown clocks are never interpolated
+            let samples = records
+                .iter()
+                .filter(|(_, entry)| entry.position_km != (0.0, 0.0, 0.0))
+                .copied()
+                .collect::<Vec<_>>();
+
+            let clock_samples = records
+                .iter()
+                .filter(|(_, entry)| entry.valid_clock_offset_us().is_some())
+                .copied()
+                .collect::<Vec<_>>();
+
+            let orbit_arcs = arcs(&samples, max_separation, |entry| entry.maneuver);
+            let clock_arcs = arcs(&clock_samples, max_separation, |entry| entry.clock_event);
+
+            for window in records.windows(2) {
+                let ((past, _), (now, now_entry)) = (window[0], window[1]);
+
+                if periods(past, now, sampling_period) < 2 {
+                    continue;
+                }
+
+                let gap = data_gap(
+                    past + sampling_period,
+                    now - sampling_period,
+                    sampling_period,
+                );
+
+                // continuous arc that surrounds the gap
+                let spans = |arc: &&Vec<(Epoch, SP3Entry)>| match (arc.first(), arc.last()) {
+                    (Some((start, _)), Some((end, _))) => *start < gap.start && *end > gap.end,
+                    _ => false,
+                };
+
+                let arc = orbit_arcs.iter().find(spans);
+                let clock_arc = clock_arcs.iter().find(spans);
+
+                let Some(arc) = arc else {
+                    report.unrepaired.push((sv, gap));
+                    continue;
+                };
+
+                if gap.duration > options.max_gap || now_entry.maneuver {
+                    report.unrepaired.push((sv, gap));
+                    continue;
+                }
+
+                let mut fills = Vec::with_capacity(gap.missing_epochs);
+
+                for i in 0..gap.missing_epochs {
+                    let epoch = gap.start + sampling_period * i as f64;
+
+                    let Some(orbit) = interpolate_orbit(arc, epoch, options.orbit) else {
+                        break;
+                    };
+
+                    let mut entry = if orbit.predicted {
+                        SP3Entry::from_predicted_position_km(orbit.position_km)
+                    } else {
+                        SP3Entry::from_position_km(orbit.position_km)
+                    };
+
+                    entry.velocity_km_s = orbit.velocity_km_s;
+                    entry.interpolated = true;
+
+                    let clock = match (options.clock, clock_arc) {
+                        (Some(method), Some(arc)) => interpolate_clock(arc, epoch, method),
+                        _ => None,
+                    };
+
+                    let mut fill = GapFill {
+                        epoch,
+                        sv,
+                        clock: false,
+                        off_centered: orbit.edge,
+                    };
+
+                    if let Some(clock) = clock {
+                        entry.clock_us = Some(clock.offset_us);
+                        entry.clock_drift_ns = clock.drift_ns;
+                        entry.predicted_clock = clock.predicted;
+
+                        fill.clock = true;
+                        fill.off_centered |= clock.edge;
+                    }
+
+                    fills.push((fill, entry));
+                }
+
+                // gaps are either entirely filled, or left untouched
+                if fills.len() < gap.missing_epochs {
+                    report.unrepaired.push((sv, gap));
+                    continue;
+                }
+
+                for (fill, entry) in fills {
+                    self.data.insert(
+                        SP3Key {
+                            epoch: fill.epoch,
+                            sv,
+                        },
+                        entry,
+                    );
+                    report.fills.push(fill);
+                }
+            }
+        }
+
+        report.fills.sort_by_key(|fill| (fill.epoch, fill.sv));
+
+        self.rebuild_header_mut();
+        report
+    }
+
+    /// Fills the data gaps of each [SV] that are shorter than [RepairOptions::max_gap],
+    /// by interpolating positions, and clocks when safe (no clock event across the gap).
+    /// This restores the centered interpolation windows that
+    /// [Self::satellite_position_interpolate] requires. Missing [Epoch]s are defined by the
+    /// sampling period (from the header, or from the record when not defined), as in
+    /// [Self::has_steady_sampling]. Gaps are never filled across maneuvers, and the edges of
+    /// the file are not extrapolated. Null positions and unknown clocks are not used as
+    /// interpolation samples, and are never overwritten.
+    /// Filled entries are marked as [SP3Entry::interpolated],
+    /// and each of them is listed in the returned [RepairReport].
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let options = RepairOptions::default()
+    ///     .with_max_gap(Duration::from_seconds(1800.0))
+    ///     .with_orbit_interpolation(OrbitInterpolation::Lagrange(11));
+    ///
+    /// let (repaired, report) = sp3.repair(&options);
+    ///
+    /// for fill in report.fills.iter() {
+    ///     println!("{} {} filled (clock: {})", fill.epoch, fill.sv, fill.clock);
+    /// }
+    ///
+    /// for (sv, gap) in report.unrepaired.iter() {
+    ///     println!("{}: {} gap could not be filled", sv, gap.duration);
+    /// }
+    ///
+    /// assert!(repaired.data.values().filter(|entry| entry.interpolated).count() >= report.fills.len());
+    /// ```
+    pub fn repair(&self, options: &RepairOptions) -> (Self, RepairReport) {
+        let mut s = self.clone();
+        let report = s.repair_mut(options);
+        (s, report)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RepairOptions;
+    use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn gap_repair() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let radius_km = 26_560.0;
+        let rate_rad_s = 2.0 * std::f64::consts::PI / 43_200.0;
+
+        let position_km = |t: Epoch| {
+            let (sin, cos) = (rate_rad_s * (t - t0).to_seconds()).sin_cos();
+            (radius_km * cos, radius_km * sin, 1.0)
+        };
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..96 {
+            let t = t0 + dt * i as f64;
+
+            // G01: 30' gap at 03:00, 2h gap at 10:00
+            // G02: 30' gap at 03:00, clock event at 03:30
+            let g01_missing = (12..14).contains(&i) || (40..48).contains(&i);
+            let g02_missing = (12..14).contains(&i);
+
+            if !g01_missing {
+                let entry = SP3Entry::from_position_km(position_km(t)).with_clock_offset_us(1.0);
+                sp3.data.insert(SP3Key { epoch: t, sv: g01 }, entry);
+            }
+
+            if !g02_missing {
+                let mut entry =
+                    SP3Entry::from_position_km(position_km(t)).with_clock_offset_us(2.0);
+                entry.clock_event = i == 14;
+                sp3.data.insert(SP3Key { epoch: t, sv: g02 }, entry);
+            }
+        }
+
+        let (repaired, report) = sp3.repair(&RepairOptions::default());
+
+        assert_eq!(report.sampling_period, dt);
+        assert_eq!(report.fills.len(), 4);
+        assert_eq!(report.unrepaired.len(), 1);
+
+        let (sv, gap) = report.unrepaired[0];
+        assert_eq!(sv, g01);
+        assert_eq!(gap.start, t0 + dt * 40.0);
+        assert_eq!(gap.missing_epochs, 8);
+
+        for fill in report.fills.iter() {
+            let entry = repaired
+                .data
+                .get(&SP3Key {
+                    epoch: fill.epoch,
+                    sv: fill.sv,
+                })
+                .unwrap();
+
+            assert!(entry.interpolated);
+            assert!(!fill.off_centered);
+
+            let expected_km = position_km(fill.epoch);
+            assert!((entry.position_km.0 - expected_km.0).abs() < 1.0E-3);
+            assert!((entry.position_km.1 - expected_km.1).abs() < 1.0E-3);
+
+            // no clock repair across clock events
+            if fill.sv == g01 {
+                assert!(fill.clock);
+                assert!((entry.clock_us.unwrap() - 1.0).abs() < 1.0E-9);
+            } else {
+                assert!(!fill.clock);
+                assert!(entry.clock_us.is_none());
+            }
+        }
+
+        assert_eq!(
+            repaired.data.values().filter(|e| !e.interpolated).count(),
+            sp3.data.len()
+        );
+    }
+
+    #[test]
+    fn gap_repair_invalid_borders() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+
+        let radius_km = 26_560.0;
+        let rate_rad_s = 2.0 * std::f64::consts::PI / 43_200.0;
+
+        let position_km = |t: Epoch| {
+            let (sin, cos) = (rate_rad_s * (t - t0).to_seconds()).sin_cos();
+            (radius_km * cos, radius_km * sin, 1.0)
+        };
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        // 30' gap at 03:00, bordered by a null position (with unknown clock) at 03:30
+        for i in 0..96 {
+            let t = t0 + dt * i as f64;
+
+            let entry = match i {
+                12 | 13 => continue,
+                14 => {
+                    SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_clock_offset_us(999_999.999999)
+                },
+                _ => SP3Entry::from_position_km(position_km(t)).with_clock_offset_us(1.0),
+            };
+
+            sp3.data.insert(SP3Key { epoch: t, sv: g01 }, entry);
+        }
+
+        let (repaired, report) = sp3.repair(&RepairOptions::default());
+
+        assert_eq!(report.fills.len(), 2);
+        assert!(report.unrepaired.is_empty());
+
+        for fill in report.fills.iter() {
+            let entry = repaired
+                .data
+                .get(&SP3Key {
+                    epoch: fill.epoch,
+                    sv: fill.sv,
+                })
+                .unwrap();
+
+            assert!(fill.clock);
+            assert!(!fill.off_centered);
+
+            let expected_km = position_km(fill.epoch);
+            assert!((entry.position_km.0 - expected_km.0).abs() < 1.0E-3);
+            assert!((entry.position_km.1 - expected_km.1).abs() < 1.0E-3);
+            assert!((entry.clock_us.unwrap() - 1.0).abs() < 1.0E-9);
+        }
+
+        // the null record is preserved
+        let null = repaired
+            .data
+            .get(&SP3Key {
+                epoch: t0 + dt * 14.0,
+                sv: g01,
+            })
+            .unwrap();
+
+        assert_eq!(null.position_km, (0.0, 0.0, 0.0));
+        assert!(!null.interpolated);
+    }
+}
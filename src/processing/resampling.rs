@@ -0,0 +1,624 @@
+use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+
+use itertools::Itertools;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Two consecutive samples further apart than this many sampling periods
+/// are considered as a data gap, which we do not interpolate across.
+const GAP_TOLERANCE: f64 = 1.5;
+
+/// [OrbitInterpolation] method used when resampling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrbitInterpolation {
+    /// Lagrange interpolation of this (odd) order, involving order +1 positions.
+    Lagrange(usize),
+
+    /// Hermite interpolation of this (odd) order, involving (order +1)/2
+    /// positions and velocities. When velocities are not available,
+    /// Lagrange interpolation of the same order is used instead.
+    Hermite(usize),
+}
+
+impl Default for OrbitInterpolation {
+    /// Lagrange 9th order, compatible with high precision geodesy.
+    fn default() -> Self {
+        Self::Lagrange(9)
+    }
+}
+
+/// [ClockInterpolation] method used when resampling.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ClockInterpolation {
+    /// Linear interpolation between two clock offsets
+    #[default]
+    Linear,
+
+    /// Lagrange interpolation of this (odd) order, involving order +1 clock offsets.
+    Lagrange(usize),
+}
+
+/// [ResamplingMethod] used by [SP3::resample].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResamplingMethod {
+    /// [OrbitInterpolation] for positions (and velocities)
+    pub orbit: OrbitInterpolation,
+
+    /// [ClockInterpolation] for clock offsets (and drifts)
+    pub clock: ClockInterpolation,
+}
+
+impl ResamplingMethod {
+    /// Copies and returns [ResamplingMethod] with desired [OrbitInterpolation].
+    pub fn with_orbit_interpolation(&self, orbit: OrbitInterpolation) -> Self {
+        let mut s = *self;
+        s.orbit = orbit;
+        s
+    }
+
+    /// Copies and returns [ResamplingMethod] with desired [ClockInterpolation].
+    pub fn with_clock_interpolation(&self, clock: ClockInterpolation) -> Self {
+        let mut s = *self;
+        s.clock = clock;
+        s
+    }
+}
+
+/// [ResampledOrbit] obtained with [SP3::resample].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResampledOrbit {
+    /// Resampled [SP3]
+    pub sp3: SP3,
+
+    /// Entries that were interpolated with an off-centered window,
+    /// because they are too close to the edge of a data arc.
+    /// Their interpolation error is larger.
+    pub edges: BTreeSet<SP3Key>,
+}
+
+impl ResampledOrbit {
+    /// Returns true if this [SV] was interpolated with an off-centered window at this [Epoch].
+    pub fn is_edge(&self, epoch: Epoch, sv: SV) -> bool {
+        self.edges.contains(&SP3Key { epoch, sv })
+    }
+}
+
+/// Lagrange interpolation at t = 0, from (t_i, y_i) samples.
+fn lagrange(points: &[(f64, f64)]) -> f64 {
+    let mut value = 0.0;
+
+    for (i, (t_i, y_i)) in points.iter().enumerate() {
+        let mut l_i = 1.0;
+
+        for (j, (t_j, _)) in points.iter().enumerate() {
+            if j != i {
+                l_i *= -t_j / (t_i - t_j);
+            }
+        }
+
+        value += y_i * l_i;
+    }
+
+    value
+}
+
+/// Hermite interpolation at t = 0, from (t_i, y_i, dy_i) samples,
+/// using Newton divided differences on doubled nodes.
+fn hermite(points: &[(f64, f64, f64)]) -> f64 {
+    let m = 2 * points.len();
+
+    let nodes = (0..m).map(|i| points[i / 2].0).collect::<Vec<_>>();
+    let mut coefficients = (0..m).map(|i| points[i / 2].1).collect::<Vec<_>>();
+
+    for k in 1..m {
+        for i in (k..m).rev() {
+            coefficients[i] = if k == 1 && i % 2 == 1 {
+                points[i / 2].2
+            } else {
+                (coefficients[i] - coefficients[i - 1]) / (nodes[i] - nodes[i - k])
+            };
+        }
+    }
+
+    let mut value = coefficients[m - 1];
+
+    for i in (0..m - 1).rev() {
+        value = value * -nodes[i] + coefficients[i];
+    }
+
+    value
+}
+
+/// Splits these samples into continuous arcs: a new arc starts on data gaps
+/// and on each sample for which `breaks` is true.
+pub(super) fn arcs<T: Copy>(
+    samples: &[(Epoch, T)],
+    max_gap: Duration,
+    breaks: impl Fn(&T) -> bool,
+) -> Vec<Vec<(Epoch, T)>> {
+    let mut arcs = Vec::<Vec<(Epoch, T)>>::new();
+
+    for (i, (t, value)) in samples.iter().enumerate() {
+        let new_arc = i == 0 || breaks(value) || *t - samples[i - 1].0 > max_gap;
+
+        if new_arc {
+            arcs.push(Vec::new());
+        }
+
+        if let Some(arc) = arcs.last_mut() {
+            arc.push((*t, *value));
+        }
+    }
+
+    arcs
+}
+
+/// Selects an interpolation window of `size` samples, centered on `t` when possible.
+/// Returns the window and true when it had to be off-centered.
+fn window<T>(arc: &[(Epoch, T)], t: Epoch, size: usize) -> Option<(&[(Epoch, T)], bool)> {
+    if size == 0 || arc.len() < size {
+        return None;
+    }
+
+    let after = arc.partition_point(|(t_i, _)| *t_i < t);
+    let centered = after as isize - (size / 2) as isize;
+    let start = centered.clamp(0, (arc.len() - size) as isize);
+
+    Some((
+        &arc[start as usize..start as usize + size],
+        start != centered,
+    ))
+}
+
+/// Resampled orbital state
+pub(super) struct OrbitState {
+    pub(super) position_km: (f64, f64, f64),
+    pub(super) velocity_km_s: Option<(f64, f64, f64)>,
+    pub(super) predicted: bool,
+    pub(super) edge: bool,
+}
+
+/// Resampled clock state
+pub(super) struct ClockState {
+    pub(super) offset_us: f64,
+    pub(super) drift_ns: Option<f64>,
+    pub(super) predicted: bool,
+    pub(super) edge: bool,
+}
+
+/// Interpolates the orbit at this [Epoch], from this continuous arc
+pub(super) fn interpolate_orbit(
+    arc: &[(Epoch, SP3Entry)],
+    t: Epoch,
+    method: OrbitInterpolation,
+) -> Option<OrbitState> {
+    if let Some((_, entry)) = arc.iter().find(|(t_i, _)| *t_i == t) {
+        return Some(OrbitState {
+            position_km: entry.position_km,
+            velocity_km_s: entry.velocity_km_s,
+            predicted: entry.predicted_orbit,
+            edge: false,
+        });
+    }
+
+    let has_velocity = arc.iter().all(|(_, entry)| entry.velocity_km_s.is_some());
+
+    let (size, hermite_interp) = match method {
+        OrbitInterpolation::Hermite(order) if has_velocity => (order.div_ceil(2), true),
+        OrbitInterpolation::Hermite(order) | OrbitInterpolation::Lagrange(order) => {
+            (order + 1, false)
+        },
+    };
+
+    let (window, edge) = window(arc, t, size)?;
+
+    let dt = |t_i: Epoch| (t_i - t).to_seconds();
+
+    let component = |position: fn(&SP3Entry) -> f64, velocity: fn(&SP3Entry) -> f64| {
+        if hermite_interp {
+            let points = window
+                .iter()
+                .map(|(t_i, entry)| (dt(*t_i), position(entry), velocity(entry)))
+                .collect::<Vec<_>>();
+            hermite(&points)
+        } else {
+            let points = window
+                .iter()
+                .map(|(t_i, entry)| (dt(*t_i), position(entry)))
+                .collect::<Vec<_>>();
+            lagrange(&points)
+        }
+    };
+
+    let velocity = |velocity: fn(&SP3Entry) -> f64| {
+        let points = window
+            .iter()
+            .map(|(t_i, entry)| (dt(*t_i), velocity(entry)))
+            .collect::<Vec<_>>();
+        lagrange(&points)
+    };
+
+    let velocity_km_s = if has_velocity {
+        Some((
+            velocity(|e| e.velocity_km_s.unwrap_or_default().0),
+            velocity(|e| e.velocity_km_s.unwrap_or_default().1),
+            velocity(|e| e.velocity_km_s.unwrap_or_default().2),
+        ))
+    } else {
+        None
+    };
+
+    Some(OrbitState {
+        position_km: (
+            component(
+                |e| e.position_km.0,
+                |e| e.velocity_km_s.unwrap_or_default().0,
+            ),
+            component(
+                |e| e.position_km.1,
+                |e| e.velocity_km_s.unwrap_or_default().1,
+            ),
+            component(
+                |e| e.position_km.2,
+                |e| e.velocity_km_s.unwrap_or_default().2,
+            ),
+        ),
+        velocity_km_s,
+        predicted: window.iter().any(|(_, entry)| entry.predicted_orbit),
+        edge,
+    })
+}
+
+/// Interpolates the clock at this [Epoch], from this continuous arc
+pub(super) fn interpolate_clock(
+    arc: &[(Epoch, SP3Entry)],
+    t: Epoch,
+    method: ClockInterpolation,
+) -> Option<ClockState> {
+    if let Some((_, entry)) = arc.iter().find(|(t_i, _)| *t_i == t) {
+        return Some(ClockState {
+            offset_us: entry.clock_us?,
+            drift_ns: entry.clock_drift_ns,
+            predicted: entry.predicted_clock,
+            edge: false,
+        });
+    }
+
+    let size = match method {
+        ClockInterpolation::Linear => 2,
+        ClockInterpolation::Lagrange(order) => order + 1,
+    };
+
+    let (window, edge) = window(arc, t, size)?;
+
+    let points = window
+        .iter()
+        .map(|(t_i, entry)| ((*t_i - t).to_seconds(), entry.clock_us.unwrap_or_default()))
+        .collect::<Vec<_>>();
+
+    let drift_ns = if window.iter().all(|(_, e)| e.clock_drift_ns.is_some()) {
+        let points = window
+            .iter()
+            .map(|(t_i, entry)| {
+                (
+                    (*t_i - t).to_seconds(),
+                    entry.clock_drift_ns.unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        Some(lagrange(&points))
+    } else {
+        None
+    };
+
+    Some(ClockState {
+        offset_us: lagrange(&points),
+        drift_ns,
+        predicted: window.iter().any(|(_, entry)| entry.predicted_clock),
+        edge,
+    })
+}
+
+impl SP3 {
+    /// Resamples this [SP3] to a new sampling period, by interpolation.
+    /// This is typically used to up-sample a 15' product to 30s, for high rate processing.
+    /// The new grid starts on the first [Epoch] of this [SP3], and each [SV] is
+    /// resampled over its own data arcs: interpolation never takes place across
+    /// data gaps, maneuvers (for the orbit) or clock events (for the clock).
+    /// [Epoch]s too close to the edge of an arc are interpolated with an off-centered
+    /// window, and reported in [ResampledOrbit::edges]. [Epoch]s that belong
+    /// to arcs too short for the selected interpolation method are not generated.
+    /// [SP3Entry] is only generated when the orbit could be resampled.
+    /// The [Header] is updated accordingly.
+    /// Null positions and unknown clocks are not used as interpolation samples.
+    /// Null or negative sampling periods return an empty [ResampledOrbit].
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let method = ResamplingMethod::default()
+    ///     .with_orbit_interpolation(OrbitInterpolation::Lagrange(11))
+    ///     .with_clock_interpolation(ClockInterpolation::Linear);
+    ///
+    /// let resampled = sp3.resample(Duration::from_seconds(30.0), method);
+    ///
+    /// assert_eq!(resampled.sp3.header.sampling_period, Duration::from_seconds(30.0));
+    ///
+    /// for key in resampled.edges.iter() {
+    ///     println!("{} {} off-centered interpolation", key.epoch, key.sv);
+    /// }
+    /// ```
+    pub fn resample(&self, sampling_period: Duration, method: ResamplingMethod) -> ResampledOrbit {
+        let mut data = BTreeMap::<SP3Key, SP3Entry>::new();
+        let mut edges = BTreeSet::<SP3Key>::new();
+
+        if sampling_period <= Duration::ZERO {
+            let mut sp3 = SP3 {
+                header: self.header.clone(),
+                comments: self.comments.clone(),
+                prod_attributes: self.prod_attributes.clone(),
+                data,
+            };
+
+            sp3.rebuild_header_mut();
+            return ResampledOrbit { sp3, edges };
+        }
+
+        let original_period = self
+            .record_sampling_period()
+            .unwrap_or(self.header.sampling_period);
+
+        let max_gap = original_period * GAP_TOLERANCE;
+
+        let first_epoch = self.first_epoch();
+
+        for sv in self.data.keys().map(|k| k.sv).unique().sorted() {
+            let Some(first_epoch) = first_epoch else {
+                break;
+            };
+
+            let samples = self
+                .data
+                .iter()
+                .filter_map(|(k, v)| {
+                    if k.sv == sv && v.position_km != (0.0, 0.0, 0.0) {
+                        Some((k.epoch, *v))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let clock_samples = samples
+                .iter()
+                .filter(|(_, entry)| entry.valid_clock_offset_us().is_some())
+                .copied()
+                .collect::<Vec<_>>();
+
+            let orbit_arcs = arcs(&samples, max_gap, |entry| entry.maneuver);
+            let clock_arcs = arcs(&clock_samples, max_gap, |entry| entry.clock_event);
+
+            for arc in orbit_arcs.iter() {
+                let (Some((start, _)), Some((end, _))) = (arc.first(), arc.last()) else {
+                    continue;
+                };
+
+                // first grid epoch of this arc
+                let periods =
+                    ((*start - first_epoch).to_seconds() / sampling_period.to_seconds()).ceil();
+
+                let mut t = first_epoch + sampling_period * periods;
+
+                while t <= *end {
+                    let Some(orbit) = interpolate_orbit(arc, t, method.orbit) else {
+                        t += sampling_period;
+                        continue;
+                    };
+
+                    let key = SP3Key { epoch: t, sv };
+
+                    let mut entry = if orbit.predicted {
+                        SP3Entry::from_predicted_position_km(orbit.position_km)
+                    } else {
+                        SP3Entry::from_position_km(orbit.position_km)
+                    };
+
+                    entry.velocity_km_s = orbit.velocity_km_s;
+
+                    let clock = clock_arcs
+                        .iter()
+                        .find(|arc| match (arc.first(), arc.last()) {
+                            (Some((start, _)), Some((end, _))) => *start <= t && t <= *end,
+                            _ => false,
+                        })
+                        .and_then(|arc| interpolate_clock(arc, t, method.clock));
+
+                    let mut edge = orbit.edge;
+
+                    if let Some(clock) = clock {
+                        entry.clock_us = Some(clock.offset_us);
+                        entry.clock_drift_ns = clock.drift_ns;
+                        entry.predicted_clock = clock.predicted;
+                        edge |= clock.edge;
+                    }
+
+                    // events are only preserved on original epochs
+                    if let Some(original) = self.data.get(&key) {
+                        entry.maneuver = original.maneuver;
+                        entry.clock_event = original.clock_event;
+                        entry.interpolated = original.interpolated;
+                    } else {
+                        entry.interpolated = true;
+                    }
+
+                    if edge {
+                        edges.insert(key.clone());
+                    }
+
+                    data.insert(key, entry);
+                    t += sampling_period;
+                }
+            }
+        }
+
+        let mut sp3 = SP3 {
+            header: self.header.clone(),
+            comments: self.comments.clone(),
+            prod_attributes: self.prod_attributes.clone(),
+            data,
+        };
+
+        sp3.rebuild_header_mut();
+        sp3.header.sampling_period = sampling_period;
+
+        if let Some(attributes) = &mut sp3.prod_attributes {
+            attributes.sampling_period = sampling_period;
+        }
+
+        ResampledOrbit { sp3, edges }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClockInterpolation, OrbitInterpolation, ResamplingMethod};
+    use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn orbit_resampling() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+
+        // circular orbit, 12h period, linear clock
+        let radius_km = 26_560.0;
+        let rate_rad_s = 2.0 * std::f64::consts::PI / 43_200.0;
+
+        let truth = |t: Epoch| {
+            let seconds = (t - t0).to_seconds();
+            let (sin, cos) = (rate_rad_s * seconds).sin_cos();
+            (
+                (radius_km * cos, radius_km * sin, 1.0),
+                (
+                    -radius_km * rate_rad_s * sin,
+                    radius_km * rate_rad_s * cos,
+                    0.0,
+                ),
+                10.0 + 1.0E-3 * seconds,
+            )
+        };
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..96 {
+            let t = t0 + dt * i as f64;
+            let (position_km, velocity_km_s, clock_us) = truth(t);
+
+            let mut entry = SP3Entry::from_position_km(position_km)
+                .with_velocity_km_s(velocity_km_s)
+                .with_clock_offset_us(clock_us);
+
+            // maneuver at 12:00
+            entry.maneuver = i == 48;
+
+            sp3.data.insert(SP3Key { epoch: t, sv: g01 }, entry);
+        }
+
+        let period = Duration::from_seconds(300.0);
+
+        for orbit in [
+            OrbitInterpolation::Lagrange(9),
+            OrbitInterpolation::Hermite(9),
+        ] {
+            let method = ResamplingMethod::default()
+                .with_orbit_interpolation(orbit)
+                .with_clock_interpolation(ClockInterpolation::Linear);
+
+            let resampled = sp3.resample(period, method);
+
+            assert_eq!(resampled.sp3.header.sampling_period, period);
+            assert_eq!(resampled.sp3.first_epoch(), Some(t0));
+
+            // 11:45 -> 12:00 is not interpolated (maneuver)
+            assert_eq!(resampled.sp3.header.num_epochs, 2 * (47 * 3 + 1));
+
+            for (k, v) in resampled.sp3.data.iter() {
+                let (position_km, _, clock_us) = truth(k.epoch);
+
+                let err_km = (v.position_km.0 - position_km.0)
+                    .abs()
+                    .max((v.position_km.1 - position_km.1).abs());
+
+                assert!(err_km < 1.0E-4, "{:?} error: {} km", orbit, err_km);
+                assert!((v.clock_us.unwrap() - clock_us).abs() < 1.0E-9);
+                assert!(v.velocity_km_s.is_some());
+
+                let gap = k.epoch > t0 + dt * 47.0 && k.epoch < t0 + Duration::from_hours(12.0);
+                assert!(!gap, "interpolated across maneuver");
+            }
+
+            // off-centered interpolation at the edges of both arcs
+            assert!(resampled.is_edge(t0 + period, g01));
+            assert!(!resampled.is_edge(t0 + Duration::from_hours(6.0) + period, g01));
+            assert!(resampled.is_edge(t0 + Duration::from_hours(12.0) + period, g01));
+        }
+    }
+
+    #[test]
+    fn resampling_invalid_samples() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..16 {
+            let t = t0 + dt * i as f64;
+            let seconds = (t - t0).to_seconds();
+
+            let entry = match i {
+                // missing position
+                4 => SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_clock_offset_us(10.0),
+                // unknown clock
+                8 => SP3Entry::from_position_km((10_000.0 + seconds, 1.0, 1.0))
+                    .with_clock_offset_us(999_999.999999),
+                _ => SP3Entry::from_position_km((10_000.0 + seconds, 1.0, 1.0))
+                    .with_clock_offset_us(10.0 + 1.0E-3 * seconds),
+            };
+
+            sp3.data.insert(SP3Key { epoch: t, sv: g01 }, entry);
+        }
+
+        let method = ResamplingMethod::default()
+            .with_orbit_interpolation(OrbitInterpolation::Lagrange(1))
+            .with_clock_interpolation(ClockInterpolation::Linear);
+
+        let period = Duration::from_seconds(300.0);
+        let resampled = sp3.resample(period, method);
+
+        // the null position opens a gap: 03:00 -> 04:00 is not interpolated
+        for (k, v) in resampled.sp3.data.iter() {
+            let seconds = (k.epoch - t0).to_seconds();
+
+            assert!(k.epoch <= t0 + dt * 3.0 || k.epoch >= t0 + dt * 5.0);
+            assert!((v.position_km.0 - 10_000.0 - seconds).abs() < 1.0E-6);
+
+            // the unknown clock never leaks into the interpolated clocks
+            if k.epoch <= t0 + dt * 7.0 || k.epoch >= t0 + dt * 9.0 {
+                assert!((v.clock_us.unwrap() - 10.0 - 1.0E-3 * seconds).abs() < 1.0E-9);
+            } else {
+                assert!(v.clock_us.is_none());
+            }
+        }
+
+        // null sampling period
+        let resampled = sp3.resample(Duration::ZERO, method);
+        assert!(resampled.sp3.data.is_empty());
+        assert!(resampled.edges.is_empty());
+    }
+}
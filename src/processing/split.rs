@@ -1,5 +1,5 @@
-use crate::prelude::{Duration, Epoch, Header, SP3};
-use qc_traits::Split;
+use crate::prelude::{Duration, Epoch, Header, ReleaseDate, ReleasePeriod, SP3};
+use qc_traits::{MaskOperand, Split};
 
 impl Split for Header {
     fn split(&self, epoch: Epoch) -> (Self, Self)
@@ -11,15 +11,75 @@ impl Split for Header {
         (lhs, rhs)
     }
 
-    fn split_mut(&mut self, _: Epoch) -> Self {
-        self.clone()
+    /// Splits the declared time frame: [Self] retains the epochs prior (and including)
+    /// this [Epoch], the returned [Header] describes the following epochs.
+    fn split_mut(&mut self, epoch: Epoch) -> Self {
+        let mut rhs = self.clone();
+        rhs.mask_epochs_mut(MaskOperand::GreaterThan, epoch);
+        self.mask_epochs_mut(MaskOperand::LowerEquals, epoch);
+        rhs
     }
 
-    fn split_even_dt(&self, _: Duration) -> Vec<Self>
+    /// Splits the declared time frame into chunks of `dt` duration,
+    /// aligned to `dt` (for example: midnight for daily chunks).
+    fn split_even_dt(&self, dt: Duration) -> Vec<Self>
     where
         Self: Sized,
     {
-        Default::default()
+        let mut chunks = Vec::new();
+
+        if dt <= Duration::ZERO || self.num_epochs == 0 {
+            return chunks;
+        }
+
+        let start = self.start_epoch();
+        let end = start + self.sampling_period * (self.num_epochs - 1) as f64;
+
+        let mut boundary = start.floor(dt);
+
+        while boundary <= end {
+            let mut chunk = self.clone();
+            chunk.mask_epochs_mut(MaskOperand::GreaterEquals, boundary);
+            chunk.mask_epochs_mut(MaskOperand::LowerThan, boundary + dt);
+
+            if chunk.num_epochs > 0 {
+                chunks.push(chunk);
+            }
+
+            boundary += dt;
+        }
+
+        chunks
+    }
+}
+
+impl SP3 {
+    /// Updates the [crate::prelude::ProductionAttributes] to describe
+    /// this chunk, that starts at this [Epoch] and spans this period.
+    fn set_chunk_attributes_mut(&mut self, start: Epoch, period: Duration) {
+        let mut attributes = self
+            .prod_attributes
+            .clone()
+            .unwrap_or_else(|| self.default_production_attributes());
+
+        attributes.release_date = ReleaseDate::from(start);
+        attributes.release_period = ReleasePeriod::from_duration(period);
+        attributes.sampling_period = self.header.sampling_period;
+
+        // legacy short names only describe daily products
+        if attributes.release_period != ReleasePeriod::Daily {
+            attributes.short_name = false;
+        }
+
+        self.prod_attributes = Some(attributes);
+    }
+
+    /// Updates the [crate::prelude::ProductionAttributes] to describe the record span
+    fn set_span_attributes_mut(&mut self) {
+        if let (Some(first), Some(last)) = (self.first_epoch(), self.last_epoch()) {
+            let span = last - first + self.header.sampling_period;
+            self.set_chunk_attributes_mut(first, span);
+        }
     }
 }
 
@@ -42,13 +102,167 @@ impl Split for SP3 {
 
         self.data.retain(|k, _| k.epoch <= epoch);
 
+        self.rebuild_header_mut();
+        rhs.rebuild_header_mut();
+
+        if self.prod_attributes.is_some() {
+            self.set_span_attributes_mut();
+            rhs.set_span_attributes_mut();
+        }
+
         rhs
     }
 
-    fn split_even_dt(&self, _dt: Duration) -> Vec<Self>
+    /// Splits this [SP3] into chunks of `dt` duration, aligned to `dt`
+    /// (for example: midnight for daily chunks, in the [crate::prelude::TimeScale]
+    /// of this file). Empty chunks are not generated. Each chunk comes with
+    /// its own [Header] and [crate::prelude::ProductionAttributes], so it may be
+    /// written using [SP3::standardized_filename].
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// // hourly chunks
+    /// let chunks = sp3.split_even_dt(Duration::from_hours(1.0));
+    /// assert_eq!(chunks.len(), 24);
+    ///
+    /// for chunk in chunks.iter() {
+    ///     assert_eq!(chunk.header.num_epochs, 4);
+    ///     let filename = chunk.standardized_filename();
+    ///     assert!(filename.contains("_01H_15M_ORB"));
+    /// }
+    /// ```
+    fn split_even_dt(&self, dt: Duration) -> Vec<Self>
     where
         Self: Sized,
     {
-        Default::default()
+        let mut chunks = Vec::new();
+
+        let (Some(first), Some(last)) = (self.first_epoch(), self.last_epoch()) else {
+            return chunks;
+        };
+
+        if dt <= Duration::ZERO {
+            return chunks;
+        }
+
+        let mut boundary = first.floor(dt);
+
+        while boundary <= last {
+            let mut chunk = Self {
+                header: self.header.clone(),
+                comments: self.comments.clone(),
+                prod_attributes: self.prod_attributes.clone(),
+                data: self
+                    .data
+                    .iter()
+                    .filter_map(|(k, v)| {
+                        if k.epoch >= boundary && k.epoch < boundary + dt {
+                            Some((k.clone(), *v))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect(),
+            };
+
+            if !chunk.data.is_empty() {
+                chunk.rebuild_header_mut();
+                chunk.set_chunk_attributes_mut(boundary, dt);
+                chunks.push(chunk);
+            }
+
+            boundary += dt;
+        }
+
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{
+        Availability, Campaign, Duration, Epoch, ReleasePeriod, SP3Builder, SP3Entry, Split, SV,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn sp3_split_even_dt() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+        let g01 = SV::from_str("G01").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+
+        // two days, E01 only on the second day
+        let mut builder = SP3Builder::default()
+            .with_agency("GRG")
+            .with_production_attributes(Campaign::MGX, Availability::Final);
+
+        for i in 0..192 {
+            let entry = SP3Entry::from_position_km((1.0, 2.0, 3.0)).with_clock_offset_us(1.0);
+            builder.push(t0 + dt * i as f64, g01, entry);
+
+            if i >= 96 {
+                builder.push(t0 + dt * i as f64, e01, entry);
+            }
+        }
+
+        let sp3 = builder.build().unwrap();
+
+        let days = sp3.split_even_dt(Duration::from_days(1.0));
+        assert_eq!(days.len(), 2);
+
+        assert_eq!(days[0].header.satellites, vec![g01]);
+        assert_eq!(days[1].header.satellites, vec![g01, e01]);
+
+        assert_eq!(
+            days[0].standardized_filename(),
+            "GRG0MGXFIN_20201770000_01D_15M_ORB.SP3"
+        );
+        assert_eq!(
+            days[1].standardized_filename(),
+            "GRG0MGXFIN_20201780000_01D_15M_ORB.SP3"
+        );
+
+        for (day, chunk) in days.iter().enumerate() {
+            assert!(chunk.validate().is_empty());
+            assert_eq!(chunk.header.num_epochs, 96);
+            assert_eq!(
+                chunk.header.start_epoch(),
+                t0 + Duration::from_days(day as f64)
+            );
+        }
+
+        let hours = sp3.split_even_dt(Duration::from_hours(1.0));
+        assert_eq!(hours.len(), 48);
+
+        for (hour, chunk) in hours.iter().enumerate() {
+            assert_eq!(chunk.header.num_epochs, 4);
+            assert_eq!(chunk.first_epoch(), Some(t0 + dt * (4 * hour) as f64));
+
+            let attributes = chunk.prod_attributes.as_ref().unwrap();
+            assert_eq!(attributes.release_period, ReleasePeriod::Hourly);
+            assert_eq!(attributes.release_date.hour as usize, hour % 24);
+        }
+
+        // header only
+        let headers = sp3.header.split_even_dt(Duration::from_hours(12.0));
+        assert_eq!(headers.len(), 4);
+        assert_eq!(headers[3].num_epochs, 48);
+        assert_eq!(headers[3].start_epoch(), t0 + Duration::from_hours(36.0));
+
+        // split
+        let (lhs, rhs) = sp3.split(t0 + Duration::from_hours(6.0));
+        assert_eq!(lhs.header.num_epochs, 25);
+        assert_eq!(rhs.header.num_epochs, 167);
+        assert!(lhs.validate().is_empty());
+        assert!(rhs.validate().is_empty());
+
+        let (lhs, rhs) = sp3.header.split(t0 + Duration::from_hours(6.0));
+        assert_eq!(lhs.num_epochs, 25);
+        assert_eq!(rhs.num_epochs, 167);
+        assert_eq!(rhs.start_epoch(), t0 + Duration::from_seconds(22_500.0));
     }
 }
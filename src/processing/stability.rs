@@ -0,0 +1,505 @@
+use crate::{
+    math::invert,
+    prelude::{Duration, Epoch, SP3, SV},
+};
+
+use std::collections::{BTreeMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [Detrending] applied to the clock phase, prior stability analysis.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Detrending {
+    /// No detrending
+    #[default]
+    None,
+
+    /// Linear trend (frequency offset) is removed
+    Linear,
+
+    /// Quadratic trend (frequency offset and drift) is removed
+    Quadratic,
+}
+
+impl Detrending {
+    fn degree(&self) -> Option<usize> {
+        match self {
+            Self::None => None,
+            Self::Linear => Some(1),
+            Self::Quadratic => Some(2),
+        }
+    }
+}
+
+/// [ClockStabilityOptions] to tune [SP3::clock_stability].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockStabilityOptions {
+    /// [Detrending] applied to each continuous clock segment
+    pub detrending: Detrending,
+
+    /// Minimal number of terms for a deviation to be reported.
+    /// Averaging times with fewer terms are dropped.
+    pub min_terms: usize,
+}
+
+impl ClockStabilityOptions {
+    /// Copies and returns [ClockStabilityOptions] with this [Detrending].
+    pub fn with_detrending(&self, detrending: Detrending) -> Self {
+        let mut s = *self;
+        s.detrending = detrending;
+        s
+    }
+
+    /// Copies and returns [ClockStabilityOptions] with this minimal number of terms.
+    pub fn with_min_terms(&self, min_terms: usize) -> Self {
+        let mut s = *self;
+        s.min_terms = min_terms;
+        s
+    }
+}
+
+/// [StabilityPoint] is one deviation at one averaging time
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StabilityPoint {
+    /// Averaging time τ
+    pub tau: Duration,
+
+    /// Deviation (unitless, fractional frequency)
+    pub deviation: f64,
+
+    /// Number of terms that contributed
+    pub terms: usize,
+}
+
+/// [ClockStability] of one satellite clock, at octave spaced averaging times.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockStability {
+    /// Sampling period (τ0) of the phase data
+    pub sampling_period: Duration,
+
+    /// Number of phase samples
+    pub samples: usize,
+
+    /// Number of clock events that were removed
+    pub clock_events: usize,
+
+    /// Overlapping Allan deviation
+    pub adev: Vec<StabilityPoint>,
+
+    /// Modified Allan deviation
+    pub mdev: Vec<StabilityPoint>,
+
+    /// Overlapping Hadamard deviation
+    pub hdev: Vec<StabilityPoint>,
+}
+
+/// [ClockStabilityReport], obtained with [SP3::clock_stability].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockStabilityReport {
+    /// [ClockStability] per [SV]
+    pub satellites: BTreeMap<SV, ClockStability>,
+}
+
+impl ClockStabilityReport {
+    /// Groups the [ClockStability] per clock type (like "RB" or "CS"), as provided
+    /// in `clock_types`, because SP3 files do not describe the onboard clocks.
+    /// Satellites not described are grouped under "UNKNOWN".
+    pub fn per_clock_type(
+        &self,
+        clock_types: &BTreeMap<SV, String>,
+    ) -> BTreeMap<String, BTreeMap<SV, &ClockStability>> {
+        let mut groups = BTreeMap::<String, BTreeMap<SV, &ClockStability>>::new();
+
+        for (sv, stability) in self.satellites.iter() {
+            let clock_type = clock_types
+                .get(sv)
+                .cloned()
+                .unwrap_or_else(|| "UNKNOWN".to_string());
+
+            groups.entry(clock_type).or_default().insert(*sv, stability);
+        }
+
+        groups
+    }
+}
+
+/// Phase samples (in seconds) on a regular grid: None for missing samples.
+/// Each sample is tagged with its continuous segment index,
+/// which is incremented on each clock event.
+struct PhaseSeries {
+    phase_s: Vec<Option<f64>>,
+    segments: Vec<usize>,
+}
+
+impl PhaseSeries {
+    /// True if all samples of this range are present and belong to the same segment
+    fn is_valid(&self, start: usize, end: usize) -> bool {
+        let segment = self.segments[start];
+        (start..=end).all(|i| self.phase_s[i].is_some() && self.segments[i] == segment)
+    }
+
+    fn phase(&self, i: usize) -> f64 {
+        self.phase_s[i].unwrap_or_default()
+    }
+
+    /// Removes a polynomial trend of this degree, from each segment
+    fn detrend(&mut self, degree: usize) {
+        let segments = self.segments.iter().copied().collect::<HashSet<_>>();
+
+        for segment in segments {
+            let samples = (0..self.phase_s.len())
+                .filter(|i| self.segments[*i] == segment)
+                .filter_map(|i| Some((i as f64, self.phase_s[i]?)))
+                .collect::<Vec<_>>();
+
+            if samples.len() <= degree {
+                continue;
+            }
+
+            // normalized abscissa for numerical stability
+            let (first, last) = (samples[0].0, samples[samples.len() - 1].0);
+            let center = (first + last) / 2.0;
+            let half_span = ((last - first) / 2.0).max(1.0);
+
+            let mut normal = vec![vec![0.0_f64; degree + 1]; degree + 1];
+            let mut rhs = vec![0.0_f64; degree + 1];
+
+            for (x, y) in samples.iter() {
+                let x = (x - center) / half_span;
+                for (i, (row, rhs)) in normal.iter_mut().zip(rhs.iter_mut()).enumerate() {
+                    *rhs += x.powi(i as i32) * y;
+                    for (j, value) in row.iter_mut().enumerate() {
+                        *value += x.powi((i + j) as i32);
+                    }
+                }
+            }
+
+            let Some(inverse) = invert(&normal) else {
+                continue;
+            };
+
+            let coefficients = inverse
+                .iter()
+                .map(|row| row.iter().zip(rhs.iter()).map(|(a, b)| a * b).sum::<f64>())
+                .collect::<Vec<_>>();
+
+            for (i, phase_s) in self.phase_s.iter_mut().enumerate() {
+                if self.segments[i] != segment {
+                    continue;
+                }
+
+                if let Some(phase_s) = phase_s {
+                    let x = (i as f64 - center) / half_span;
+                    *phase_s -= coefficients
+                        .iter()
+                        .enumerate()
+                        .map(|(k, c)| c * x.powi(k as i32))
+                        .sum::<f64>();
+                }
+            }
+        }
+    }
+
+    /// Overlapping Allan variance at m.τ0, with number of terms
+    fn avar(&self, m: usize, tau_s: f64) -> Option<(f64, usize)> {
+        let n = self.phase_s.len();
+        let (mut sum, mut terms) = (0.0_f64, 0);
+
+        for i in 0..n.checked_sub(2 * m)? {
+            if self.is_valid(i, i + 2 * m) {
+                let d = self.phase(i + 2 * m) - 2.0 * self.phase(i + m) + self.phase(i);
+                sum += d.powi(2);
+                terms += 1;
+            }
+        }
+
+        if terms == 0 {
+            return None;
+        }
+
+        Some((sum / (2.0 * tau_s.powi(2) * terms as f64), terms))
+    }
+
+    /// Modified Allan variance at m.τ0, with number of terms
+    fn mvar(&self, m: usize, tau_s: f64) -> Option<(f64, usize)> {
+        let n = self.phase_s.len();
+        let (mut sum, mut terms) = (0.0_f64, 0);
+
+        for j in 0..(n + 1).checked_sub(3 * m)? {
+            if self.is_valid(j, j + 3 * m - 1) {
+                let d = (j..j + m)
+                    .map(|i| self.phase(i + 2 * m) - 2.0 * self.phase(i + m) + self.phase(i))
+                    .sum::<f64>();
+                sum += d.powi(2);
+                terms += 1;
+            }
+        }
+
+        if terms == 0 {
+            return None;
+        }
+
+        Some((
+            sum / (2.0 * (m as f64).powi(2) * tau_s.powi(2) * terms as f64),
+            terms,
+        ))
+    }
+
+    /// Overlapping Hadamard variance at m.τ0, with number of terms
+    fn hvar(&self, m: usize, tau_s: f64) -> Option<(f64, usize)> {
+        let n = self.phase_s.len();
+        let (mut sum, mut terms) = (0.0_f64, 0);
+
+        for i in 0..n.checked_sub(3 * m)? {
+            if self.is_valid(i, i + 3 * m) {
+                let d = self.phase(i + 3 * m) - 3.0 * self.phase(i + 2 * m)
+                    + 3.0 * self.phase(i + m)
+                    - self.phase(i);
+                sum += d.powi(2);
+                terms += 1;
+            }
+        }
+
+        if terms == 0 {
+            return None;
+        }
+
+        Some((sum / (6.0 * tau_s.powi(2) * terms as f64), terms))
+    }
+}
+
+impl SP3 {
+    /// Smallest interval between two clock offsets of this [SV]
+    fn clock_sampling_period(epochs: &[Epoch]) -> Option<Duration> {
+        epochs
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .filter(|dt| *dt > Duration::ZERO)
+            .min()
+    }
+
+    /// Analyzes the frequency stability of each satellite clock offset
+    /// (sentinel values are discarded): overlapping Allan deviation,
+    /// modified Allan deviation and overlapping Hadamard deviation, at octave spaced
+    /// averaging times (τ0, 2τ0, 4τ0..).
+    /// - data gaps are supported: terms involving a missing sample are discarded
+    /// - clock events split the phase series: terms across a clock event are discarded
+    /// - the optional [Detrending] is applied on each continuous segment.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let options = ClockStabilityOptions::default()
+    ///     .with_detrending(Detrending::Quadratic);
+    ///
+    /// let report = sp3.clock_stability(&options);
+    ///
+    /// for (sv, stability) in report.satellites.iter() {
+    ///     for point in stability.hdev.iter() {
+    ///         println!("{} HDEV({}) = {:e}", sv, point.tau, point.deviation);
+    ///     }
+    /// }
+    /// ```
+    pub fn clock_stability(&self, options: &ClockStabilityOptions) -> ClockStabilityReport {
+        let mut per_sv = BTreeMap::<SV, Vec<(Epoch, f64)>>::new();
+
+        for (k, v) in self.data.iter() {
+            if let Some(clock_us) = v.valid_clock_offset_us() {
+                per_sv
+                    .entry(k.sv)
+                    .or_default()
+                    .push((k.epoch, clock_us * 1.0E-6));
+            }
+        }
+
+        let events = self
+            .satellites_epoch_clock_event_iter()
+            .collect::<HashSet<_>>();
+
+        let mut report = ClockStabilityReport::default();
+
+        for (sv, samples) in per_sv.iter() {
+            let epochs = samples.iter().map(|(t, _)| *t).collect::<Vec<_>>();
+
+            let sampling_period = if self.header.sampling_period > Duration::ZERO {
+                self.header.sampling_period
+            } else {
+                match Self::clock_sampling_period(&epochs) {
+                    Some(dt) => dt,
+                    None => continue,
+                }
+            };
+
+            let tau0_s = sampling_period.to_seconds();
+            let t0 = epochs[0];
+            let size = ((epochs[epochs.len() - 1] - t0).to_seconds() / tau0_s).round() as usize + 1;
+
+            let mut series = PhaseSeries {
+                phase_s: vec![None; size],
+                segments: vec![0; size],
+            };
+
+            let mut clock_events = 0;
+
+            for (epoch, clock_s) in samples.iter() {
+                let index = ((*epoch - t0).to_seconds() / tau0_s).round() as usize;
+                series.phase_s[index] = Some(*clock_s);
+
+                if events.contains(&(*epoch, *sv)) {
+                    clock_events += 1;
+                    for segment in series.segments[index..].iter_mut() {
+                        *segment += 1;
+                    }
+                }
+            }
+
+            if let Some(degree) = options.detrending.degree() {
+                series.detrend(degree);
+            }
+
+            let mut stability = ClockStability {
+                sampling_period,
+                samples: samples.len(),
+                clock_events,
+                ..Default::default()
+            };
+
+            let min_terms = options.min_terms.max(1);
+            let mut m = 1;
+
+            while 2 * m < size {
+                let tau = sampling_period * m as f64;
+                let tau_s = tau.to_seconds();
+
+                for (variance, points) in [
+                    (series.avar(m, tau_s), &mut stability.adev),
+                    (series.mvar(m, tau_s), &mut stability.mdev),
+                    (series.hvar(m, tau_s), &mut stability.hdev),
+                ] {
+                    if let Some((variance, terms)) = variance {
+                        if terms >= min_terms {
+                            points.push(StabilityPoint {
+                                tau,
+                                deviation: variance.sqrt(),
+                                terms,
+                            });
+                        }
+                    }
+                }
+
+                m *= 2;
+            }
+
+            report.satellites.insert(*sv, stability);
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClockStabilityOptions, Detrending};
+    use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::{collections::BTreeMap, str::FromStr};
+
+    #[test]
+    fn clock_stability() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(300.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        // frequency drift D = 1E-15 s/s²: ADEV(τ) = D.τ/√2, HDEV = 0
+        let drift = 1.0E-15;
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        for i in 0..288 {
+            let epoch = t0 + dt * i as f64;
+            let t_s = 300.0 * i as f64;
+
+            let mut clock_s = 1.0E-4 + 1.0E-11 * t_s + 0.5 * drift * t_s.powi(2);
+
+            // G01: data gap, reported as sentinel values
+            let clock_us = if (100..110).contains(&i) {
+                999999.999999
+            } else {
+                clock_s * 1.0E6
+            };
+
+            sp3.data.insert(
+                SP3Key { sv: g01, epoch },
+                SP3Entry::from_position_km((0.0, 0.0, 0.0)).with_clock_offset_us(clock_us),
+            );
+
+            // G02: reported clock jump
+            let mut entry = SP3Entry::from_position_km((0.0, 0.0, 0.0));
+            if i >= 150 {
+                clock_s += 1.0E-6;
+            }
+
+            entry.clock_event = i == 150;
+            entry = entry.with_clock_offset_us(clock_s * 1.0E6);
+
+            sp3.data.insert(SP3Key { sv: g02, epoch }, entry);
+        }
+
+        let report = sp3.clock_stability(&ClockStabilityOptions::default());
+
+        for sv in [g01, g02] {
+            let stability = report.satellites.get(&sv).unwrap();
+            assert_eq!(stability.sampling_period, dt);
+            assert!(stability.adev.len() >= 6);
+
+            for point in stability.adev.iter() {
+                let expected = drift * point.tau.to_seconds() / 2.0_f64.sqrt();
+                assert!((point.deviation - expected).abs() / expected < 1.0E-3);
+            }
+
+            // MDEV of a linear frequency drift equals ADEV
+            for point in stability.mdev.iter() {
+                let expected = drift * point.tau.to_seconds() / 2.0_f64.sqrt();
+                assert!((point.deviation - expected).abs() / expected < 1.0E-3);
+            }
+
+            for point in stability.hdev.iter() {
+                assert!(point.deviation < 1.0E-18);
+            }
+        }
+
+        let g01_stability = report.satellites.get(&g01).unwrap();
+        assert_eq!(g01_stability.samples, 278);
+        assert_eq!(g01_stability.adev[0].terms, 286 - 12);
+
+        let g02_stability = report.satellites.get(&g02).unwrap();
+        assert_eq!(g02_stability.clock_events, 1);
+
+        // drift removal
+        let options = ClockStabilityOptions::default().with_detrending(Detrending::Quadratic);
+        let report = sp3.clock_stability(&options);
+
+        for stability in report.satellites.values() {
+            for point in stability.adev.iter() {
+                assert!(point.deviation < 1.0E-18);
+            }
+        }
+
+        let clock_types = BTreeMap::from([(g01, "RB".to_string())]);
+        let groups = report.per_clock_type(&clock_types);
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups["RB"].contains_key(&g01));
+        assert!(groups["UNKNOWN"].contains_key(&g02));
+    }
+}
@@ -1,8 +1,22 @@
-use crate::prelude::{Epoch, SP3Entry, SP3Key, TimeScale, SP3};
+#[cfg(doc)]
+use crate::prelude::Epoch;
+
+use crate::prelude::{SP3Entry, SP3Key, TimeScale, TimeSystem, SP3};
 use qc_traits::{TimeCorrectionError, TimeCorrectionsDB, Timeshift};
 
 use std::collections::BTreeMap;
 
+impl SP3 {
+    /// Updates the [TimeSystem] descriptor, when it no longer describes the [TimeScale]
+    fn update_time_system_mut(&mut self) {
+        if self.header.time_system.timescale() != self.header.timescale {
+            if let Some(time_system) = TimeSystem::from_timescale(self.header.timescale) {
+                self.header.time_system = time_system;
+            }
+        }
+    }
+}
+
 impl Timeshift for SP3 {
     fn timeshift(&self, timescale: TimeScale) -> Self
     where
@@ -13,36 +27,17 @@ impl Timeshift for SP3 {
         s
     }
 
+    /// Transposes this [SP3] to another [TimeScale]: all [Epoch]s are converted,
+    /// and the week counter and MJD are recomputed in the new [TimeScale]
+    /// (leap seconds apply when converting to or from UTC). The [TimeSystem]
+    /// descriptor is preserved when it still describes the new [TimeScale],
+    /// for example when converting [TimeSystem::GLO] to UTC.
     fn timeshift_mut(&mut self, timescale: TimeScale) {
-        // transpose week counter
-        let epoch = Epoch::from_time_of_week(
-            self.header.week,
-            self.header.week_nanos,
-            self.header.timescale,
-        )
-        .to_time_scale(timescale);
-
-        (self.header.week, self.header.week_nanos) = epoch.to_time_of_week();
-
-        // transpose MJD
-        let mut days = self.header.mjd as f64;
-        days += self.header.mjd_fraction;
-
-        let mjd_epoch = Epoch::from_mjd_in_time_scale(days, self.header.timescale);
-
-        let days = match self.header.timescale {
-            TimeScale::TAI | TimeScale::UTC => mjd_epoch.to_mjd_utc_days(),
-            ts => (mjd_epoch
-                - ts.reference_epoch()
-                    .to_duration_in_time_scale(TimeScale::TAI))
-            .to_mjd_utc_days(),
-        };
-
-        self.header.mjd = days.floor() as u32;
-        self.header.mjd_fraction = days.fract();
-
-        // finally: overwrite
+        let start = self.header.start_epoch().to_time_scale(timescale);
+
         self.header.timescale = timescale;
+        self.header.set_start_epoch_mut(start);
+        self.update_time_system_mut();
 
         let mut new = BTreeMap::<SP3Key, SP3Entry>::new();
 
@@ -52,10 +47,10 @@ impl Timeshift for SP3 {
                 epoch: k.epoch.to_time_scale(timescale),
             };
 
-            new.insert(key, v.clone());
+            new.insert(key, *v);
         }
 
-        self.data = new.clone();
+        self.data = new;
     }
 
     fn precise_correction(
@@ -78,35 +73,10 @@ impl Timeshift for SP3 {
     ) -> Result<(), TimeCorrectionError> {
         let (lhs, rhs) = (self.header.timescale, timescale);
 
-        // transpose week counter
-        let epoch = Epoch::from_time_of_week(self.header.week, self.header.week_nanos, lhs);
-
-        let transposed = db
-            .precise_epoch_correction(epoch, rhs)
+        let start = db
+            .precise_epoch_correction(self.header.start_epoch(), rhs)
             .ok_or(TimeCorrectionError::NoCorrectionAvailable(lhs, rhs))?;
 
-        (self.header.week, self.header.week_nanos) = transposed.to_time_of_week();
-
-        // transpose MJD
-        let mut days = self.header.mjd as f64;
-        days += self.header.mjd_fraction;
-
-        let mjd_epoch = Epoch::from_mjd_in_time_scale(days, lhs);
-
-        let days = match self.header.timescale {
-            TimeScale::TAI | TimeScale::UTC => mjd_epoch.to_mjd_utc_days(),
-            ts => (mjd_epoch
-                - ts.reference_epoch()
-                    .to_duration_in_time_scale(TimeScale::TAI))
-            .to_mjd_utc_days(),
-        };
-
-        self.header.mjd = days.floor() as u32;
-        self.header.mjd_fraction = days.fract();
-
-        // finally: overwrite
-        self.header.timescale = rhs;
-
         let mut new = BTreeMap::<SP3Key, SP3Entry>::new();
 
         for (k, v) in self.data.iter() {
@@ -116,10 +86,14 @@ impl Timeshift for SP3 {
 
             let key = SP3Key { epoch, sv: k.sv };
 
-            new.insert(key, v.clone());
+            new.insert(key, *v);
         }
 
-        self.data = new.clone();
+        self.data = new;
+
+        self.header.timescale = rhs;
+        self.header.set_start_epoch_mut(start);
+        self.update_time_system_mut();
 
         Ok(())
     }
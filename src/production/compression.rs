@@ -0,0 +1,72 @@
+use crate::errors::ParsingError;
+
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// [FileCompression] algorithms, identified by the file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FileCompression {
+    /// Gzip compression (`.gz`)
+    Gzip,
+
+    /// Unix compress, LZW compression (`.Z`)
+    Unix,
+
+    /// Bzip2 compression (`.bz2`)
+    Bzip2,
+
+    /// Zstandard compression (`.zst`)
+    Zstd,
+}
+
+impl FileCompression {
+    /// Identifies [FileCompression] from the first bytes of the content.
+    /// Returns None for plain (uncompressed) content.
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(&[0x1f, 0x9d]) {
+            Some(Self::Unix)
+        } else if bytes.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Identifies [FileCompression] from the file extension.
+    /// Returns None when the extension is not a known compression.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl std::fmt::Display for FileCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gz"),
+            Self::Unix => write!(f, "Z"),
+            Self::Bzip2 => write!(f, "bz2"),
+            Self::Zstd => write!(f, "zst"),
+        }
+    }
+}
+
+impl std::str::FromStr for FileCompression {
+    type Err = ParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gz" => Ok(Self::Gzip),
+            "Z" => Ok(Self::Unix),
+            "bz2" => Ok(Self::Bzip2),
+            "zst" => Ok(Self::Zstd),
+            _ => Err(ParsingError::InvalidFilename),
+        }
+    }
+}
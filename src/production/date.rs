@@ -3,7 +3,7 @@ use crate::prelude::Epoch;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use hifitime::HifitimeError;
+use hifitime::{HifitimeError, Unit};
 
 /// [SP3] [ReleaseDate]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
@@ -14,13 +14,24 @@ pub struct ReleaseDate {
 
     /// Day of year (starting at 1).
     pub doy: u16,
+
+    /// Hour of day
+    pub hour: u8,
+
+    /// Minute of hour
+    pub minute: u8,
 }
 
 impl From<Epoch> for ReleaseDate {
     fn from(value: Epoch) -> Self {
+        let day_of_year = value.day_of_year();
+        let minutes = (day_of_year.fract() * 1440.0 + 1.0E-6).floor() as u16;
+
         Self {
             year: value.year() as u16,
-            doy: value.day_of_year().floor() as u16,
+            doy: day_of_year.floor() as u16,
+            hour: (minutes / 60) as u8,
+            minute: (minutes % 60) as u8,
         }
     }
 }
@@ -28,6 +39,7 @@ impl From<Epoch> for ReleaseDate {
 impl ReleaseDate {
     /// Converts [ReleaseDate] to [Epoch]
     pub fn to_epoch(&self) -> Result<Epoch, HifitimeError> {
-        Epoch::from_format_str(&format!("{} {}", self.year, self.doy), "%Y %j")
+        let epoch = Epoch::from_format_str(&format!("{} {}", self.year, self.doy), "%Y %j")?;
+        Ok(epoch + self.hour as f64 * Unit::Hour + self.minute as f64 * Unit::Minute)
     }
 }
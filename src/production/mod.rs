@@ -1,4 +1,9 @@
-use crate::{prelude::Duration, ParsingError};
+use crate::{
+    prelude::{Duration, Epoch, TimeScale},
+    ParsingError,
+};
+
+use std::str::FromStr;
 
 #[cfg(doc)]
 use crate::prelude::SP3;
@@ -9,19 +14,26 @@ pub use campaign::*;
 mod availability;
 pub use availability::*;
 
+mod compression;
+pub use compression::*;
+
 mod date;
 pub use date::*;
 
 mod period;
 pub use period::*;
 
+pub(crate) use period::{format_filename_duration, parse_filename_duration};
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// [SP3] [ProductionAttributes] come with files that
-/// follow standard naming conventions.
+/// follow standard naming conventions, either the IGS long product filenames
+/// (like `ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz`),
+/// or the legacy short filenames (like `igs21930.sp3` or `igu21930_18.sp3.Z`).
 /// See <https://files.igs.org/pub/resource/guidelines/Guidelines_for_Long_Product_Filenames_in_the_IGS_v2.2_EN.pdf>
-#[derive(Default, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ProductionAttributes {
     /// 3-Letter code
@@ -30,7 +42,7 @@ pub struct ProductionAttributes {
     /// ID# in case this file is part of a batch (starting at 0).
     pub batch_id: u8,
 
-    /// [ReleaseDate]
+    /// [ReleaseDate]: start of the time frame covered by this file.
     pub release_date: ReleaseDate,
 
     /// [ReleasePeriod]
@@ -45,45 +57,167 @@ pub struct ProductionAttributes {
     /// Steady sampling period as [Duration] contained in this file
     pub sampling_period: Duration,
 
-    /// True if this file was gzip compressed
-    #[cfg(feature = "flate2")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
-    pub gzip_compressed: bool,
+    /// 3-Letter content type, `ORB` for SP3 files
+    pub content_type: String,
+
+    /// [FileCompression] when this file is compressed
+    pub compression: Option<FileCompression>,
+
+    /// True when this file follows the legacy short filename convention
+    pub short_name: bool,
+
+    /// Extension of legacy short filenames, either `sp3` or `eph`
+    pub short_name_extension: String,
 }
 
-impl std::fmt::Display for ProductionAttributes {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let sampling_interval_mins = (self.sampling_period.to_seconds() / 60.0).round() as u16;
-
-        write!(
-            f,
-            "{}{}{}{}_{:04}{:03}0000_{}_{:02}M_ORB.SP3",
-            &self.agency[..3],
-            self.batch_id,
-            self.campaign,
-            self.availability,
-            self.release_date.year,
-            self.release_date.doy,
-            self.release_period,
-            sampling_interval_mins,
-        )?;
-
-        #[cfg(feature = "flate2")]
-        if self.gzip_compressed {
-            write!(f, ".gz")?;
+impl Default for ProductionAttributes {
+    fn default() -> Self {
+        Self {
+            agency: Default::default(),
+            batch_id: Default::default(),
+            release_date: Default::default(),
+            release_period: Default::default(),
+            campaign: Default::default(),
+            availability: Default::default(),
+            sampling_period: Default::default(),
+            content_type: "ORB".to_string(),
+            compression: None,
+            short_name: false,
+            short_name_extension: "sp3".to_string(),
         }
-
-        Ok(())
     }
 }
 
-impl std::str::FromStr for ProductionAttributes {
-    type Err = ParsingError;
+/// Sampling period of legacy products
+const SHORT_NAME_SAMPLING_PERIOD_MINS: f64 = 15.0;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let size = s.len();
+/// Time frame covered by legacy ultra rapid products
+const SHORT_NAME_ULTRA_RAPID_PERIOD_HOURS: f64 = 48.0;
+
+impl ProductionAttributes {
+    /// True if this file was gzip compressed
+    #[deprecated(note = "use the `compression` field instead")]
+    pub fn gzip_compressed(&self) -> bool {
+        self.compression == Some(FileCompression::Gzip)
+    }
+
+    /// Returns legacy 3-letter prefix: agency code, with dedicated
+    /// codes for IGS rapid and ultra rapid products.
+    fn short_name_prefix(&self) -> String {
+        if self.agency.eq_ignore_ascii_case("IGS") {
+            match self.availability {
+                Availability::Final => "igs".to_string(),
+                Availability::Rapid => "igr".to_string(),
+                Availability::UltraRapid => "igu".to_string(),
+            }
+        } else {
+            self.agency.to_lowercase()
+        }
+    }
+
+    /// Returns (GPS week, day of week) of the [ReleaseDate]
+    fn gps_week_day(&self) -> (u32, u64) {
+        let t =
+            Epoch::from_gregorian_at_midnight(self.release_date.year as i32, 1, 1, TimeScale::GPST)
+                + Duration::from_days(self.release_date.doy.saturating_sub(1) as f64);
 
-        if size < 38 {
+        let (week, nanos) = t.to_time_of_week();
+        (week, nanos / 86_400_000_000_000)
+    }
+
+    /// Parses legacy short filename, like `igs21930.sp3` or `igu21930_18.sp3.Z`
+    fn from_short_name(s: &str) -> Result<Self, ParsingError> {
+        if s.len() < 12 || !s.is_ascii() {
+            return Err(ParsingError::InvalidFilename);
+        }
+
+        let prefix = s[0..3].to_lowercase();
+
+        let week = s[3..7]
+            .parse::<u32>()
+            .or(Err(ParsingError::InvalidFilename))?;
+
+        let day = s[7..8]
+            .parse::<u64>()
+            .or(Err(ParsingError::InvalidFilename))?;
+
+        if day > 6 {
+            return Err(ParsingError::InvalidFilename);
+        }
+
+        let mut rem = &s[8..];
+        let mut hour = 0;
+
+        if let Some(hh) = rem.strip_prefix('_') {
+            if hh.len() < 2 {
+                return Err(ParsingError::InvalidFilename);
+            }
+
+            hour = hh[0..2]
+                .parse::<u8>()
+                .or(Err(ParsingError::InvalidFilename))?;
+
+            rem = &hh[2..];
+        }
+
+        let (extension, compression) = match rem.strip_prefix('.') {
+            Some(rem) => match rem.split_once('.') {
+                Some((extension, compression)) => {
+                    (extension, Some(FileCompression::from_str(compression)?))
+                },
+                None => (rem, None),
+            },
+            None => return Err(ParsingError::InvalidFilename),
+        };
+
+        if !extension.eq_ignore_ascii_case("sp3") && !extension.eq_ignore_ascii_case("eph") {
+            return Err(ParsingError::InvalidFilename);
+        }
+
+        let (agency, availability) = match prefix.as_str() {
+            "igs" => ("IGS".to_string(), Availability::Final),
+            "igr" => ("IGS".to_string(), Availability::Rapid),
+            "igu" => ("IGS".to_string(), Availability::UltraRapid),
+            prefix => (prefix.to_uppercase(), Availability::Final),
+        };
+
+        let release_period = if availability == Availability::UltraRapid {
+            ReleasePeriod::Other(Duration::from_hours(SHORT_NAME_ULTRA_RAPID_PERIOD_HOURS))
+        } else {
+            ReleasePeriod::Daily
+        };
+
+        let t = Epoch::from_time_of_week(week, day * 86_400_000_000_000, TimeScale::GPST);
+
+        let mut release_date = ReleaseDate::from(t);
+        release_date.hour = hour;
+
+        Ok(Self {
+            agency,
+            availability,
+            release_date,
+            release_period,
+            sampling_period: Duration::from_seconds(SHORT_NAME_SAMPLING_PERIOD_MINS * 60.0),
+            compression,
+            short_name: true,
+            short_name_extension: extension.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Parses IGS long product filename
+    fn from_long_name(s: &str) -> Result<Self, ParsingError> {
+        if s.len() < 38 || !s.is_ascii() {
+            return Err(ParsingError::InvalidFilename);
+        }
+
+        for offset in [10, 22, 26, 30] {
+            if &s[offset..offset + 1] != "_" {
+                return Err(ParsingError::InvalidFilename);
+            }
+        }
+
+        if &s[34..35] != "." || !s[35..38].eq_ignore_ascii_case("SP3") {
             return Err(ParsingError::InvalidFilename);
         }
 
@@ -98,31 +232,32 @@ impl std::str::FromStr for ProductionAttributes {
         let availability =
             Availability::from_str(&s[7..10]).or(Err(ParsingError::InvalidFilename))?;
 
-        let release_year = s[11..15]
+        let year = s[11..15]
             .parse::<u16>()
             .or(Err(ParsingError::InvalidFilename))?;
 
-        let release_doy = s[15..18]
+        let doy = s[15..18]
             .parse::<u16>()
             .or(Err(ParsingError::InvalidFilename))?;
 
-        let release_period = ReleasePeriod::from_str(&s[23..26])?;
+        let hour = s[18..20]
+            .parse::<u8>()
+            .or(Err(ParsingError::InvalidFilename))?;
 
-        let sampling = s[27..29]
+        let minute = s[20..22]
             .parse::<u8>()
             .or(Err(ParsingError::InvalidFilename))?;
 
-        let scaling = match &s[29..30] {
-            "S" => 1.0,
-            "M" => 60.0,
-            "H" => 3600.0,
-            "D" => 24.0 * 3600.0,
-            "W" => 7.0 * 24.0 * 3600.0,
-            "L" => 30.0 * 7.0 * 24.0 * 3600.0,
-            _ => 365.0 * 7.0 * 24.0 * 3600.0,
-        };
+        let release_period = ReleasePeriod::from_str(&s[23..26])?;
+        let sampling_period = parse_filename_duration(&s[27..30])?;
+
+        let content_type = s[31..34].to_string();
 
-        let sampling_period = Duration::from_seconds((sampling as f64) * scaling);
+        let compression = match s[38..].strip_prefix('.') {
+            Some(compression) => Some(FileCompression::from_str(compression)?),
+            None if s.len() == 38 => None,
+            None => return Err(ParsingError::InvalidFilename),
+        };
 
         Ok(Self {
             agency,
@@ -130,17 +265,71 @@ impl std::str::FromStr for ProductionAttributes {
             campaign,
             availability,
             release_date: ReleaseDate {
-                year: release_year,
-                doy: release_doy,
+                year,
+                doy,
+                hour,
+                minute,
             },
             release_period,
             sampling_period,
-            #[cfg(feature = "flate2")]
-            gzip_compressed: s.ends_with(".gz"),
+            content_type,
+            compression,
+            short_name: false,
+            short_name_extension: "sp3".to_string(),
         })
     }
 }
 
+impl std::fmt::Display for ProductionAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.short_name {
+            let (week, day) = self.gps_week_day();
+
+            write!(f, "{}{:04}{}", self.short_name_prefix(), week, day)?;
+
+            if self.availability == Availability::UltraRapid {
+                write!(f, "_{:02}", self.release_date.hour)?;
+            }
+
+            write!(f, ".{}", self.short_name_extension)?;
+        } else {
+            write!(
+                f,
+                "{}{}{}{}_{:04}{:03}{:02}{:02}_{}_{}_{}.SP3",
+                &self.agency[..3],
+                self.batch_id,
+                self.campaign,
+                self.availability,
+                self.release_date.year,
+                self.release_date.doy,
+                self.release_date.hour,
+                self.release_date.minute,
+                self.release_period,
+                format_filename_duration(self.sampling_period),
+                self.content_type,
+            )?;
+        }
+
+        if let Some(compression) = self.compression {
+            write!(f, ".{}", compression)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ProductionAttributes {
+    type Err = ParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() >= 38 && s.get(10..11) == Some("_") {
+            Self::from_long_name(s)
+        } else {
+            Self::from_short_name(s)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -152,6 +341,8 @@ mod test {
         let release_date = ReleaseDate {
             year: 2023,
             doy: 239,
+            hour: 0,
+            minute: 0,
         };
 
         let release_epoch = release_date.to_epoch().unwrap();
@@ -172,12 +363,17 @@ mod test {
                     release_date: ReleaseDate {
                         year: 2023,
                         doy: 239,
+                        hour: 0,
+                        minute: 0,
                     },
                     campaign: Campaign::OPS,
                     availability: Availability::Rapid,
                     release_period: ReleasePeriod::Daily,
                     sampling_period: Duration::from_hours(0.25),
-                    gzip_compressed: true,
+                    content_type: "ORB".to_string(),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: false,
+                    short_name_extension: "sp3".to_string(),
                 },
                 "ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz",
             ),
@@ -186,11 +382,19 @@ mod test {
                     agency: "GRS".to_string(),
                     batch_id: 0,
                     campaign: Campaign::MGX,
-                    release_date: ReleaseDate { year: 2019, doy: 1 },
+                    release_date: ReleaseDate {
+                        year: 2019,
+                        doy: 1,
+                        hour: 0,
+                        minute: 0,
+                    },
                     availability: Availability::Final,
                     release_period: ReleasePeriod::Hourly,
                     sampling_period: Duration::from_hours(0.25),
-                    gzip_compressed: true,
+                    content_type: "ORB".to_string(),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: false,
+                    short_name_extension: "sp3".to_string(),
                 },
                 "GRS0MGXFIN_20190010000_01H_15M_ORB.SP3.gz",
             ),
@@ -199,11 +403,19 @@ mod test {
                     agency: "GRS".to_string(),
                     campaign: Campaign::Test,
                     batch_id: 5,
-                    release_date: ReleaseDate { year: 2019, doy: 1 },
+                    release_date: ReleaseDate {
+                        year: 2019,
+                        doy: 1,
+                        hour: 0,
+                        minute: 0,
+                    },
                     availability: Availability::Final,
                     release_period: ReleasePeriod::Hourly,
                     sampling_period: Duration::from_seconds(5.0 * 60.0),
-                    gzip_compressed: true,
+                    content_type: "ORB".to_string(),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: false,
+                    short_name_extension: "sp3".to_string(),
                 },
                 "GRS5TSTFIN_20190010000_01H_05M_ORB.SP3.gz",
             ),
@@ -212,11 +424,19 @@ mod test {
                     agency: "GRS".to_string(),
                     campaign: Campaign::Reprocessing(01),
                     batch_id: 1,
-                    release_date: ReleaseDate { year: 2019, doy: 1 },
+                    release_date: ReleaseDate {
+                        year: 2019,
+                        doy: 1,
+                        hour: 0,
+                        minute: 0,
+                    },
                     availability: Availability::Final,
                     release_period: ReleasePeriod::Hourly,
                     sampling_period: Duration::from_seconds(5.0 * 60.0),
-                    gzip_compressed: true,
+                    content_type: "ORB".to_string(),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: false,
+                    short_name_extension: "sp3".to_string(),
                 },
                 "GRS1R01FIN_20190010000_01H_05M_ORB.SP3.gz",
             ),
@@ -225,11 +445,19 @@ mod test {
                     agency: "GRS".to_string(),
                     campaign: Campaign::Reprocessing(10),
                     batch_id: 1,
-                    release_date: ReleaseDate { year: 2019, doy: 1 },
+                    release_date: ReleaseDate {
+                        year: 2019,
+                        doy: 1,
+                        hour: 0,
+                        minute: 0,
+                    },
                     availability: Availability::Final,
                     release_period: ReleasePeriod::Hourly,
                     sampling_period: Duration::from_seconds(5.0 * 60.0),
-                    gzip_compressed: true,
+                    content_type: "ORB".to_string(),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: false,
+                    short_name_extension: "sp3".to_string(),
                 },
                 "GRS1R10FIN_20190010000_01H_05M_ORB.SP3.gz",
             ),
@@ -238,14 +466,157 @@ mod test {
                     agency: "GRS".to_string(),
                     campaign: Campaign::Reprocessing(99),
                     batch_id: 1,
-                    release_date: ReleaseDate { year: 2019, doy: 1 },
+                    release_date: ReleaseDate {
+                        year: 2019,
+                        doy: 1,
+                        hour: 0,
+                        minute: 0,
+                    },
                     availability: Availability::Final,
                     release_period: ReleasePeriod::Hourly,
                     sampling_period: Duration::from_seconds(5.0 * 60.0),
-                    gzip_compressed: true,
+                    content_type: "ORB".to_string(),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: false,
+                    short_name_extension: "sp3".to_string(),
                 },
                 "GRS1R99FIN_20190010000_01H_05M_ORB.SP3.gz",
             ),
+            (
+                ProductionAttributes {
+                    agency: "EMR".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2023,
+                        doy: 239,
+                        hour: 18,
+                        minute: 0,
+                    },
+                    availability: Availability::UltraRapid,
+                    release_period: ReleasePeriod::Other(Duration::from_days(2.0)),
+                    sampling_period: Duration::from_hours(0.25),
+                    compression: Some(FileCompression::Unix),
+                    ..Default::default()
+                },
+                "EMR0OPSULT_20232391800_02D_15M_ORB.SP3.Z",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "COD".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2024,
+                        doy: 32,
+                        hour: 6,
+                        minute: 30,
+                    },
+                    campaign: Campaign::MGX,
+                    availability: Availability::Final,
+                    release_period: ReleasePeriod::Other(Duration::from_hours(6.0)),
+                    sampling_period: Duration::from_seconds(30.0),
+                    compression: Some(FileCompression::Zstd),
+                    ..Default::default()
+                },
+                "COD0MGXFIN_20240320630_06H_30S_ORB.SP3.zst",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "WUM".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2024,
+                        doy: 32,
+                        hour: 0,
+                        minute: 0,
+                    },
+                    release_period: ReleasePeriod::Weekly,
+                    sampling_period: Duration::from_days(1.0),
+                    compression: Some(FileCompression::Bzip2),
+                    ..Default::default()
+                },
+                "WUM0OPSRAP_20240320000_01W_01D_ORB.SP3.bz2",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "IGS".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2022,
+                        doy: 16,
+                        hour: 0,
+                        minute: 0,
+                    },
+                    availability: Availability::Final,
+                    sampling_period: Duration::from_hours(0.25),
+                    short_name: true,
+                    ..Default::default()
+                },
+                "igs21930.sp3",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "IGS".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2022,
+                        doy: 19,
+                        hour: 0,
+                        minute: 0,
+                    },
+                    availability: Availability::Rapid,
+                    sampling_period: Duration::from_hours(0.25),
+                    compression: Some(FileCompression::Unix),
+                    short_name: true,
+                    ..Default::default()
+                },
+                "igr21933.sp3.Z",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "IGS".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2022,
+                        doy: 16,
+                        hour: 18,
+                        minute: 0,
+                    },
+                    availability: Availability::UltraRapid,
+                    release_period: ReleasePeriod::Other(Duration::from_days(2.0)),
+                    sampling_period: Duration::from_hours(0.25),
+                    short_name: true,
+                    ..Default::default()
+                },
+                "igu21930_18.sp3",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "COD".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2022,
+                        doy: 22,
+                        hour: 0,
+                        minute: 0,
+                    },
+                    availability: Availability::Final,
+                    sampling_period: Duration::from_hours(0.25),
+                    compression: Some(FileCompression::Gzip),
+                    short_name: true,
+                    ..Default::default()
+                },
+                "cod21936.sp3.gz",
+            ),
+            (
+                ProductionAttributes {
+                    agency: "COD".to_string(),
+                    release_date: ReleaseDate {
+                        year: 2022,
+                        doy: 22,
+                        hour: 0,
+                        minute: 0,
+                    },
+                    availability: Availability::Final,
+                    sampling_period: Duration::from_hours(0.25),
+                    short_name: true,
+                    short_name_extension: "eph".to_string(),
+                    ..Default::default()
+                },
+                "cod21936.eph",
+            ),
         ] {
             let parsed = ProductionAttributes::from_str(filename).unwrap_or_else(|e| {
                 panic!(
@@ -256,8 +627,29 @@ mod test {
 
             assert_eq!(parsed, expected);
 
+            #[allow(deprecated)]
+            let gzip_compressed = parsed.gzip_compressed();
+            assert_eq!(gzip_compressed, filename.ends_with(".gz"));
+
             let formatted = expected.to_string();
             assert_eq!(formatted, filename);
         }
+
+        for filename in [
+            "ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.rar",
+            "ESA0OPSRAP_20232390000_01D_15X_ORB.SP3",
+            "ESA0OPSRAP-20232390000_01D_15M_ORB.SP3",
+            "igs21937.sp3",
+            "igs2193.sp3",
+            "igs21930.clk",
+            "prévision.sp3",
+            "igu21930_é.sp3",
+        ] {
+            assert!(
+                ProductionAttributes::from_str(filename).is_err(),
+                "\"{}\" should not be accepted",
+                filename
+            );
+        }
     }
 }
@@ -4,7 +4,62 @@ use serde::{Deserialize, Serialize};
 #[cfg(doc)]
 use crate::prelude::SP3;
 
-use crate::ParsingError;
+use crate::{prelude::Duration, ParsingError};
+
+/// Filename duration units, from largest to smallest, that may be used when formatting.
+/// Months (`L`) are only supported when parsing, because they do not have a fixed length.
+const FORMATTING_UNITS: [(char, f64); 6] = [
+    ('Y', 365.0 * 86400.0),
+    ('W', 7.0 * 86400.0),
+    ('D', 86400.0),
+    ('H', 3600.0),
+    ('M', 60.0),
+    ('S', 1.0),
+];
+
+/// Parses a filename duration like `15M`, `02D` or `00U` (unspecified).
+/// Months (`L`) are considered to be 30 days long.
+pub(crate) fn parse_filename_duration(s: &str) -> Result<Duration, ParsingError> {
+    if s.len() != 3 {
+        return Err(ParsingError::InvalidFilename);
+    }
+
+    let value = s[0..2]
+        .parse::<u8>()
+        .or(Err(ParsingError::InvalidFilename))? as f64;
+
+    let scaling = match &s[2..3] {
+        "S" => 1.0,
+        "M" => 60.0,
+        "H" => 3600.0,
+        "D" => 86400.0,
+        "W" => 7.0 * 86400.0,
+        "L" => 30.0 * 86400.0,
+        "Y" => 365.0 * 86400.0,
+        "U" => 0.0,
+        _ => return Err(ParsingError::InvalidFilename),
+    };
+
+    Ok(Duration::from_seconds(value * scaling))
+}
+
+/// Formats a [Duration] as filename duration, using the largest unit
+/// that describes it exactly on two digits, or `00U` when that is not possible.
+pub(crate) fn format_filename_duration(dt: Duration) -> String {
+    let seconds = dt.to_seconds();
+
+    if seconds > 0.0 {
+        for (unit, unit_seconds) in FORMATTING_UNITS {
+            let value = seconds / unit_seconds;
+
+            if value.fract() == 0.0 && value <= 99.0 {
+                return format!("{:02}{}", value as u8, unit);
+            }
+        }
+    }
+
+    "00U".to_string()
+}
 
 /// [SP3] [ReleasePeriod]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
@@ -28,6 +83,41 @@ pub enum ReleasePeriod {
 
     /// [ReleasePeriod::Yearly] files
     Yearly,
+
+    /// Any other period, like `02D` or `06H`.
+    /// [Duration::ZERO] means unspecified (`00U`).
+    Other(Duration),
+}
+
+impl ReleasePeriod {
+    /// Returns [ReleasePeriod] matching this [Duration].
+    /// [ReleasePeriod::Monthly] is never returned, because months do not have a fixed length.
+    pub fn from_duration(dt: Duration) -> Self {
+        [
+            Self::Hourly,
+            Self::HalfDay,
+            Self::Daily,
+            Self::Weekly,
+            Self::Yearly,
+        ]
+        .into_iter()
+        .find(|period| period.duration() == dt)
+        .unwrap_or(Self::Other(dt))
+    }
+
+    /// Returns [ReleasePeriod] as [Duration].
+    /// Months are considered to be 30 days long.
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Hourly => Duration::from_hours(1.0),
+            Self::HalfDay => Duration::from_hours(12.0),
+            Self::Daily => Duration::from_days(1.0),
+            Self::Weekly => Duration::from_days(7.0),
+            Self::Monthly => Duration::from_days(30.0),
+            Self::Yearly => Duration::from_days(365.0),
+            Self::Other(dt) => *dt,
+        }
+    }
 }
 
 impl std::fmt::Display for ReleasePeriod {
@@ -39,6 +129,7 @@ impl std::fmt::Display for ReleasePeriod {
             Self::Weekly => write!(f, "01W"),
             Self::Monthly => write!(f, "01L"),
             Self::Yearly => write!(f, "01Y"),
+            Self::Other(dt) => write!(f, "{}", format_filename_duration(*dt)),
         }
     }
 }
@@ -54,7 +145,7 @@ impl std::str::FromStr for ReleasePeriod {
             "01W" => Ok(Self::Weekly),
             "01L" => Ok(Self::Monthly),
             "01Y" => Ok(Self::Yearly),
-            _ => Err(ParsingError::InvalidFilename),
+            s => Ok(Self::from_duration(parse_filename_duration(s)?)),
         }
     }
 }
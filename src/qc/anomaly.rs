@@ -0,0 +1,509 @@
+use crate::{
+    lagrange_interpolation,
+    math::norm,
+    prelude::{Duration, Epoch, SP3Entry, SP3, SV},
+    Vector3D,
+};
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Sentinel value used by SP3 producers for bad or absent data
+const SENTINEL_VALUE: f64 = 999_999.0;
+
+/// [AnomalySeverity] of an [Anomaly]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AnomalySeverity {
+    /// Exceeds the detection threshold
+    Warning,
+
+    /// Exceeds ten times the detection threshold, or invalid data
+    Critical,
+}
+
+/// [AnomalyKind] describes the nature of an [Anomaly]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AnomalyKind {
+    /// Position departs from the polynomial model of the neighboring epochs.
+    /// Magnitude is the 3D residual in meters.
+    PositionJump,
+
+    /// Clock departs from the polynomial model of the neighboring epochs,
+    /// while no clock event was reported. Magnitude is the residual in nanoseconds.
+    ClockJump,
+
+    /// Null or sentinel (999999.999999) coordinates.
+    /// Magnitude is the position norm in kilometers.
+    InvalidPosition,
+
+    /// Null or sentinel (999999.999999) clock offset.
+    /// Magnitude is the clock offset in microseconds.
+    InvalidClock,
+
+    /// Velocity (published, or between consecutive epochs) exceeds the physical limit.
+    /// Magnitude is the velocity norm in km.s⁻¹.
+    Velocity,
+}
+
+/// [Anomaly] found by [SP3::anomalies]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Anomaly {
+    /// [Epoch] of this anomaly
+    pub epoch: Epoch,
+
+    /// Affected [SV]
+    pub sv: SV,
+
+    /// [AnomalyKind]
+    pub kind: AnomalyKind,
+
+    /// Magnitude, unit depends on [AnomalyKind]
+    pub magnitude: f64,
+
+    /// [AnomalySeverity]
+    pub severity: AnomalySeverity,
+}
+
+/// [AnomalyDetectionOptions] to tune [SP3::anomalies].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnomalyDetectionOptions {
+    /// Number of neighboring epochs, on each side, used to model the position.
+    /// The polynomial degree is twice this value, minus one.
+    pub position_neighbors: usize,
+
+    /// Position residual threshold, in meters
+    pub position_threshold_m: f64,
+
+    /// Number of neighboring epochs, on each side, used to model the clock.
+    /// The polynomial degree is twice this value, minus one.
+    pub clock_neighbors: usize,
+
+    /// Clock residual threshold, in nanoseconds
+    pub clock_threshold_ns: f64,
+
+    /// Maximal Earth fixed velocity, in km.s⁻¹
+    pub max_velocity_km_s: f64,
+}
+
+impl Default for AnomalyDetectionOptions {
+    fn default() -> Self {
+        Self {
+            position_neighbors: 4,
+            position_threshold_m: 1.0,
+            clock_neighbors: 2,
+            clock_threshold_ns: 10.0,
+            max_velocity_km_s: 10.0,
+        }
+    }
+}
+
+impl AnomalyDetectionOptions {
+    /// Copies and returns [AnomalyDetectionOptions] with this position threshold (in meters).
+    pub fn with_position_threshold_m(&self, threshold_m: f64) -> Self {
+        let mut s = *self;
+        s.position_threshold_m = threshold_m;
+        s
+    }
+
+    /// Copies and returns [AnomalyDetectionOptions] with this clock threshold (in nanoseconds).
+    pub fn with_clock_threshold_ns(&self, threshold_ns: f64) -> Self {
+        let mut s = *self;
+        s.clock_threshold_ns = threshold_ns;
+        s
+    }
+
+    /// Copies and returns [AnomalyDetectionOptions] with this maximal velocity (in km.s⁻¹).
+    pub fn with_max_velocity_km_s(&self, max_velocity_km_s: f64) -> Self {
+        let mut s = *self;
+        s.max_velocity_km_s = max_velocity_km_s;
+        s
+    }
+}
+
+fn is_invalid_position(position_km: Vector3D) -> bool {
+    let (x, y, z) = position_km;
+    (x == 0.0 && y == 0.0 && z == 0.0)
+        || x.abs() >= SENTINEL_VALUE
+        || y.abs() >= SENTINEL_VALUE
+        || z.abs() >= SENTINEL_VALUE
+}
+
+fn is_invalid_clock(clock_us: f64) -> bool {
+    clock_us == 0.0 || clock_us.abs() >= SENTINEL_VALUE
+}
+
+fn severity(magnitude: f64, threshold: f64) -> AnomalySeverity {
+    if magnitude > 10.0 * threshold {
+        AnomalySeverity::Critical
+    } else {
+        AnomalySeverity::Warning
+    }
+}
+
+/// Leave-one-out polynomial residuals of a time series:
+/// each sample is compared to the polynomial passing through its neighbors.
+/// Samples whose neighbors are not all available within the time frame are not evaluated.
+fn polynomial_residuals(
+    series: &[(Epoch, Vector3D)],
+    neighbors: usize,
+    sampling_period: Duration,
+) -> Vec<Option<Vector3D>> {
+    let max_dt = sampling_period * (neighbors as f64 + 0.5);
+
+    (0..series.len())
+        .map(|i| {
+            if neighbors == 0 || i < neighbors || i + neighbors >= series.len() {
+                return None;
+            }
+
+            let (t, value) = series[i];
+
+            let window = series[i - neighbors..i]
+                .iter()
+                .chain(series[i + 1..=i + neighbors].iter())
+                .copied()
+                .collect::<Vec<_>>();
+
+            if window.iter().any(|(t_j, _)| (*t_j - t).abs() > max_dt) {
+                return None;
+            }
+
+            let model = lagrange_interpolation(window.len() - 1, t, window)?;
+            Some((value.0 - model.0, value.1 - model.1, value.2 - model.2))
+        })
+        .collect()
+}
+
+/// Only retains detections that are the largest within this many samples,
+/// because one outlier also affects the model of its neighbors.
+fn local_maxima(detections: &[Option<f64>], neighbors: usize) -> Vec<usize> {
+    (0..detections.len())
+        .filter(|i| {
+            let Some(magnitude) = detections[*i] else {
+                return false;
+            };
+
+            let start = i.saturating_sub(neighbors);
+            let end = (i + neighbors).min(detections.len() - 1);
+
+            (start..=end).all(|j| match detections[j] {
+                Some(other) => other < magnitude || (other == magnitude && j >= *i),
+                None => true,
+            })
+        })
+        .collect()
+}
+
+impl SP3 {
+    /// Scans the position and clock time series of each satellite, and reports
+    /// all [Anomaly]s, sorted by [Epoch] then [SV]:
+    /// - null or sentinel (999999.999999) positions and clocks
+    /// - velocities exceeding the physical limit
+    /// - position jumps, with respect to a polynomial model of the neighboring epochs
+    /// - clock jumps that were not flagged as clock events, with respect to
+    ///   a polynomial model of the neighboring epochs.
+    ///
+    /// Maneuvered entries are not tested for position jumps.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let anomalies = sp3.anomalies(&AnomalyDetectionOptions::default());
+    ///
+    /// for anomaly in anomalies.iter() {
+    ///     if anomaly.severity == AnomalySeverity::Critical {
+    ///         println!("{}({}): {:?}", anomaly.epoch, anomaly.sv, anomaly.kind);
+    ///     }
+    /// }
+    /// ```
+    pub fn anomalies(&self, options: &AnomalyDetectionOptions) -> Vec<Anomaly> {
+        let mut anomalies = Vec::<Anomaly>::new();
+        let mut per_sv = BTreeMap::<SV, Vec<(Epoch, SP3Entry)>>::new();
+
+        for (k, v) in self.data.iter() {
+            per_sv.entry(k.sv).or_default().push((k.epoch, *v));
+        }
+
+        let sampling_period = self.nominal_sampling_period();
+
+        for (sv, entries) in per_sv.iter() {
+            let mut positions = Vec::<(Epoch, Vector3D)>::new();
+            let mut maneuvers = Vec::<bool>::new();
+            let mut clocks = Vec::<(Epoch, Vector3D)>::new();
+            let mut clock_events = Vec::<bool>::new();
+
+            for (epoch, entry) in entries.iter() {
+                if is_invalid_position(entry.position_km) {
+                    anomalies.push(Anomaly {
+                        epoch: *epoch,
+                        sv: *sv,
+                        kind: AnomalyKind::InvalidPosition,
+                        magnitude: norm(entry.position_km),
+                        severity: AnomalySeverity::Critical,
+                    });
+                } else {
+                    if let Some((past_t, past_position_km)) = positions.last() {
+                        let dt = (*epoch - *past_t).to_seconds();
+                        let (x, y, z) = entry.position_km;
+                        let velocity_km_s = norm((
+                            (x - past_position_km.0) / dt,
+                            (y - past_position_km.1) / dt,
+                            (z - past_position_km.2) / dt,
+                        ));
+
+                        if velocity_km_s > options.max_velocity_km_s {
+                            anomalies.push(Anomaly {
+                                epoch: *epoch,
+                                sv: *sv,
+                                kind: AnomalyKind::Velocity,
+                                magnitude: velocity_km_s,
+                                severity: AnomalySeverity::Critical,
+                            });
+                        }
+                    }
+
+                    positions.push((*epoch, entry.position_km));
+                    maneuvers.push(entry.maneuver);
+                }
+
+                if let Some(velocity_km_s) = entry.velocity_km_s {
+                    let velocity_km_s = norm(velocity_km_s);
+                    if velocity_km_s > options.max_velocity_km_s {
+                        anomalies.push(Anomaly {
+                            epoch: *epoch,
+                            sv: *sv,
+                            kind: AnomalyKind::Velocity,
+                            magnitude: velocity_km_s,
+                            severity: AnomalySeverity::Critical,
+                        });
+                    }
+                }
+
+                if let Some(clock_us) = entry.clock_us {
+                    if is_invalid_clock(clock_us) {
+                        anomalies.push(Anomaly {
+                            epoch: *epoch,
+                            sv: *sv,
+                            kind: AnomalyKind::InvalidClock,
+                            magnitude: clock_us,
+                            severity: AnomalySeverity::Critical,
+                        });
+                    } else {
+                        clocks.push((*epoch, (clock_us, 0.0, 0.0)));
+                        clock_events.push(entry.clock_event);
+                    }
+                }
+            }
+
+            // position jumps
+            let residuals =
+                polynomial_residuals(&positions, options.position_neighbors, sampling_period);
+
+            let detections = residuals
+                .iter()
+                .zip(maneuvers.iter())
+                .map(|(residual, maneuver)| {
+                    let residual_m = norm((*residual)?) * 1.0E3;
+                    if !maneuver && residual_m > options.position_threshold_m {
+                        Some(residual_m)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            for i in local_maxima(&detections, options.position_neighbors) {
+                if let Some(magnitude) = detections[i] {
+                    anomalies.push(Anomaly {
+                        epoch: positions[i].0,
+                        sv: *sv,
+                        kind: AnomalyKind::PositionJump,
+                        magnitude,
+                        severity: severity(magnitude, options.position_threshold_m),
+                    });
+                }
+            }
+
+            // clock jumps
+            let residuals = polynomial_residuals(&clocks, options.clock_neighbors, sampling_period);
+
+            let detections = residuals
+                .iter()
+                .enumerate()
+                .map(|(i, residual)| {
+                    // a reported event explains any discontinuity in its surroundings
+                    let start = i.saturating_sub(options.clock_neighbors);
+                    let end = (i + options.clock_neighbors).min(clock_events.len() - 1);
+
+                    if clock_events[start..=end].iter().any(|event| *event) {
+                        return None;
+                    }
+
+                    let residual_ns = residual.as_ref()?.0.abs() * 1.0E3;
+                    if residual_ns > options.clock_threshold_ns {
+                        Some(residual_ns)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            for i in local_maxima(&detections, options.clock_neighbors) {
+                if let Some(magnitude) = detections[i] {
+                    anomalies.push(Anomaly {
+                        epoch: clocks[i].0,
+                        sv: *sv,
+                        kind: AnomalyKind::ClockJump,
+                        magnitude,
+                        severity: severity(magnitude, options.clock_threshold_ns),
+                    });
+                }
+            }
+        }
+
+        anomalies.sort_by_key(|a| (a.epoch, a.sv));
+        anomalies
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AnomalyDetectionOptions, AnomalyKind, AnomalySeverity};
+    use crate::prelude::{Duration, Epoch, SP3Entry, SP3Key, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn anomaly_detection() {
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(900.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g02 = SV::from_str("G02").unwrap();
+
+        let mut sp3 = SP3::default();
+        sp3.header.sampling_period = dt;
+
+        // circular MEO orbit, linear clocks
+        let (radius_km, rate_rad_s) = (26_560.0, 1.458E-4);
+
+        for i in 0..40 {
+            let epoch = t0 + dt * i as f64;
+            let phase = rate_rad_s * 900.0 * i as f64;
+
+            for (sv, offset) in [(g01, 0.0), (g02, 1.0)] {
+                let (sin, cos) = (phase + offset).sin_cos();
+                let mut position_km = (radius_km * cos, radius_km * sin, 0.0);
+                let mut clock_us = 100.0 + 1.0E-3 * i as f64;
+
+                let mut entry = SP3Entry::from_position_km((0.0, 0.0, 0.0));
+
+                if sv == g01 {
+                    match i {
+                        // 5 m jump
+                        10 => position_km.2 += 5.0E-3,
+                        // sentinel clock
+                        15 => clock_us = 999_999.999_999,
+                        // leaked null position
+                        20 => position_km = (0.0, 0.0, 0.0),
+                        _ => {},
+                    }
+                } else {
+                    // undeclared 50 ns jump
+                    if i >= 25 {
+                        clock_us += 0.05;
+                    }
+
+                    // reported clock event
+                    if i == 30 {
+                        entry.clock_event = true;
+                        clock_us += 1.0;
+                    }
+                }
+
+                entry.position_km = position_km;
+                entry.clock_us = Some(clock_us);
+
+                sp3.data.insert(SP3Key { sv, epoch }, entry);
+            }
+        }
+
+        let anomalies = sp3.anomalies(&AnomalyDetectionOptions::default());
+
+        // sampling period is deduced from the record when not declared
+        let mut undeclared = sp3.clone();
+        undeclared.header.sampling_period = Duration::ZERO;
+        assert_eq!(
+            undeclared.anomalies(&AnomalyDetectionOptions::default()),
+            anomalies
+        );
+
+        let g01_anomalies = anomalies
+            .iter()
+            .filter(|anomaly| anomaly.sv == g01)
+            .collect::<Vec<_>>();
+
+        let position_jumps = g01_anomalies
+            .iter()
+            .filter(|anomaly| anomaly.kind == AnomalyKind::PositionJump)
+            .collect::<Vec<_>>();
+
+        assert_eq!(position_jumps.len(), 1);
+        assert_eq!(position_jumps[0].epoch, t0 + dt * 10.0);
+        assert!(position_jumps[0].magnitude > 4.0);
+        assert_eq!(position_jumps[0].severity, AnomalySeverity::Warning);
+
+        let invalid = g01_anomalies
+            .iter()
+            .filter(|anomaly| {
+                matches!(
+                    anomaly.kind,
+                    AnomalyKind::InvalidClock | AnomalyKind::InvalidPosition
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(invalid.len(), 2);
+        assert_eq!(invalid[0].epoch, t0 + dt * 15.0);
+        assert_eq!(invalid[0].kind, AnomalyKind::InvalidClock);
+        assert_eq!(invalid[1].epoch, t0 + dt * 20.0);
+        assert_eq!(invalid[1].severity, AnomalySeverity::Critical);
+
+        // null position does not induce velocity anomalies
+        assert!(g01_anomalies
+            .iter()
+            .all(|anomaly| anomaly.kind != AnomalyKind::Velocity));
+
+        assert!(g01_anomalies
+            .iter()
+            .all(|anomaly| anomaly.kind != AnomalyKind::ClockJump));
+
+        // G02: only the undeclared clock jump
+        let g02_anomalies = anomalies
+            .iter()
+            .filter(|anomaly| anomaly.sv == g02)
+            .collect::<Vec<_>>();
+
+        assert_eq!(g02_anomalies.len(), 1);
+        assert_eq!(g02_anomalies[0].kind, AnomalyKind::ClockJump);
+        assert!(g02_anomalies[0].magnitude > 10.0);
+
+        let epoch = g02_anomalies[0].epoch;
+        assert!(epoch >= t0 + dt * 24.0 && epoch <= t0 + dt * 25.0);
+
+        // unphysical velocity
+        let options = AnomalyDetectionOptions::default().with_max_velocity_km_s(1.0);
+        let anomalies = sp3.anomalies(&options);
+
+        assert!(anomalies
+            .iter()
+            .any(|anomaly| anomaly.kind == AnomalyKind::Velocity));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::prelude::{Constellation, Header, SP3};
+use crate::prelude::{Constellation, Header, ReferenceFrame, SP3};
 
 use qc_traits::{Merge, MergeError};
 
@@ -14,7 +14,9 @@ impl Merge for Header {
     /// - data must be published by the same provider
     /// - both files must be expressed in the same [TimeScale].
     /// Use our [Timeshift] transpositions if you need it.
-    /// - both files must use the same coordinates system
+    /// - both files must use the same coordinates system, or equivalent
+    ///   realizations of the same ITRF (like IGS20 and ITRF2020): the coordinates
+    ///   system of Self is preserved. Use [SP3::to_reference_frame] if you need it.
     fn merge(&self, rhs: &Self) -> Result<Self, MergeError>
     where
         Self: Sized,
@@ -36,7 +38,13 @@ impl Merge for Header {
         }
 
         if self.coord_system != rhs.coord_system {
-            return Err(MergeError::ReferenceFrameMismatch);
+            let lhs_frame = self.coord_system.parse::<ReferenceFrame>();
+            let rhs_frame = rhs.coord_system.parse::<ReferenceFrame>();
+
+            match (lhs_frame, rhs_frame) {
+                (Ok(lhs), Ok(rhs)) if lhs.itrf() == rhs.itrf() => {},
+                _ => return Err(MergeError::ReferenceFrameMismatch),
+            }
         }
 
         // "upgrade" constellation
@@ -97,6 +105,35 @@ impl Merge for SP3 {
                 self.data.insert(key.clone(), entry.clone()); // new entry
             }
         }
+
+        self.rebuild_header_mut();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::Header;
+    use qc_traits::Merge;
+
+    #[test]
+    fn header_reference_frame_merge() {
+        let lhs = Header {
+            coord_system: "IGS20".to_string(),
+            ..Default::default()
+        };
+
+        let mut rhs = lhs.clone();
+        rhs.coord_system = "ITR20".to_string();
+
+        // equivalent realizations
+        let merged = lhs.merge(&rhs).unwrap();
+        assert_eq!(merged.coord_system, "IGS20");
+
+        rhs.coord_system = "ITR14".to_string();
+        assert!(lhs.merge(&rhs).is_err());
+
+        rhs.coord_system = "ITRF2".to_string();
+        assert!(lhs.merge(&rhs).is_err());
+    }
+}
@@ -1 +1,4 @@
+mod anomaly;
 mod merge;
+
+pub use anomaly::{Anomaly, AnomalyDetectionOptions, AnomalyKind, AnomalySeverity};
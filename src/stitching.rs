@@ -0,0 +1,261 @@
+//! Ultra-rapid products stitching
+use crate::prelude::{Availability, Duration, Epoch, SP3Entry, SP3Key, StitchingError, SP3, SV};
+
+use itertools::Itertools;
+
+use std::collections::{BTreeMap, HashMap};
+
+/// [StitchedOrbit] is a continuous product, obtained by stitching
+/// a sequence of ultra-rapid products, with [SP3::stitch_ultra_rapid].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StitchedOrbit {
+    /// Stitched [SP3]
+    pub sp3: SP3,
+
+    /// Prediction age of each entry: time elapsed since the last fitted [Epoch]
+    /// of this [SV], in the product the entry was taken from.
+    /// [Duration::ZERO] for fitted entries.
+    pub prediction_age: BTreeMap<SP3Key, Duration>,
+}
+
+impl StitchedOrbit {
+    /// Returns the prediction age of this [SV] at this [Epoch], if it exists.
+    pub fn prediction_age(&self, epoch: Epoch, sv: SV) -> Option<Duration> {
+        self.prediction_age.get(&SP3Key { epoch, sv }).copied()
+    }
+
+    /// Returns an [Iterator] over all predicted entries, with their prediction age.
+    pub fn satellites_prediction_age_iter(
+        &self,
+    ) -> impl Iterator<Item = (Epoch, SV, Duration)> + '_ {
+        self.prediction_age.iter().filter_map(|(k, age)| {
+            if *age > Duration::ZERO {
+                Some((k.epoch, k.sv, *age))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the last fitted [Epoch] of the stitched product, after
+    /// which all entries are predicted.
+    pub fn last_fitted_epoch(&self) -> Option<Epoch> {
+        self.sp3
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                if v.predicted_orbit {
+                    None
+                } else {
+                    Some(k.epoch)
+                }
+            })
+            .max()
+    }
+}
+
+/// Last fitted [Epoch] of each [SV] in this product
+fn last_fitted_epochs(sp3: &SP3) -> HashMap<SV, Epoch> {
+    let mut epochs = HashMap::new();
+
+    for (k, _) in sp3.data.iter().filter(|(_, v)| !v.predicted_orbit) {
+        epochs.insert(k.sv, k.epoch);
+    }
+
+    epochs
+}
+
+impl SP3 {
+    /// Stitches a sequence of ultra-rapid [SP3] products into one continuous product.
+    /// Ultra-rapid products are released every 6 hours, and each covers 24 hours of
+    /// fitted (observed) orbits, followed by 24 hours of predicted orbits.
+    /// For each [Epoch] and [SV], fitted entries are always preferred over predicted entries,
+    /// then the most recent product (latest first [Epoch]) is preferred. The stitched product
+    /// is therefore made of the most recent fitted portion, followed by the newest predicted portion.
+    /// Products do not have to be sorted. [ProductionAttributes](crate::prelude::ProductionAttributes)
+    /// are verified when they exist, and all products must be expressed in the same
+    /// [TimeScale](crate::prelude::TimeScale) and coordinates system, by the same data provider.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let esa = SP3::from_path("data/SP3/C/ESA0OPSULT_20232320600_02D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// let stitched = SP3::stitch_ultra_rapid(&[esa.clone()])
+    ///     .unwrap();
+    ///
+    /// for (epoch, sv, age) in stitched.satellites_prediction_age_iter() {
+    ///     println!("{} {}: predicted {} ago", epoch, sv, age);
+    /// }
+    ///
+    /// // products from different data providers may not be stitched
+    /// let emr = SP3::from_path("data/SP3/C/EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// assert!(matches!(
+    ///     SP3::stitch_ultra_rapid(&[esa, emr]),
+    ///     Err(StitchingError::DataProviderMismatch)
+    /// ));
+    /// ```
+    pub fn stitch_ultra_rapid(products: &[SP3]) -> Result<StitchedOrbit, StitchingError> {
+        let products = products
+            .iter()
+            .filter_map(|sp3| Some((sp3.first_epoch()?, sp3)))
+            .sorted_by_key(|(first_epoch, _)| *first_epoch)
+            .map(|(_, sp3)| sp3)
+            .collect::<Vec<_>>();
+
+        let Some(newest) = products.last() else {
+            return Err(StitchingError::NoData);
+        };
+
+        for sp3 in products.iter() {
+            if let Some(attributes) = &sp3.prod_attributes {
+                if attributes.availability != Availability::UltraRapid {
+                    return Err(StitchingError::NotUltraRapid);
+                }
+            }
+
+            if sp3.header.agency != newest.header.agency {
+                return Err(StitchingError::DataProviderMismatch);
+            }
+
+            if sp3.header.timescale != newest.header.timescale {
+                return Err(StitchingError::TimescaleMismatch);
+            }
+
+            if sp3.header.coord_system != newest.header.coord_system {
+                return Err(StitchingError::ReferenceFrameMismatch);
+            }
+        }
+
+        let mut data = BTreeMap::<SP3Key, SP3Entry>::new();
+        let mut prediction_age = BTreeMap::<SP3Key, Duration>::new();
+
+        // from newest to oldest: only replace predicted entries with fitted entries
+        for sp3 in products.iter().rev() {
+            let last_fitted = last_fitted_epochs(sp3);
+
+            for (k, v) in sp3.data.iter() {
+                let replace = match data.get(k) {
+                    Some(stitched) => stitched.predicted_orbit && !v.predicted_orbit,
+                    None => true,
+                };
+
+                if !replace {
+                    continue;
+                }
+
+                let age = if v.predicted_orbit {
+                    match last_fitted.get(&k.sv) {
+                        Some(last_fitted) => k.epoch - *last_fitted,
+                        None => k.epoch - sp3.first_epoch().unwrap_or(k.epoch),
+                    }
+                } else {
+                    Duration::ZERO
+                };
+
+                data.insert(k.clone(), *v);
+                prediction_age.insert(k.clone(), age);
+            }
+        }
+
+        let mut sp3 = SP3 {
+            header: newest.header.clone(),
+            comments: newest.comments.clone(),
+            // no longer describes a single product
+            prod_attributes: None,
+            data,
+        };
+
+        sp3.rebuild_header_mut();
+
+        Ok(StitchedOrbit {
+            sp3,
+            prediction_age,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{Duration, Epoch, SP3Builder, SP3Entry, StitchingError, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn ultra_rapid_stitching() {
+        let t0 = Epoch::from_str("2023-08-20T00:00:00 GPST").unwrap();
+        let dt = Duration::from_hours(1.0);
+        let g01 = SV::from_str("G01").unwrap();
+
+        // three products released every 6h: 24h fitted + 24h predicted,
+        // position x identifies the product
+        let products = (0..3)
+            .rev()
+            .map(|n| {
+                let mut builder = SP3Builder::default().with_sampling_period(dt);
+                let first = t0 + Duration::from_hours(6.0 * n as f64);
+
+                for i in 0..48 {
+                    let position_km = (n as f64, 2.0, 3.0);
+
+                    let entry = if i < 24 {
+                        SP3Entry::from_position_km(position_km)
+                    } else {
+                        SP3Entry::from_predicted_position_km(position_km)
+                    };
+
+                    builder.push(first + dt * i as f64, g01, entry);
+                }
+
+                builder.build().unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let stitched = SP3::stitch_ultra_rapid(&products).unwrap();
+
+        let last_fitted = t0 + Duration::from_hours(35.0);
+        assert_eq!(stitched.last_fitted_epoch(), Some(last_fitted));
+
+        assert_eq!(stitched.sp3.first_epoch(), Some(t0));
+        assert_eq!(
+            stitched.sp3.last_epoch(),
+            Some(t0 + Duration::from_hours(59.0))
+        );
+        assert_eq!(stitched.sp3.header.num_epochs, 60);
+        assert!(stitched.sp3.validate().is_empty());
+
+        for (k, v) in stitched.sp3.data.iter() {
+            let age = stitched.prediction_age(k.epoch, k.sv).unwrap();
+
+            let product = if k.epoch < t0 + Duration::from_hours(6.0) {
+                0.0
+            } else if k.epoch < t0 + Duration::from_hours(12.0) {
+                1.0
+            } else {
+                2.0
+            };
+
+            assert_eq!(v.position_km.0, product, "wrong product at {}", k.epoch);
+
+            if k.epoch > last_fitted {
+                assert!(v.predicted_orbit);
+                assert_eq!(age, k.epoch - last_fitted);
+            } else {
+                assert!(!v.predicted_orbit);
+                assert_eq!(age, Duration::ZERO);
+            }
+        }
+
+        assert_eq!(stitched.satellites_prediction_age_iter().count(), 24);
+        assert!(SP3::stitch_ultra_rapid(&[]).is_err());
+
+        let mut products = products;
+        products[0].header.agency = "EMR".to_string();
+
+        assert!(matches!(
+            SP3::stitch_ultra_rapid(&products),
+            Err(StitchingError::DataProviderMismatch)
+        ));
+    }
+}
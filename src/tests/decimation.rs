@@ -0,0 +1,129 @@
+//! SP3 decimation operations
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use itertools::Itertools;
+    use qc_traits::{Decimate, DecimationFilter, FilterItem};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    fn mgex_file(revision: &str, name: &str) -> SP3 {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join(revision)
+            .join(name);
+
+        SP3::from_gzip_file(&path).unwrap()
+    }
+
+    /// Number of entries per [Epoch]
+    fn entries_per_epoch(sp3: &SP3, epoch: Epoch) -> usize {
+        sp3.data.keys().filter(|k| k.epoch == epoch).count()
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_decimation() {
+        let sp3 = mgex_file("C", "GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz");
+
+        assert_eq!(sp3.total_epochs(), 96);
+
+        for (modulo, expected) in [(2, 48), (3, 32), (4, 24), (96, 1)] {
+            let decimated = sp3.decimate(&DecimationFilter::modulo(modulo));
+
+            assert_eq!(decimated.total_epochs(), expected, "modulo {}", modulo);
+            assert_eq!(decimated.header.num_epochs, expected as u64);
+            assert!(decimated.validate().is_empty());
+
+            // epoch wise decimation: all satellites are preserved
+            for epoch in decimated.epochs_iter() {
+                assert_eq!(
+                    entries_per_epoch(&decimated, epoch),
+                    entries_per_epoch(&sp3, epoch),
+                    "modulo {}: satellites dropped at {}",
+                    modulo,
+                    epoch
+                );
+            }
+        }
+
+        let decimated = sp3.decimate(&DecimationFilter::duration(Duration::from_hours(1.0)));
+
+        assert_eq!(decimated.total_epochs(), 24);
+        assert_eq!(decimated.header.sampling_period, Duration::from_hours(1.0));
+
+        // header order may differ: compare as sets
+        assert_eq!(
+            decimated
+                .header
+                .satellites
+                .iter()
+                .sorted()
+                .collect::<Vec<_>>(),
+            sp3.header.satellites.iter().sorted().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_targeted_decimation() {
+        let sp3 = mgex_file("C", "GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz");
+
+        let filter = DecimationFilter::modulo(4)
+            .with_item(FilterItem::ConstellationItem(vec![Constellation::GPS]));
+
+        let decimated = sp3.decimate(&filter);
+
+        // record time frame is preserved
+        assert_eq!(decimated.total_epochs(), 96);
+        assert_eq!(decimated.header.sampling_period, sp3.header.sampling_period);
+
+        for (k, _) in sp3.data.iter() {
+            let retained = decimated.data.contains_key(k);
+
+            if k.sv.constellation == Constellation::GPS {
+                let index = sp3.epochs_iter().position(|t| t == k.epoch).unwrap();
+                assert_eq!(retained, index % 4 == 0, "{} {}", k.epoch, k.sv);
+            } else {
+                assert!(retained, "{} {} should not be decimated", k.epoch, k.sv);
+            }
+        }
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+
+        let filter = DecimationFilter::duration(Duration::from_hours(2.0))
+            .with_item(FilterItem::SvItem(vec![g01]));
+
+        let decimated = sp3.decimate(&filter);
+
+        let g01_epochs = decimated.data.keys().filter(|k| k.sv == g01).count();
+        let e01_epochs = decimated.data.keys().filter(|k| k.sv == e01).count();
+
+        assert_eq!(g01_epochs, 12);
+        assert_eq!(e01_epochs, sp3.data.keys().filter(|k| k.sv == e01).count());
+    }
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn cod0mgxfin_decimation() {
+        let sp3 = mgex_file("D", "COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz");
+
+        let decimated = sp3.decimate(&DecimationFilter::duration(Duration::from_seconds(900.0)));
+
+        assert_eq!(decimated.total_epochs(), sp3.total_epochs().div_ceil(3));
+        assert_eq!(
+            decimated.header.sampling_period,
+            Duration::from_seconds(900.0)
+        );
+
+        for epoch in decimated.epochs_iter() {
+            assert_eq!(
+                entries_per_epoch(&decimated, epoch),
+                entries_per_epoch(&sp3, epoch)
+            );
+        }
+    }
+}
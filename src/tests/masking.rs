@@ -0,0 +1,75 @@
+//! SP3 masking operations
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use qc_traits::{FilterItem, MaskFilter, MaskOperand, Masking};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn grg0mgxfin_masking() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("data/SP3")
+            .join("C")
+            .join("GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_gzip_file(&path).unwrap();
+
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let g10 = SV::from_str("G10").unwrap();
+
+        let masks = [
+            MaskFilter {
+                item: FilterItem::EpochItem(t0 + Duration::from_hours(12.0)),
+                operand: MaskOperand::GreaterEquals,
+            },
+            MaskFilter {
+                item: FilterItem::ConstellationItem(vec![Constellation::GPS]),
+                operand: MaskOperand::Equals,
+            },
+            MaskFilter {
+                item: FilterItem::SvItem(vec![g10]),
+                operand: MaskOperand::LowerThan,
+            },
+            MaskFilter {
+                item: FilterItem::DurationItem(Duration::from_hours(1.0)),
+                operand: MaskOperand::GreaterEquals,
+            },
+            MaskFilter {
+                item: SP3MaskItem::Fitted.into(),
+                operand: MaskOperand::Equals,
+            },
+        ];
+
+        let mut masked = sp3.clone();
+
+        for mask in masks.iter() {
+            masked.mask_mut(mask);
+            assert!(masked.validate().is_empty(), "{:?}", mask);
+        }
+
+        assert_eq!(masked.first_epoch(), Some(t0 + Duration::from_hours(12.0)));
+        assert_eq!(masked.total_epochs(), 12);
+        assert_eq!(masked.header.sampling_period, Duration::from_hours(1.0));
+        assert_eq!(masked.header.constellation, Constellation::GPS);
+
+        for sv in masked.header.satellites.iter() {
+            assert_eq!(sv.constellation, Constellation::GPS);
+            assert!(sv.prn < 10);
+        }
+
+        // header only
+        let mut header = sp3.header.clone();
+
+        for mask in masks.iter() {
+            header.mask_mut(mask);
+        }
+
+        assert_eq!(header.start_epoch(), t0 + Duration::from_hours(12.0));
+        assert_eq!(header.num_epochs, 12);
+        assert_eq!(header.satellites, masked.header.satellites);
+    }
+}
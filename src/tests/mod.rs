@@ -5,6 +5,7 @@ mod maneuver;
 mod parser_3c;
 mod parser_3d;
 mod test_pool;
+mod timescales;
 
 #[cfg(feature = "qc")]
 mod merge;
@@ -12,11 +13,11 @@ mod merge;
 #[cfg(feature = "processing")]
 mod substract;
 
-//#[cfg(feature = "qc")]
-//mod decimation;
+#[cfg(feature = "processing")]
+mod decimation;
 
-//#[cfg(feature = "qc")]
-//mod masking;
+#[cfg(feature = "processing")]
+mod masking;
 
 use log::LevelFilter;
 use std::sync::Once;
@@ -100,7 +100,7 @@ mod test {
         assert_eq!(attributes.release_date.doy, 239);
         assert_eq!(attributes.release_period, ReleasePeriod::Daily);
         assert_eq!(attributes.sampling_period, sp3.header.sampling_period);
-        assert!(attributes.gzip_compressed);
+        assert_eq!(attributes.compression, Some(FileCompression::Gzip));
 
         assert_eq!(
             sp3.standardized_filename(),
@@ -34,7 +34,7 @@ mod test {
             });
 
             // parse back
-            let _ = SP3::from_file(&proposed).unwrap_or_else(|e| {
+            let _ = SP3::from_path(&proposed).unwrap_or_else(|e| {
                 panic!("Failed to parse dumped data/C/{}: {}", file, e);
             });
 
@@ -65,7 +65,7 @@ mod test {
             });
 
             // parse back
-            let parsed_back = SP3::from_file(&proposed).unwrap_or_else(|e| {
+            let parsed_back = SP3::from_path(&proposed).unwrap_or_else(|e| {
                 panic!("Failed to parse dumped data/C/{}: {}", file, e);
             });
 
@@ -105,7 +105,7 @@ mod test {
             });
 
             // parse back
-            let _ = SP3::from_file(&proposed).unwrap_or_else(|e| {
+            let _ = SP3::from_path(&proposed).unwrap_or_else(|e| {
                 panic!("Failed to parse dumped data/D/{}: {}", file, e);
             });
 
@@ -141,7 +141,7 @@ mod test {
             });
 
             // parse back
-            let _ = SP3::from_file(&proposed).unwrap_or_else(|e| {
+            let _ = SP3::from_path(&proposed).unwrap_or_else(|e| {
                 panic!("Failed to parse dumped data/D/{}: {}", file, e);
             });
 
@@ -185,7 +185,7 @@ mod test {
             });
 
             // parse back
-            let _ = SP3::from_file(&proposed).unwrap_or_else(|e| {
+            let _ = SP3::from_path(&proposed).unwrap_or_else(|e| {
                 panic!("Failed to parse dumped data/C/{}: {}", file, e);
             });
 
@@ -0,0 +1,150 @@
+//! Time systems support
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::io::{BufReader, BufWriter};
+    use std::str::FromStr;
+
+    /// Formats and parses this [SP3] back
+    fn round_trip(sp3: &SP3) -> SP3 {
+        let mut buffer = BufWriter::new(Vec::<u8>::new());
+        sp3.format(&mut buffer).unwrap();
+
+        let content = buffer.into_inner().unwrap();
+        SP3::from_reader(&mut BufReader::new(content.as_slice())).unwrap()
+    }
+
+    /// Builds 4 hours of data, starting at this date in this [TimeSystem]
+    fn synthetic(time_system: TimeSystem, t0: &str) -> SP3 {
+        let dt = Duration::from_seconds(900.0);
+        let r01 = SV::from_str("R01").unwrap();
+
+        let t0 = Epoch::from_str(&format!("{} {}", t0, time_system.timescale())).unwrap();
+
+        let mut builder = SP3Builder::default()
+            .with_agency("TEST")
+            .with_time_system(time_system);
+
+        let mut t = t0;
+
+        for _ in 0..16 {
+            let entry = SP3Entry::from_position_km((1.0, 2.0, 3.0)).with_clock_offset_us(1.0);
+            builder.push(t, r01, entry);
+
+            // epochs are evenly spaced in the file timescale
+            t = Epoch::from_duration(t.to_duration_in_time_scale(t.time_scale) + dt, t.time_scale);
+        }
+
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn time_systems_round_trip() {
+        for descriptor in ["GPS", "GLO", "GAL", "QZS", "BDT", "IRN", "TAI", "UTC"] {
+            let time_system = TimeSystem::from_str(descriptor).unwrap();
+
+            // UTC based: the file spans the 2016 leap second
+            let t0 = match time_system {
+                TimeSystem::GLO | TimeSystem::UTC => "2016-12-31T22:00:00",
+                _ => "2020-06-25T00:00:00",
+            };
+
+            let sp3 = synthetic(time_system, t0);
+
+            assert!(
+                sp3.validate().is_empty(),
+                "{}: {:?}",
+                descriptor,
+                sp3.validate()
+            );
+            assert!(sp3.has_steady_sampling(), "{}", descriptor);
+
+            let parsed = round_trip(&sp3);
+
+            assert_eq!(parsed.header.time_system, time_system);
+            assert_eq!(parsed.header.timescale, time_system.timescale());
+            assert_eq!(parsed.header.week, sp3.header.week, "{}", descriptor);
+            assert_eq!(parsed.header.week_nanos, sp3.header.week_nanos);
+            assert_eq!(parsed.header.mjd, sp3.header.mjd);
+            assert_eq!(parsed.header.sampling_period, Duration::from_seconds(900.0));
+            assert_eq!(parsed.header.start_epoch(), sp3.first_epoch().unwrap());
+
+            // satellites list is not formatted: only verify time related fields
+            for inconsistency in parsed.validate() {
+                assert!(
+                    !matches!(
+                        inconsistency,
+                        HeaderInconsistency::WeekCounter { .. }
+                            | HeaderInconsistency::ModifiedJulianDate { .. }
+                            | HeaderInconsistency::SamplingPeriod { .. }
+                            | HeaderInconsistency::UnsteadySampling
+                    ),
+                    "{}: {:?}",
+                    descriptor,
+                    inconsistency
+                );
+            }
+
+            assert_eq!(
+                parsed.epochs_iter().collect::<Vec<_>>(),
+                sp3.epochs_iter().collect::<Vec<_>>(),
+                "{}",
+                descriptor
+            );
+        }
+    }
+
+    #[cfg(feature = "processing")]
+    #[test]
+    fn time_systems_timeshift() {
+        let sp3 = synthetic(TimeSystem::GPS, "2020-06-25T00:00:00");
+
+        for timescale in [
+            TimeScale::UTC,
+            TimeScale::GST,
+            TimeScale::BDT,
+            TimeScale::QZSST,
+            TimeScale::TAI,
+        ] {
+            let transposed = sp3.timeshift(timescale);
+
+            assert_eq!(transposed.header.timescale, timescale);
+            assert_eq!(
+                transposed.header.time_system,
+                TimeSystem::from_timescale(timescale).unwrap()
+            );
+
+            assert!(
+                transposed.validate().is_empty(),
+                "{}: {:?}",
+                timescale,
+                transposed.validate()
+            );
+
+            let parsed = round_trip(&transposed);
+            assert_eq!(parsed.header.time_system, transposed.header.time_system);
+            assert_eq!(parsed.header.week, transposed.header.week);
+            assert_eq!(parsed.header.week_nanos, transposed.header.week_nanos);
+            assert_eq!(parsed.header.mjd, transposed.header.mjd);
+
+            // back and forth: same instants
+            let back = parsed.timeshift(TimeScale::GPST);
+            assert_eq!(back.header.week, sp3.header.week);
+            assert_eq!(back.header.week_nanos, sp3.header.week_nanos);
+            assert_eq!(
+                back.epochs_iter().collect::<Vec<_>>(),
+                sp3.epochs_iter().collect::<Vec<_>>()
+            );
+        }
+
+        // GLONASS time descriptor is preserved by UTC conversions
+        let glo = synthetic(TimeSystem::GLO, "2020-06-25T00:00:00");
+        let utc = glo.timeshift(TimeScale::UTC);
+        assert_eq!(utc.header.time_system, TimeSystem::GLO);
+
+        let gpst = glo.timeshift(TimeScale::GPST);
+        assert_eq!(gpst.header.time_system, TimeSystem::GPS);
+        assert_eq!(gpst.first_epoch(), glo.first_epoch());
+    }
+}
@@ -0,0 +1,339 @@
+//! Header consistency verification and header rebuild
+use crate::{
+    header::time_system::{clock_interval, from_week_counter, mjd_days, week_counter},
+    prelude::{Constellation, Duration, Epoch, Header, SP3, SV},
+};
+
+use itertools::Itertools;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Tolerance on the MJD day fraction (about 1ms)
+const MJD_FRACTION_TOLERANCE: f64 = 1.0E-8;
+
+/// [HeaderInconsistency] between the [SP3] header and its content
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HeaderInconsistency {
+    /// Declared number of epochs does not match the record
+    NumberOfEpochs {
+        /// Value declared in header
+        header: u64,
+        /// Number of epochs found in record
+        record: u64,
+    },
+
+    /// Declared sampling period does not match the record
+    SamplingPeriod {
+        /// Value declared in header
+        header: Duration,
+        /// Smallest sampling interval found in record
+        record: Duration,
+    },
+
+    /// Epochs are not evenly spaced
+    UnsteadySampling,
+
+    /// Declared week counter does not match the first epoch of the record
+    WeekCounter {
+        /// (week, nanoseconds of week) declared in header
+        header: (u32, u64),
+        /// (week, nanoseconds of week) of the first epoch of the record
+        record: (u32, u64),
+    },
+
+    /// Declared MJD does not match the first epoch of the record
+    ModifiedJulianDate {
+        /// MJD (including day fraction) declared in header
+        header: f64,
+        /// MJD (including day fraction) of the first epoch of the record
+        record: f64,
+    },
+
+    /// Declared [Constellation] does not describe the record
+    ConstellationType {
+        /// Value declared in header
+        header: Constellation,
+        /// [Constellation] describing the record
+        record: Constellation,
+    },
+
+    /// [SV] declared in header but absent from record
+    MissingSatellite(SV),
+
+    /// [SV] found in record but not declared in header
+    UndeclaredSatellite(SV),
+
+    /// [SV] found in record does not match the declared [Constellation]
+    Constellation(SV),
+}
+
+impl Header {
+    /// Returns the [Epoch] this file starts at, as declared by the week counter.
+    pub fn start_epoch(&self) -> Epoch {
+        from_week_counter(self.week, self.week_nanos, self.timescale)
+    }
+
+    /// Updates both the week counter and the MJD to this start [Epoch].
+    pub(crate) fn set_start_epoch_mut(&mut self, t: Epoch) {
+        (self.week, self.week_nanos) = week_counter(t, self.timescale);
+
+        let days = mjd_days(t, self.timescale);
+
+        self.mjd = days.floor() as u32;
+        self.mjd_fraction = days.fract();
+
+        if self.mjd_fraction < MJD_FRACTION_TOLERANCE {
+            self.mjd_fraction = 0.0;
+        } else if 1.0 - self.mjd_fraction < MJD_FRACTION_TOLERANCE {
+            self.mjd += 1;
+            self.mjd_fraction = 0.0;
+        }
+    }
+}
+
+impl SP3 {
+    /// Smallest interval between two [Epoch]s of this record
+    pub(crate) fn record_sampling_period(&self) -> Option<Duration> {
+        let epochs = self.epochs_iter().collect::<Vec<_>>();
+        epochs.windows(2).map(|w| clock_interval(w[0], w[1])).min()
+    }
+
+    /// Nominal sampling period: as declared in the header,
+    /// or the smallest interval of the record when not declared.
+    pub(crate) fn nominal_sampling_period(&self) -> Duration {
+        if self.header.sampling_period > Duration::ZERO {
+            self.header.sampling_period
+        } else {
+            self.record_sampling_period().unwrap_or_default()
+        }
+    }
+
+    /// [SV]s actually found in this record, sorted
+    fn record_satellites(&self) -> Vec<SV> {
+        self.data.keys().map(|k| k.sv).unique().sorted().collect()
+    }
+
+    /// [Constellation] describing the [SV]s of this record
+    fn record_constellation(&self) -> Option<Constellation> {
+        let mut constellations = self.data.keys().map(|k| k.sv.constellation).unique();
+
+        let first = constellations.next()?;
+
+        if constellations.next().is_some() {
+            Some(Constellation::Mixed)
+        } else {
+            Some(first)
+        }
+    }
+
+    /// Verifies this [SP3] [Header] against the record, and reports every
+    /// [HeaderInconsistency]. An empty list means this [SP3] is self consistent.
+    /// Use [Self::rebuild_header_mut] to fix all derived header fields.
+    /// ```
+    /// use sp3::prelude::*;
+    ///
+    /// let sp3 = SP3::from_gzip_file("data/SP3/C/GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz")
+    ///     .unwrap();
+    ///
+    /// for inconsistency in sp3.validate() {
+    ///     println!("header inconsistency: {:?}", inconsistency);
+    /// }
+    ///
+    /// // manual modifications
+    /// let mut sp3 = sp3.clone();
+    /// sp3.data.retain(|k, _| k.sv.constellation == Constellation::GPS);
+    ///
+    /// sp3.rebuild_header_mut();
+    /// assert!(sp3.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<HeaderInconsistency> {
+        let mut inconsistencies = Vec::new();
+
+        let record_epochs = self.total_epochs() as u64;
+
+        if self.header.num_epochs != record_epochs {
+            inconsistencies.push(HeaderInconsistency::NumberOfEpochs {
+                header: self.header.num_epochs,
+                record: record_epochs,
+            });
+        }
+
+        if let Some(record) = self.record_sampling_period() {
+            if record != self.header.sampling_period {
+                inconsistencies.push(HeaderInconsistency::SamplingPeriod {
+                    header: self.header.sampling_period,
+                    record,
+                });
+            }
+        }
+
+        if !self.has_steady_sampling() {
+            inconsistencies.push(HeaderInconsistency::UnsteadySampling);
+        }
+
+        if let Some(first) = self.first_epoch() {
+            let mut expected = self.header.clone();
+            expected.set_start_epoch_mut(first);
+
+            if (expected.week, expected.week_nanos) != (self.header.week, self.header.week_nanos) {
+                inconsistencies.push(HeaderInconsistency::WeekCounter {
+                    header: (self.header.week, self.header.week_nanos),
+                    record: (expected.week, expected.week_nanos),
+                });
+            }
+
+            let header_mjd = self.header.mjd as f64 + self.header.mjd_fraction;
+            let record_mjd = expected.mjd as f64 + expected.mjd_fraction;
+
+            if (header_mjd - record_mjd).abs() > MJD_FRACTION_TOLERANCE {
+                inconsistencies.push(HeaderInconsistency::ModifiedJulianDate {
+                    header: header_mjd,
+                    record: record_mjd,
+                });
+            }
+        }
+
+        if let Some(record) = self.record_constellation() {
+            if record != self.header.constellation {
+                inconsistencies.push(HeaderInconsistency::ConstellationType {
+                    header: self.header.constellation,
+                    record,
+                });
+            }
+        }
+
+        let satellites = self.record_satellites();
+
+        for sv in self.header.satellites.iter() {
+            if !satellites.contains(sv) {
+                inconsistencies.push(HeaderInconsistency::MissingSatellite(*sv));
+            }
+        }
+
+        for sv in satellites.iter() {
+            if !self.header.satellites.contains(sv) {
+                inconsistencies.push(HeaderInconsistency::UndeclaredSatellite(*sv));
+            }
+
+            if self.header.constellation != Constellation::Mixed
+                && sv.constellation != self.header.constellation
+            {
+                inconsistencies.push(HeaderInconsistency::Constellation(*sv));
+            }
+        }
+
+        inconsistencies
+    }
+
+    /// Recomputes all derived [Header] fields from the record: number of epochs,
+    /// sampling period, [SV] list, [Constellation], week counter and MJD.
+    /// Declared [SV]s keep their order, [SV]s missing from the header are appended.
+    /// This is automatically called by all processing operations that modify the record.
+    /// The sampling period and start time are preserved when the record is empty.
+    pub fn rebuild_header_mut(&mut self) {
+        self.header.num_epochs = self.total_epochs() as u64;
+
+        // preserve the header order, new satellites are grouped by constellation
+        let record = self.record_satellites();
+
+        self.header.satellites.retain(|sv| record.contains(sv));
+
+        let mut added = record
+            .into_iter()
+            .filter(|sv| !self.header.satellites.contains(sv))
+            .collect::<Vec<_>>();
+
+        added.sort_by_key(|sv| (sv.constellation, sv.prn));
+        self.header.satellites.extend(added);
+
+        if let Some(constellation) = self.record_constellation() {
+            self.header.constellation = constellation;
+        }
+
+        if let Some(sampling_period) = self.record_sampling_period() {
+            self.header.sampling_period = sampling_period;
+        }
+
+        if let Some(first) = self.first_epoch() {
+            self.header.set_start_epoch_mut(first);
+        }
+    }
+
+    /// Returns a copy of this [SP3] with all derived [Header] fields recomputed.
+    /// See [Self::rebuild_header_mut].
+    pub fn rebuild_header(&self) -> Self {
+        let mut s = self.clone();
+        s.rebuild_header_mut();
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HeaderInconsistency;
+    use crate::prelude::{Constellation, Duration, Epoch, SP3Entry, SP3Key, TimeScale, SP3, SV};
+    use std::str::FromStr;
+
+    #[test]
+    fn header_rebuild() {
+        let t0 = Epoch::from_str("2019-10-27T00:15:00 GPST").unwrap();
+        let dt = Duration::from_seconds(300.0);
+
+        let g01 = SV::from_str("G01").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+
+        let mut sp3 = SP3::default();
+        sp3.header.timescale = TimeScale::GPST;
+        sp3.header.constellation = Constellation::GPS;
+        sp3.header.satellites = vec![g01];
+
+        for i in 0..10 {
+            let epoch = t0 + dt * i as f64;
+            let entry = SP3Entry::from_position_km((1.0, 2.0, 3.0));
+
+            sp3.data.insert(SP3Key { sv: g01, epoch }, entry);
+            sp3.data.insert(SP3Key { sv: e01, epoch }, entry);
+        }
+
+        let inconsistencies = sp3.validate();
+
+        assert!(
+            inconsistencies.contains(&HeaderInconsistency::NumberOfEpochs {
+                header: 0,
+                record: 10,
+            })
+        );
+        assert!(inconsistencies.contains(&HeaderInconsistency::UndeclaredSatellite(e01)));
+        assert!(
+            inconsistencies.contains(&HeaderInconsistency::ConstellationType {
+                header: Constellation::GPS,
+                record: Constellation::Mixed,
+            })
+        );
+
+        sp3.rebuild_header_mut();
+        assert!(sp3.validate().is_empty());
+
+        assert_eq!(sp3.header.num_epochs, 10);
+        assert_eq!(sp3.header.satellites, vec![g01, e01]);
+        assert_eq!(sp3.header.constellation, Constellation::Mixed);
+        assert_eq!(sp3.header.sampling_period, dt);
+        assert_eq!(sp3.header.week, 2077);
+        assert_eq!(sp3.header.week_nanos, 900_000_000_000);
+        assert_eq!(sp3.header.mjd, 58783);
+        assert!((sp3.header.mjd_fraction - 15.0 / 1440.0).abs() < 1.0E-9);
+
+        // manual edit
+        sp3.data.retain(|k, _| k.sv == g01 && k.epoch > t0);
+        assert_eq!(sp3.validate().len(), 5);
+
+        sp3.header.satellites = vec![e01, g01];
+        sp3.rebuild_header_mut();
+        assert!(sp3.validate().is_empty());
+        assert_eq!(sp3.header.satellites, vec![g01]);
+        assert_eq!(sp3.header.constellation, Constellation::GPS);
+        assert_eq!(sp3.header.week_nanos, 1_200_000_000_000);
+    }
+}
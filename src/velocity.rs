@@ -1,6 +1,7 @@
 //! Velocity entry parsing
 use crate::{
     errors::ParsingError,
+    position::std_dev_exponents,
     prelude::{Constellation, Version, SV},
 };
 
@@ -14,6 +15,7 @@ pub struct VelocityEntry {
     sv: SV,
     velocity: (f64, f64, f64),
     clock: Option<f64>,
+    std_dev_exponents: Option<(i32, i32, i32)>,
 }
 
 impl VelocityEntry {
@@ -45,7 +47,9 @@ impl VelocityEntry {
             .or(Err(ParsingError::Coordinates(line[32..46].to_string())))?
             * 1.0E-4;
 
-        if !line[45..52].trim().eq("999999.") {
+        let clock_blank = line.get(46..60).is_none_or(|clock| clock.trim().is_empty());
+
+        if !clock_blank && !line[45..52].trim().eq("999999.") {
             /*
              * Clock data present
              */
@@ -59,6 +63,7 @@ impl VelocityEntry {
             sv,
             velocity: (x_km, y_km, z_km),
             clock,
+            std_dev_exponents: std_dev_exponents(line),
         })
     }
 }
@@ -67,4 +72,9 @@ impl VelocityEntry {
     pub fn to_parts(&self) -> (SV, (f64, f64, f64), Option<f64>) {
         (self.sv, self.velocity, self.clock)
     }
+
+    /// Standard deviation exponents, in the header base, of 10⁻⁴ mm/s
+    pub fn std_dev_exponents(&self) -> Option<(i32, i32, i32)> {
+        self.std_dev_exponents
+    }
 }